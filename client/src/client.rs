@@ -1,11 +1,15 @@
 // STD Dependencies -----------------------------------------------------------
 use std::io::Read;
-use std::f32::consts;
 
 
 // External Dependencies ------------------------------------------------------
+use rand;
 use hyper;
 use clock_ticks;
+use netsync::{
+    NetworkState, SnapshotStats, PredictionStats,
+    NetworkConditioner, NetworkConditions
+};
 use piston::input::*;
 use graphics::Transformed;
 
@@ -18,19 +22,31 @@ use hexahydrate;
 use ::Timer;
 use ::level::Level;
 use ::camera::Camera;
-use ::entity::{Entity, Registry};
-use ::effect::{Effect, LaserBeam, LaserBeamHit, ScreenFlash, ParticleSystem};
-use ::renderer::{Circle, CircleArc, Renderer, MAX_PARTICLES};
+use ::entity::{Entity, PlayerRenderer, Registry, ClientProjectileState};
+use ::effect::{self, Effect, LaserBeam, ParticleSystem};
+use ::renderer::{Circle, Renderer, MAX_PARTICLES};
 
+use shared::UPDATES_PER_SECOND;
 use shared::action::Action;
-use shared::color::ColorName;
-use shared::level::{Level as SharedLevel, LevelCollision};
+use shared::clock::ClockSync;
+use shared::logging;
+use shared::stats::{NetworkStats, TrafficKind};
+use shared::weapon::Weapon;
+use shared::color::{Color, ColorName};
+use shared::level::Level as SharedLevel;
 use shared::entity::{
-    PlayerInput, PlayerData,
-    PLAYER_RADIUS, PLAYER_BEAM_FIRE_INTERVAL, PLAYER_MAX_HP
+    PlayerInput, PlayerData, PickupData,
+    ProjectileInput, ProjectileData,
+    PLAYER_RADIUS, PLAYER_MAX_HP, PLAYER_ERROR_SNAP_DISTANCE,
+    PICKUP_RADIUS, PROJECTILE_RADIUS, PROJECTILE_STATE_BUFFER
 };
 
 
+// Statics --------------------------------------------------------------------
+const NETWORK_STATS_WIDTH: f32 = 200.0;
+const NETWORK_BYTES_PER_PIXEL: f32 = 50.0;
+
+
 // Client Implementation ------------------------------------------------------
 pub struct Client {
 
@@ -43,21 +59,42 @@ pub struct Client {
     // Rendering
     camera: Camera,
     player: LocalPlayerData,
+    player_renderer: PlayerRenderer,
     effects: Vec<Box<Effect>>,
     screen_effects: Vec<Box<Effect>>,
     particle_system: ParticleSystem,
+    pickup_circle: Circle,
+    projectile_circle: Circle,
     debug_level: u8,
+    interpolation_delay: u64,
 
     // Network
-    tick: u8,
+    tick: u16,
+    clock: ClockSync,
+    loaded: bool,
     ready: bool,
     addr: String,
-    actions: Vec<Action>
+    actions: Vec<Action>,
+    predicted_projectiles: Vec<(u16, ClientProjectileState)>,
+    conditioner: NetworkConditioner<(bool, Vec<u8>)>,
+    network_stats: NetworkStats
 }
 
 impl Client {
 
-    pub fn new(_: &mut Renderer, addr: &str, width: u32, height: u32) -> Client {
+    pub fn new(
+        _: &mut Renderer,
+        addr: &str,
+        interpolation_delay: u64,
+        conditions: NetworkConditions,
+        width: u32,
+        height: u32
+
+    ) -> Client {
+
+        if !conditions.is_ideal() {
+            warn!("Simulating network conditions: {:?}", conditions);
+        }
 
         Client {
 
@@ -70,16 +107,25 @@ impl Client {
             // Rendering
             camera: Camera::new(width, height),
             player: LocalPlayerData::new(),
+            player_renderer: PlayerRenderer::new(),
             effects: Vec::new(),
             screen_effects: Vec::new(),
             particle_system: ParticleSystem::new(MAX_PARTICLES),
+            pickup_circle: Circle::new(12, 0.0, 0.0, PICKUP_RADIUS),
+            projectile_circle: Circle::new(8, 0.0, 0.0, PROJECTILE_RADIUS),
             debug_level: 0,
+            interpolation_delay: interpolation_delay,
 
             // Network
             tick: 0,
+            clock: ClockSync::new(UPDATES_PER_SECOND),
+            loaded: false,
             ready: false,
             addr: addr.to_string(),
-            actions: Vec::new()
+            actions: Vec::new(),
+            predicted_projectiles: Vec::new(),
+            conditioner: NetworkConditioner::new(conditions, rand::random()),
+            network_stats: NetworkStats::new(0)
 
         }
     }
@@ -98,25 +144,48 @@ impl Client {
         if let Some(Button::Mouse(button)) = e.press_args() {
             if button == MouseButton::Left {
 
-                if t >= self.player.last_beam_fire + PLAYER_BEAM_FIRE_INTERVAL {
-
-                    // TODO play laser SFX
-                    self.actions.push(Action::FiredLaserBeam(self.tick, self.player.data.r));
-
-                    if self.debug_level == 1 {
-                        self.effects.push(Box::new(LaserBeam::from_point(
-                            &mut self.particle_system,
-                            ColorName::Grey,
+                let weapon = self.player.weapon;
+                let stats = weapon.stats();
+                if t >= self.player.last_weapon_fire + stats.fire_interval {
+
+                    // TODO play weapon SFX
+                    // Remote players are displayed with the interpolation
+                    // delay on top of the network latency
+                    self.actions.push(Action::FiredWeapon(
+                        weapon.to_u8(),
+                        self.tick,
+                        self.clock.view_tick(t.saturating_sub(self.interpolation_delay)),
+                        self.player.data.r
+                    ));
+
+                    // Predict projectiles until the server confirms them
+                    if stats.projectile_speed.is_some() {
+                        let mut state = ClientProjectileState::new(PROJECTILE_STATE_BUFFER);
+                        state.set(ProjectileData::new(
+                            weapon,
+                            self.player.color,
                             self.player.data.x,
                             self.player.data.y,
-                            self.player.data.r,
-                            PLAYER_RADIUS + 0.5,
-                            100.0,
-                            None
-                        )));
+                            self.player.data.r
+                        ));
+                        self.predicted_projectiles.push((self.tick, state));
+
+                    } else if self.debug_level == 1 {
+                        for r in weapon.pellet_angles(self.player.data.r) {
+                            self.effects.push(Box::new(LaserBeam::from_point(
+                                &mut self.particle_system,
+                                ColorName::Grey,
+                                self.player.data.x,
+                                self.player.data.y,
+                                r,
+                                PLAYER_RADIUS + 0.5,
+                                stats.range,
+                                None
+                            )));
+                        }
                     }
 
-                    self.player.last_beam_fire = t;
+                    self.player.last_weapon_fire = t;
 
                 }
 
@@ -143,6 +212,14 @@ impl Client {
                 renderer.set_wireframe(!enabled);
             }
 
+            // Weapon selection
+            match key {
+                Key::D1 => self.player.weapon = Weapon::LaserBeam,
+                Key::D2 => self.player.weapon = Weapon::Rocket,
+                Key::D3 => self.player.weapon = Weapon::Shotgun,
+                _ => {}
+            }
+
             self.buttons |= match key {
                 Key::W => 1,
                 Key::A => 8,
@@ -177,8 +254,13 @@ impl Client {
         entity_client: &mut hexahydrate::Client<Entity, ConnectionID, Registry>,
         client: &mut cobalt::ClientStream,
     ) {
+        self.loaded = false;
         self.ready = false;
+        self.clock.reset();
         self.player.data.hp = 0;
+        self.predicted_projectiles.clear();
+        self.conditioner.clear();
+        self.network_stats = NetworkStats::new(clock_ticks::precise_time_ms());
         entity_client.reset();
         client.close().ok();
     }
@@ -193,27 +275,34 @@ impl Client {
     ) {
 
         // Receive messages
+        let t = clock_ticks::precise_time_ms();
         let mut actions = Vec::new();
         while let Ok(event) = client.receive() {
             match event {
                 cobalt::ClientEvent::Connection => {
-                    println!("[Client] Now connected to server.");
+                    info!("Now connected to server.");
 
                     if let Ok(toml) = download_map(self.addr.as_str()) {
-                        println!("[Client] Map downloaded");
+                        info!("Map downloaded");
                         level.load(SharedLevel::from_toml_string(toml.as_str()));
-                        self.actions.push(Action::JoinGame);
-                        self.ready = true;
+                        self.loaded = true;
 
                     } else {
-                        println!("[Client] Map download failed!");
+                        error!("Map download failed!");
                     }
                 },
                 cobalt::ClientEvent::Message(packet) => {
+                    self.network_stats.received.record_server_packet(&packet);
                     match entity_client.receive(packet) {
                         Err(hexahydrate::ClientError::InvalidPacketData(bytes)) => {
-                            if let Ok(action) = Action::from_bytes(&bytes) {
-                                actions.push(action);
+                            match Action::from_bytes(&bytes) {
+                                Ok(Action::ClockResponse(client_time, server_time, tick, elapsed)) => {
+                                    if self.clock.receive(t, client_time, server_time, tick, elapsed) {
+                                        self.network_stats.record_rtt(t.saturating_sub(client_time) as u32);
+                                    }
+                                },
+                                Ok(action) => actions.push(action),
+                                Err(_) => {}
                             }
                         },
                         _ => {
@@ -222,20 +311,20 @@ impl Client {
                     }
                 },
                 cobalt::ClientEvent::ConnectionLost => {
-                    println!("[Client] Connection to server was lost!");
+                    warn!("Connection to server was lost!");
                     self.reset(entity_client, client);
                     client.connect(self.addr.as_str()).ok();
                 },
                 cobalt::ClientEvent::ConnectionClosed(_) => {
-                    println!("[Client] Connection to server was closed.");
+                    info!("Connection to server was closed.");
                     self.reset(entity_client, client);
                     client.connect(self.addr.as_str()).ok();
                 },
                 cobalt::ClientEvent::ConnectionFailed => {
-                    println!("[Client] Failed to connect to server!");
+                    warn!("Failed to connect to server!");
                     self.reset(entity_client, client);
                     timer.schedule(|client, _, network, _| {
-                        println!("[Client] Trying to reconnect...");
+                        info!("Trying to reconnect...");
                         network.connect(client.addr.as_str()).ok();
 
                     }, 1000);
@@ -244,18 +333,62 @@ impl Client {
             }
         }
 
+        if self.network_stats.update(t) {
+            self.network_stats.set_packet_loss(self.clock.packet_loss());
+        }
+
+        // Keep the clock synchronized, requests are not sent reliably since
+        // resends would distort the measured round trip time
+        if self.loaded {
+            if let Some(request) = self.clock.request(t) {
+                self.conditioner.send(t, (false, request.to_bytes()), false);
+            }
+        }
+
+        // Join once inputs can be aligned with the server tick
+        if self.loaded && !self.ready && self.clock.is_synced() {
+            info!(
+                "Clock synchronized (RTT {:.1}ms, offset {:.1}ms)",
+                self.clock.rtt(),
+                self.clock.offset()
+            );
+            self.actions.push(Action::JoinGame);
+            self.ready = true;
+        }
+
         // Prevent any updates if not ready to play
         if self.ready {
-            self.update_connected(timer, entity_client, client, level, dt, actions);
+
+            if let Some(tick) = self.clock.align(self.tick, t) {
+                debug!("Tick realigned from {} to {}", self.tick, tick);
+                self.tick = tick;
+            }
+
+            let steps = self.clock.steps(self.tick, t);
+            self.update_connected(timer, entity_client, client, level, dt, steps, actions);
+
         }
 
         // Send actions to server
         for action in self.actions.drain(0..) {
-            client.send(cobalt::MessageKind::Reliable, action.to_bytes()).ok();
+            self.conditioner.send(t, (true, action.to_bytes()), true);
+        }
+
+        // Packets are held back by the conditioner while simulating bad
+        // network conditions and passed through right away otherwise
+        let t = clock_ticks::precise_time_ms();
+        while let Some((reliable, packet)) = self.conditioner.receive(t) {
+            self.network_stats.sent.record_client_packet(&packet);
+            client.send(if reliable {
+                cobalt::MessageKind::Reliable
+
+            } else {
+                cobalt::MessageKind::Instant
+
+            }, packet).ok();
         }
 
         client.flush().ok();
-        self.tick = self.tick.wrapping_add(1);
 
     }
 
@@ -263,97 +396,120 @@ impl Client {
         &mut self,
         _: &mut Timer,
         entity_client: &mut hexahydrate::Client<Entity, ConnectionID, Registry>,
-        client: &mut cobalt::ClientStream,
+        _: &mut cobalt::ClientStream,
         level: &Level,
         dt: f32,
+        steps: usize,
         mut actions: Vec<Action>
     ) {
 
-        // Ignore inputs when player is currently dead
-        let input = if self.player.data.hp == 0 {
-            PlayerInput::new(self.tick, 0, self.input_angle, dt)
-
-        } else {
-            PlayerInput::new(self.tick, self.buttons, self.input_angle, dt)
-        };
+        // Run locally predicted entities once per local tick, time dilation
+        // might result in zero or multiple ticks per update
+        for _ in 0..steps {
+            self.update_tick(entity_client, level, dt);
+            self.tick = self.tick.wrapping_add(1);
+        }
+        logging::set_tick(self.tick);
 
-        // Update entities
+        // Update remote entities
         let t = clock_ticks::precise_time_ms();
+        let delay = self.interpolation_delay;
         entity_client.update_with(|_, entity| {
-            if entity.is_local() {
-                if entity.is_new() {
-                    self.player.colors = entity.colors();
+            if let Some(player) = entity.as_player_mut() {
+                if !player.is_local() {
+                    player.set_interpolation_delay(delay);
+                    player.update_remote(level, t);
                 }
-                entity.update_local(level, input.clone());
+            }
 
-            } else {
-                entity.update_remote(level, t);
+            if let Some(projectile) = entity.as_projectile_mut() {
+                if !projectile.is_local() {
+                    projectile.update_remote(level, dt);
+                }
             }
         });
 
         // Apply actions
         for action in actions.drain(0..) {
-            println!("[Client] Received action from server: {:?}", action);
-            match action {
-
-                Action::CreateLaserBeam(color, x, y, r, l) => {
-                    self.effects.push(Box::new(LaserBeam::from_point(
-                        &mut self.particle_system,
-                        ColorName::from_u8(color),
-                        x, y, r,
-                        0.0, l,
-                        level.collide_beam_wall(x, y, r, l + 1.0)
-                    )));
-                },
+            debug!("Received action from server: {:?}", action);
+            effect::apply_action(
+                action,
+                level,
+                &mut self.particle_system,
+                &mut self.effects,
+                &mut self.screen_effects,
+                self.player.color
+            );
+        }
 
-                Action::LaserBeamHit(hit_color, shooter_color, x, y) => {
-
-                    let hit_color = ColorName::from_u8(hit_color);
-                    self.effects.push(Box::new(LaserBeamHit::from_point(
-                        &mut self.particle_system,
-                        hit_color,
-                        x, y,
-                        1.0
-                    )));
-
-                    if self.player.color == hit_color {
-                        self.screen_effects.push(Box::new(ScreenFlash::new(
-                            ColorName::from_u8(shooter_color),
-                            500
-                        )));
-                    }
+        // Send client inputs to server
+        for packet in entity_client.send(512) {
+            self.conditioner.send(t, (false, packet), false);
+        }
 
-                },
+    }
+
+    fn update_tick(
+        &mut self,
+        entity_client: &mut hexahydrate::Client<Entity, ConnectionID, Registry>,
+        level: &Level,
+        dt: f32
+    ) {
 
-                Action::LaserBeamKill(hit_color, shooter_color, x, y) => {
+        // Ignore inputs when player is currently dead
+        let input = if self.player.data.hp == 0 {
+            PlayerInput::new(self.tick, 0, self.input_angle, dt)
+
+        } else {
+            PlayerInput::new(self.tick, self.buttons, self.input_angle, dt)
+        };
 
-                    let hit_color = ColorName::from_u8(hit_color);
+        // Update entities
+        entity_client.update_with(|_, entity| {
+            if let Some(player) = entity.as_player_mut() {
+                if player.is_local() {
+                    if player.is_new() {
+                        self.player.colors = player.colors();
+                    }
+                    player.update_local(level, input.clone());
+                }
+            }
 
-                    self.effects.push(Box::new(LaserBeamHit::from_point(
-                        &mut self.particle_system,
-                        hit_color,
-                        x, y,
-                        2.0
-                    )));
+            if let Some(projectile) = entity.as_projectile_mut() {
+                if projectile.is_local() {
 
-                    if self.player.color == hit_color {
-                        self.screen_effects.push(Box::new(ScreenFlash::new(
-                            ColorName::from_u8(shooter_color),
-                            2000
-                        )));
+                    // Continue from the local prediction once the server
+                    // side projectile arrives
+                    if projectile.is_new() {
+                        let fired_tick = projectile.fired_tick();
+                        let index = self.predicted_projectiles.iter().position(|&(tick, _)| tick == fired_tick);
+                        if let Some(index) = index {
+                            projectile.take_prediction(self.predicted_projectiles.remove(index).1);
+                        }
                     }
 
-                },
+                    projectile.update_local(level, ProjectileInput::new(self.tick, dt));
 
-                _ => {}
+                }
             }
-        }
 
-        // Send client inputs to server
-        for packet in entity_client.send(512) {
-            client.send(cobalt::MessageKind::Instant, packet).ok();
+        });
+
+        // Advance projectiles which have not yet been confirmed by the server
+        let tick = self.tick;
+        for &mut (_, ref mut state) in &mut self.predicted_projectiles {
+            state.input(ProjectileInput::new(tick, dt));
+            state.update_with(|state, _, input| {
+                ProjectileData::update(input.unwrap().dt, state, level);
+            });
         }
 
+        // Drop predictions which were never confirmed, e.g. due to the server
+        // rejecting the shot
+        self.predicted_projectiles.retain(|&(fired_tick, _)| {
+            (tick.wrapping_sub(fired_tick) as usize) < PROJECTILE_STATE_BUFFER
+        });
+
     }
 
     pub fn render(
@@ -374,26 +530,45 @@ impl Client {
 
         // Get player positions, colors and visibility
         let (t, u) = (renderer.t(), renderer.u());
-        let players = entity_client.map_entities::<(PlayerData, [[f32; 4]; 2], f32), _>(|_, entity| {
-
-            let p = entity.interpolate(u);
-            if entity.is_local() {
-                self.player.color = entity.color_name();
-                self.player.data = p.clone();
-                (p, entity.colors(), if entity.is_alive() { 1.0 } else { 0.0 })
+        let players = entity_client.map_entities::<Option<(PlayerData, [[f32; 4]; 2], f32)>, _>(|_, entity| {
+
+            if let Some(player) = entity.as_player_mut() {
+
+                let p = player.interpolate(t, u);
+                if player.is_local() {
+                    self.player.color = player.color_name();
+                    self.player.data = p.clone();
+                    Some((p, player.colors(), if player.is_alive() { 1.0 } else { 0.0 }))
+
+                } else {
+                    let visibility = player.update_visibility(
+                        level,
+                        &self.player.data,
+                        &p,
+                        t
+                    );
+                    Some((p, player.colors(), visibility))
+                }
 
             } else {
-                let visibility = entity.update_visibility(
-                    level,
-                    &self.player.data,
-                    &p,
-                    t
-                );
-                (p, entity.colors(), visibility)
+                None
             }
 
         });
 
+        let pickups = entity_client.map_entities::<Option<PickupData>, _>(|_, entity| {
+            entity.as_pickup().map(|pickup| pickup.data.clone())
+        });
+
+        let mut projectiles: Vec<ProjectileData> = entity_client.map_entities::<Option<ProjectileData>, _>(|_, entity| {
+            entity.as_projectile().map(|projectile| projectile.interpolate(u))
+
+        }).into_iter().filter_map(|p| p).collect();
+
+        for &(_, ref state) in &self.predicted_projectiles {
+            projectiles.push(state.interpolate(u));
+        }
+
         // Camera setup
         self.camera.center(self.player.data.x, self.player.data.y);
         self.camera.limit(level.bounds());
@@ -417,23 +592,41 @@ impl Client {
 
         {
 
-            // Players
+            // Pickups
             let context = self.camera.context();
-            for (p, mut colors, visibility) in players {
-                if visibility > 0.0 {
+            let s = 1.0 + ((t as f32 * 0.005).sin() * 0.15);
+            for pickup in pickups.into_iter().filter_map(|p| p).filter(|p| p.active) {
 
-                    colors[0][3] = visibility;
-                    colors[1][3] = visibility;
+                let color = Color::from_name(pickup.kind.color_name()).into_f32();
+                let q = context.trans(pickup.x as f64, pickup.y as f64).scale(s as f64, s as f64);
+                renderer.set_color([0.0, 0.0, 0.0, 0.5]);
+                self.pickup_circle.render(renderer, &q.scale(1.2, 1.2));
+
+                renderer.set_color(color);
+                self.pickup_circle.render(renderer, &q);
+
+            }
 
-                    let q = context.trans(p.x as f64, p.y as f64).rot_rad(p.r as f64);
-                    renderer.set_color([0.0, 0.0, 0.0, 0.5]);
-                    self.player.circle.render(renderer, &q.scale(1.1, 1.1));
+            // Projectiles
+            for projectile in projectiles.into_iter().filter(|p| !p.impacted) {
+
+                let color = Color::from_name(projectile.color).into_f32();
+                let q = context.trans(projectile.x as f64, projectile.y as f64);
+                renderer.set_color([0.0, 0.0, 0.0, 0.5]);
+                self.projectile_circle.render(renderer, &q.scale(1.3, 1.3));
+
+                renderer.set_color(color);
+                self.projectile_circle.render(renderer, &q);
+
+            }
 
-                    renderer.set_color(colors[0]);
-                    self.player.circle.render(renderer, &q);
+            // Players
+            for (p, mut colors, visibility) in players.into_iter().filter_map(|p| p) {
+                if visibility > 0.0 {
 
-                    renderer.set_color(colors[1]);
-                    self.player.cone.render(renderer, &q);
+                    colors[0][3] = visibility;
+                    colors[1][3] = visibility;
+                    self.player_renderer.render(renderer, &context, &p, colors);
 
                 }
             }
@@ -487,6 +680,24 @@ impl Client {
         // HUD
         self.render_hud(renderer);
 
+        if self.debug_level > 0 {
+            let stats = entity_client.map_entities::<Option<(SnapshotStats, [[f32; 4]; 2])>, _>(|_, entity| {
+                entity.as_player().and_then(|player| {
+                    player.snapshot_stats().map(|stats| (stats, player.colors()))
+                })
+            });
+            self.render_snapshot_stats(renderer, stats.into_iter().filter_map(|s| s).collect());
+
+            let prediction = entity_client.map_entities::<Option<PredictionStats>, _>(|_, entity| {
+                entity.as_player().and_then(|player| player.prediction_stats())
+            });
+            if let Some(stats) = prediction.into_iter().filter_map(|s| s).next() {
+                self.render_prediction_stats(renderer, stats);
+            }
+
+            self.render_network_stats(renderer);
+        }
+
     }
 
     pub fn render_hud(&mut self, renderer: &mut Renderer) {
@@ -515,21 +726,112 @@ impl Client {
 
     }
 
+    fn render_snapshot_stats(&mut self, renderer: &mut Renderer, stats: Vec<(SnapshotStats, [[f32; 4]; 2])>) {
+
+        // One bar per remote player showing how far its jitter buffer is
+        // ahead of the render time, turning red while extrapolating
+        let context = renderer.context().clone();
+        let delay = self.interpolation_delay as f32;
+        for (i, (stats, colors)) in stats.into_iter().enumerate() {
+
+            let y = 20.0 + i as f32 * 12.0;
+            renderer.set_color(if stats.extrapolating {
+                [1.0, 0.0, 0.0, 1.0]
+
+            } else {
+                colors[0]
+            });
+            renderer.rectangle(&context, &[20.0, y, stats.buffered_time.max(0.0).min(delay * 2.0), 6.0]);
+
+            renderer.set_color(colors[1]);
+            renderer.rectangle(&context, &[20.0, y + 6.0, stats.jitter.min(delay * 2.0), 2.0]);
+
+            renderer.set_color([1.0, 1.0, 1.0, 1.0]);
+            renderer.line(&context, &[20.0 + delay, y, 20.0 + delay, y + 8.0], 1.0);
+
+        }
+
+    }
+
+    fn render_prediction_stats(&mut self, renderer: &mut Renderer, stats: PredictionStats) {
+
+        // Size of the last and the average local prediction error, scaled so
+        // the snapping distance spans the full bar
+        let context = renderer.context().clone();
+        let y = renderer.height() - 30.0;
+        let scale = 100.0 / PLAYER_ERROR_SNAP_DISTANCE;
+
+        renderer.set_color(self.player.colors[0]);
+        renderer.rectangle(&context, &[20.0, y, (stats.last_error * scale).min(100.0), 6.0]);
+
+        renderer.set_color(self.player.colors[1]);
+        renderer.rectangle(&context, &[20.0, y + 6.0, (stats.average_error * scale).min(100.0), 2.0]);
+
+        renderer.set_color([1.0, 1.0, 1.0, 1.0]);
+        renderer.line(&context, &[120.0, y, 120.0, y + 8.0], 1.0);
+
+    }
+
+    fn render_network_stats(&mut self, renderer: &mut Renderer) {
+
+        // Received and sent bytes per second stacked by traffic kind, one
+        // pixel per NETWORK_BYTES_PER_PIXEL bytes
+        let context = renderer.context().clone();
+        let x = renderer.width() - 20.0 - NETWORK_STATS_WIDTH;
+        for (i, stats) in [&self.network_stats.received, &self.network_stats.sent].iter().enumerate() {
+            let y = 20.0 + i as f32 * 12.0;
+            let mut offset = 0.0;
+            for kind in &TrafficKind::all() {
+                let w = stats.rates[*kind as usize] as f32 / NETWORK_BYTES_PER_PIXEL;
+                let w = w.min(NETWORK_STATS_WIDTH - offset);
+                renderer.set_color(traffic_color(*kind));
+                renderer.rectangle(&context, &[x + offset, y, w, 8.0]);
+                offset += w;
+            }
+        }
+
+        // Packet loss
+        renderer.set_color([1.0, 0.0, 0.0, 1.0]);
+        renderer.rectangle(&context, &[
+            x, 44.0,
+            (self.network_stats.packet_loss * 2.0).min(NETWORK_STATS_WIDTH), 4.0
+        ]);
+
+        // Round trip time history with the most recent sample on the right
+        renderer.set_color([1.0, 1.0, 1.0, 1.0]);
+        let history = self.network_stats.rtt_history();
+        let start = x + NETWORK_STATS_WIDTH - history.len() as f32 * 2.0;
+        for (i, rtt) in history.iter().enumerate() {
+            let h = (*rtt as f32 * 0.25).min(50.0);
+            let rx = start + i as f32 * 2.0;
+            renderer.line(&context, &[rx, 104.0 - h, rx, 104.0], 1.0);
+        }
+
+    }
+
 }
 
 
 // Helpers --------------------------------------------------------------------
+fn traffic_color(kind: TrafficKind) -> [f32; 4] {
+    match kind {
+        TrafficKind::Create => [0.2, 0.8, 0.2, 1.0],
+        TrafficKind::Update => [0.2, 0.5, 1.0, 1.0],
+        TrafficKind::Action => [1.0, 0.8, 0.2, 1.0],
+        TrafficKind::Input => [0.8, 0.3, 0.8, 1.0]
+    }
+}
+
 struct LocalPlayerData {
 
     // State
     data: PlayerData,
     color: ColorName,
-    last_beam_fire: u64,
+    weapon: Weapon,
+    last_weapon_fire: u64,
 
     // Rendering
-    colors: [[f32; 4]; 2],
-    circle: Circle,
-    cone: CircleArc
+    colors: [[f32; 4]; 2]
 }
 
 impl LocalPlayerData {
@@ -539,12 +841,11 @@ impl LocalPlayerData {
             // State
             data: PlayerData::default(),
             color: ColorName::Black,
-            last_beam_fire: 0,
+            weapon: Weapon::LaserBeam,
+            last_weapon_fire: 0,
 
             // Rendering
-            colors: [[0f32; 4]; 2],
-            circle: Circle::new(10, 0.0, 0.0, PLAYER_RADIUS),
-            cone: CircleArc::new(10, 0.0, 0.0, PLAYER_RADIUS, 0.0, consts::PI * 0.25),
+            colors: [[0f32; 4]; 2]
 
         }
     }
@@ -3,12 +3,14 @@ mod particle;
 mod laser_beam;
 mod laser_beam_hit;
 mod particle_system;
+mod pickup_collect;
 mod screen_flash;
 
 
 // Re-Exports -----------------------------------------------------------------
 pub use self::laser_beam::LaserBeam;
 pub use self::laser_beam_hit::LaserBeamHit;
+pub use self::pickup_collect::PickupCollect;
 pub use self::screen_flash::ScreenFlash;
 pub use self::particle_system::*;
 pub use self::particle::*;
@@ -17,6 +19,11 @@ pub use self::particle::*;
 // Internal Dependencies ------------------------------------------------------
 use ::renderer::Renderer;
 use ::camera::Camera;
+use ::level::Level;
+use shared::action::Action;
+use shared::color::ColorName;
+use shared::level::LevelCollision;
+use shared::entity::{PickupKind, PLAYER_RADIUS};
 
 
 // Effect Trait ---------------------------------------------------------------
@@ -25,3 +32,103 @@ pub trait Effect {
     fn render(&self, &mut Renderer, &Camera);
 }
 
+
+// Action Effects -------------------------------------------------------------
+
+/// Creates the world and screen effects for a action received from the
+/// server, screen effects are only triggered for the player of `local_color`.
+pub fn apply_action(
+    action: Action,
+    level: &Level,
+    particle_system: &mut ParticleSystem,
+    effects: &mut Vec<Box<Effect>>,
+    screen_effects: &mut Vec<Box<Effect>>,
+    local_color: ColorName
+) {
+
+    match action {
+
+        Action::CreateLaserBeam(color, x, y, r, l) => {
+            effects.push(Box::new(LaserBeam::from_point(
+                particle_system,
+                ColorName::from_u8(color),
+                x, y, r,
+                0.0, l,
+                level.collide_beam_wall(x, y, r, l + 1.0)
+            )));
+        },
+
+        Action::PlayerHit(hit_color, shooter_color, x, y) => {
+
+            let hit_color = ColorName::from_u8(hit_color);
+            effects.push(Box::new(LaserBeamHit::from_point(
+                particle_system,
+                hit_color,
+                x, y,
+                1.0
+            )));
+
+            if local_color == hit_color {
+                screen_effects.push(Box::new(ScreenFlash::new(
+                    ColorName::from_u8(shooter_color),
+                    500
+                )));
+            }
+
+        },
+
+        Action::PlayerKill(hit_color, shooter_color, x, y) => {
+
+            let hit_color = ColorName::from_u8(hit_color);
+
+            effects.push(Box::new(LaserBeamHit::from_point(
+                particle_system,
+                hit_color,
+                x, y,
+                2.0
+            )));
+
+            if local_color == hit_color {
+                screen_effects.push(Box::new(ScreenFlash::new(
+                    ColorName::from_u8(shooter_color),
+                    2000
+                )));
+            }
+
+        },
+
+        Action::ProjectileImpact(color, x, y, radius) => {
+            effects.push(Box::new(LaserBeamHit::from_point(
+                particle_system,
+                ColorName::from_u8(color),
+                x, y,
+                1.0 + radius / PLAYER_RADIUS * 0.25
+            )));
+        },
+
+        Action::PickupCollected(player_color, kind, x, y) => {
+
+            if let Some(kind) = PickupKind::from_u8(kind) {
+
+                effects.push(Box::new(PickupCollect::from_point(
+                    particle_system,
+                    kind.color_name(),
+                    x, y
+                )));
+
+                if local_color == ColorName::from_u8(player_color) {
+                    screen_effects.push(Box::new(ScreenFlash::new(
+                        kind.color_name(),
+                        300
+                    )));
+                }
+
+            }
+
+        },
+
+        _ => {}
+    }
+
+}
+
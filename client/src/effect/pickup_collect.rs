@@ -0,0 +1,76 @@
+// External Dependencies ------------------------------------------------------
+use clock_ticks;
+use graphics::Transformed;
+
+
+// Internal Dependencies ------------------------------------------------------
+use ::camera::Camera;
+use ::renderer::{Renderer, Circle};
+use ::effect::{Effect, ParticleSystem, particle};
+use shared::entity::PICKUP_RADIUS;
+use shared::color::{Color, ColorName};
+
+
+// Pickup Collection Effect ---------------------------------------------------
+pub struct PickupCollect {
+    x: f32,
+    y: f32,
+    color_light: [f32; 4],
+    start: u64,
+    duration: u64,
+    circle: Circle
+}
+
+impl PickupCollect {
+
+    pub fn new(color: ColorName, x: f32, y: f32) -> PickupCollect {
+        PickupCollect {
+            x: x,
+            y: y,
+            color_light: Color::from_name(color).into_f32(),
+            start: clock_ticks::precise_time_ms(),
+            duration: 400,
+            circle: Circle::new(16, 0.0, 0.0, PICKUP_RADIUS)
+        }
+    }
+
+    pub fn from_point(
+        ps: &mut ParticleSystem,
+        color: ColorName,
+        x: f32, y: f32
+
+    ) -> PickupCollect {
+        particle::circle(ps, color, x, y, PICKUP_RADIUS, 0.75, 24);
+        PickupCollect::new(color, x, y)
+    }
+
+}
+
+impl Effect for PickupCollect {
+
+    fn alive(&self, t: u64) -> bool {
+        t < self.start + self.duration
+    }
+
+    fn render(&self, renderer: &mut Renderer, camera: &Camera) {
+
+        let context = camera.context();
+        let exp = renderer.t() - self.start;
+        let u = ((1.0 / self.duration as f32) * exp as f32).min(1.0).max(0.0);
+        let q = context.trans(self.x as f64, self.y as f64).scale(
+            1.0 + u as f64 * 3.0,
+            1.0 + u as f64 * 3.0
+        );
+
+        renderer.set_color([
+            self.color_light[0],
+            self.color_light[1],
+            self.color_light[2],
+            (1.0 - u) * 0.5
+        ]);
+
+        self.circle.render(renderer, &q);
+
+    }
+
+}
@@ -1,15 +1,24 @@
+// STD Dependencies -----------------------------------------------------------
+use std::f32::consts;
+
+
 // External Dependencies ------------------------------------------------------
 use hexahydrate;
+use graphics::{Context, Transformed};
 use cobalt::ConnectionID;
-use hexahydrate::Entity as EntityTrait;
-use netsync::{ClientState, NetworkState};
+use netsync::{ClientState, NetworkState, SnapshotStats, PredictionStats};
 
 
 // Internal Dependencies ------------------------------------------------------
 use ::level::Level;
-use shared::color::ColorName;
+use ::renderer::{Circle, CircleArc, Renderer};
+use shared::color::{Color, ColorName};
 use shared::level::LevelVisibility;
-use shared::entity::{PlayerInput, PlayerData, PlayerEntity};
+use shared::entity::{
+    PlayerInput, PlayerData, PlayerEntity, PLAYER_RADIUS,
+    PickupEntity,
+    ProjectileInput, ProjectileData, ProjectileEntity
+};
 
 
 // Statics --------------------------------------------------------------------
@@ -18,19 +27,72 @@ const PLAYER_EXTRAPOLATE_DURATION: f32 = PLAYER_FADE_DURATION * 4.0;
 
 
 // Client Entity --------------------------------------------------------------
+type ClientPlayerEntity = PlayerEntity<ClientState<PlayerData, PlayerInput>>;
+type ClientProjectileEntity = ProjectileEntity<ClientProjectileState>;
+pub type ClientProjectileState = ClientState<ProjectileData, ProjectileInput>;
+
 pub trait Entity: hexahydrate::Entity<ConnectionID> {
+
+    fn as_player(&self) -> Option<&Player> {
+        None
+    }
+
+    fn as_player_mut(&mut self) -> Option<&mut Player> {
+        None
+    }
+
+    fn as_pickup(&self) -> Option<&PickupEntity> {
+        None
+    }
+
+    fn as_projectile(&self) -> Option<&Projectile> {
+        None
+    }
+
+    fn as_projectile_mut(&mut self) -> Option<&mut Projectile> {
+        None
+    }
+
+}
+
+pub trait Player {
     fn is_new(&mut self) -> bool;
     fn is_local(&self) -> bool;
     fn is_alive(&self) -> bool;
     fn color_name(&self) -> ColorName;
     fn colors(&self) -> [[f32; 4]; 2];
-    fn interpolate(&self, u: f32) -> PlayerData;
+    fn interpolate(&mut self, t: u64, u: f32) -> PlayerData;
+    fn snapshot_stats(&self) -> Option<SnapshotStats>;
+    fn prediction_stats(&self) -> Option<PredictionStats>;
+    fn set_interpolation_delay(&mut self, delay: u64);
     fn update_remote(&mut self, level: &Level, t: u64);
     fn update_local(&mut self, level: &Level, input: PlayerInput);
     fn update_visibility(&mut self, level: &Level, data: &PlayerData, p: &PlayerData, t: u64) -> f32;
 }
 
-impl Entity for PlayerEntity<ClientState<PlayerData, PlayerInput>> {
+pub trait Projectile {
+    fn is_new(&mut self) -> bool;
+    fn is_local(&self) -> bool;
+    fn fired_tick(&self) -> u16;
+    fn take_prediction(&mut self, state: ClientProjectileState);
+    fn interpolate(&self, u: f32) -> ProjectileData;
+    fn update_remote(&mut self, level: &Level, dt: f32);
+    fn update_local(&mut self, level: &Level, input: ProjectileInput);
+}
+
+impl Entity for ClientPlayerEntity {
+
+    fn as_player(&self) -> Option<&Player> {
+        Some(self)
+    }
+
+    fn as_player_mut(&mut self) -> Option<&mut Player> {
+        Some(self)
+    }
+
+}
+
+impl Player for ClientPlayerEntity {
 
     fn is_new(&mut self) -> bool {
         if self.is_new {
@@ -58,8 +120,39 @@ impl Entity for PlayerEntity<ClientState<PlayerData, PlayerInput>> {
         [self.color_light, self.color_dark]
     }
 
-    fn interpolate(&self, u: f32) -> PlayerData {
-        self.state.interpolate(u)
+    fn interpolate(&mut self, t: u64, u: f32) -> PlayerData {
+        if self.local {
+            self.state.interpolate(u)
+
+        // Remote players are rendered from their jitter buffer
+        } else if let Some(data) = self.snapshots.sample(t) {
+            data
+
+        } else {
+            self.state.interpolate(u)
+        }
+    }
+
+    fn snapshot_stats(&self) -> Option<SnapshotStats> {
+        if self.local {
+            None
+
+        } else {
+            Some(self.snapshots.stats())
+        }
+    }
+
+    fn prediction_stats(&self) -> Option<PredictionStats> {
+        if self.local {
+            Some(self.state.prediction_stats())
+
+        } else {
+            None
+        }
+    }
+
+    fn set_interpolation_delay(&mut self, delay: u64) {
+        self.snapshots.set_delay(delay);
     }
 
     fn update_remote(&mut self, level: &Level, t: u64) {
@@ -84,7 +177,11 @@ impl Entity for PlayerEntity<ClientState<PlayerData, PlayerInput>> {
     fn update_local(&mut self, level: &Level, input: PlayerInput) {
         self.state.input(input);
         self.state.update_with(|state, _, input| {
-            PlayerData::update(input.unwrap().dt, state, input.unwrap(), level);
+            // Dead players stay in place until they respawn, matching the
+            // server side simulation
+            if state.hp > 0 {
+                PlayerData::update(input.unwrap().dt, state, input.unwrap(), level);
+            }
         });
     }
 
@@ -125,16 +222,121 @@ impl Entity for PlayerEntity<ClientState<PlayerData, PlayerInput>> {
 }
 
 
-// Entity Registry ------------------------------------------------------------
-#[derive(Debug)]
-pub struct Registry;
-
-impl hexahydrate::EntityRegistry<Entity, ConnectionID> for Registry {
-    fn entity_from_bytes(&self, kind: u8, bytes: &[u8]) -> Option<Box<Entity>> {
-        match kind {
-            1 => PlayerEntity::from_bytes(bytes).map(|p| Box::new(p) as Box<Entity>),
-            _ => None
+impl Entity for PickupEntity {
+
+    fn as_pickup(&self) -> Option<&PickupEntity> {
+        Some(self)
+    }
+
+}
+
+impl Entity for ClientProjectileEntity {
+
+    fn as_projectile(&self) -> Option<&Projectile> {
+        Some(self)
+    }
+
+    fn as_projectile_mut(&mut self) -> Option<&mut Projectile> {
+        Some(self)
+    }
+
+}
+
+impl Projectile for ClientProjectileEntity {
+
+    fn is_new(&mut self) -> bool {
+        if self.is_new {
+            self.is_new = false;
+            true
+
+        } else {
+            false
         }
     }
+
+    fn is_local(&self) -> bool {
+        self.local
+    }
+
+    fn fired_tick(&self) -> u16 {
+        self.fired_tick
+    }
+
+    fn take_prediction(&mut self, state: ClientProjectileState) {
+        // Continue with the locally predicted state, it gets reconciled
+        // with the next confirmed server state
+        self.state = state;
+    }
+
+    fn interpolate(&self, u: f32) -> ProjectileData {
+        self.state.interpolate(u)
+    }
+
+    fn update_remote(&mut self, level: &Level, dt: f32) {
+        self.state.update_with(|state, _, _| {
+            ProjectileData::update(dt, state, level);
+        });
+    }
+
+    fn update_local(&mut self, level: &Level, input: ProjectileInput) {
+        self.state.input(input);
+        self.state.update_with(|state, _, input| {
+            ProjectileData::update(input.unwrap().dt, state, level);
+        });
+    }
+
+}
+
+
+// Entity Rendering -----------------------------------------------------------
+pub struct PlayerRenderer {
+    circle: Circle,
+    cone: CircleArc
+}
+
+impl PlayerRenderer {
+
+    pub fn new() -> PlayerRenderer {
+        PlayerRenderer {
+            circle: Circle::new(10, 0.0, 0.0, PLAYER_RADIUS),
+            cone: CircleArc::new(10, 0.0, 0.0, PLAYER_RADIUS, 0.0, consts::PI * 0.25)
+        }
+    }
+
+    /// Returns the light body and dark cone colors of a player.
+    pub fn colors(color: ColorName) -> [[f32; 4]; 2] {
+        let color = Color::from_name(color);
+        let dark = color.darken(0.5).into_f32();
+        [color.into_f32(), dark]
+    }
+
+    pub fn render(
+        &self,
+        renderer: &mut Renderer,
+        context: &Context,
+        p: &PlayerData,
+        colors: [[f32; 4]; 2]
+    ) {
+
+        let q = context.trans(p.x as f64, p.y as f64).rot_rad(p.r as f64);
+        renderer.set_color([0.0, 0.0, 0.0, 0.5]);
+        self.circle.render(renderer, &q.scale(1.1, 1.1));
+
+        renderer.set_color(colors[0]);
+        self.circle.render(renderer, &q);
+
+        renderer.set_color(colors[1]);
+        self.cone.render(renderer, &q);
+
+    }
+
 }
 
+
+// Entity Registry ------------------------------------------------------------
+entity_registry!(Registry, Entity, [
+    ClientPlayerEntity,
+    PickupEntity,
+    ClientProjectileEntity
+]);
+
@@ -11,7 +11,8 @@ use shared::level::{
     Level as SharedLevel,
     LevelCollision,
     LevelVisibility,
-    LEVEL_MAX_VISIBILITY_DISTANCE
+    LEVEL_MAX_VISIBILITY_DISTANCE,
+    triangulate_solid
 };
 
 
@@ -94,7 +95,7 @@ impl Level {
         }).collect();
 
         self.solids = level.solids.iter().map(|s| {
-            Polygon::new(&s)
+            Polygon::new(triangulate_solid(s))
 
         }).collect();
 
@@ -13,12 +13,16 @@ extern crate hyper;
 extern crate piston;
 extern crate renderer;
 
+#[macro_use]
+extern crate log;
 extern crate rand;
 extern crate clock_ticks;
 
 extern crate hexahydrate;
 extern crate netsync;
 extern crate cobalt;
+
+#[macro_use]
 extern crate shared;
 
 
@@ -28,6 +32,8 @@ mod client;
 mod effect;
 mod entity;
 mod level;
+mod query;
+mod replay;
 
 
 // Statics --------------------------------------------------------------------
@@ -35,8 +41,13 @@ const BASE_WIDTH: u32 = 800;
 const BASE_HEIGHT: u32 = 600;
 
 
+// STD Dependencies -----------------------------------------------------------
+use std::thread;
+
+
 // External Dependencies ------------------------------------------------------
 use cobalt::ConnectionID;
+use netsync::NetworkConditions;
 use piston::input::*;
 use piston::event_loop::*;
 
@@ -44,10 +55,12 @@ use piston::event_loop::*;
 // Internal Dependencies ------------------------------------------------------
 use ::entity::{Entity, Registry};
 use ::level::Level;
+use ::replay::Replay;
 use ::renderer::Renderer;
 use shared::UPDATES_PER_SECOND;
 use shared::Timer as SharedTimer;
 use shared::level::Level as SharedLevel;
+use shared::replay::ReplayError;
 
 
 // Re-Exports -----------------------------------------------------------------
@@ -64,7 +77,12 @@ pub type Timer = SharedTimer<
 
 
 // Client Runner --------------------------------------------------------------
-pub fn run(updates_per_second: u64, addr: &str) {
+pub fn run(
+    updates_per_second: u64,
+    addr: &str,
+    interpolation_delay: u64,
+    conditions: NetworkConditions
+) {
 
     // Create window and renderer
     let mut renderer = Renderer::new(
@@ -86,7 +104,14 @@ pub fn run(updates_per_second: u64, addr: &str) {
     let mut level = Level::new(SharedLevel::new());
 
     // Game Client
-    let mut client = Client::new(&mut renderer, addr, BASE_WIDTH, BASE_HEIGHT);
+    let mut client = Client::new(
+        &mut renderer,
+        addr,
+        interpolation_delay,
+        conditions,
+        BASE_WIDTH,
+        BASE_HEIGHT
+    );
     let mut entity_client = hexahydrate::Client::<Entity, ConnectionID, Registry>::new(
         Registry,
         (updates_per_second * 2) as usize
@@ -137,3 +162,72 @@ pub fn run(updates_per_second: u64, addr: &str) {
 
 }
 
+
+// Replay Runner --------------------------------------------------------------
+pub fn run_replay(path: &str) -> Result<(), ReplayError> {
+
+    // Load the recorded match, frames are streamed from disk during playback
+    let mut replay = try!(Replay::open(path, BASE_WIDTH, BASE_HEIGHT));
+    let updates_per_second = replay.updates_per_second();
+
+    // Create window and renderer
+    let mut renderer = Renderer::new(
+        "Shooter Replay",
+        BASE_WIDTH,
+        BASE_HEIGHT,
+        updates_per_second
+    );
+
+    // Events
+    let mut events = renderer.events();
+    events.set_ups(updates_per_second);
+    events.set_max_fps(60);
+
+    // Level
+    let level = Level::new(SharedLevel::from_toml_string(replay.level()));
+
+    // Main Loop
+    while let Some(e) = events.next(&mut renderer) {
+        match e {
+            Event::Input(ref event) => replay.input(&mut renderer, event),
+            Event::Update(_) => replay.update(&level),
+            Event::Render(args) => {
+                renderer.begin(args);
+                replay.render(&mut renderer, &level);
+                renderer.end();
+                renderer.finalize();
+            },
+            _ => { }
+        }
+    }
+
+    Ok(())
+
+}
+
+
+// Server Query ---------------------------------------------------------------
+pub fn run_query(addrs: &[&str]) {
+
+    // All servers are queried at once so unreachable ones delay the results
+    // by a single timeout at most
+    let handles: Vec<_> = addrs.iter().map(|addr| {
+        let addr = addr.to_string();
+        thread::spawn(move || {
+            let result = query::query_status(addr.as_str());
+            (addr, result)
+        })
+
+    }).collect();
+
+    for handle in handles {
+        if let Ok((addr, result)) = handle.join() {
+            match result {
+                Ok((status, ping)) => println!("{:<21} {:>5}ms  {}", addr, ping, status),
+                Err(err) => println!("{:<21} {:>5}    {}", addr, "-", err)
+            }
+        }
+    }
+
+}
+
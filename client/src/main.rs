@@ -4,6 +4,8 @@ extern crate server;
 extern crate client;
 extern crate shared;
 extern crate clap;
+#[macro_use]
+extern crate log;
 
 
 // External Dependencies ------------------------------------------------------
@@ -29,7 +31,7 @@ fn main() {
         .arg(Arg::with_name("local")
             .short("l")
             .long("local")
-            .help("Starts the game in local mode.")
+            .help("Starts the game in local mode, network conditions then apply to the packets of both the client and the server.")
         )
         .arg(Arg::with_name("addr")
             .short("a")
@@ -39,14 +41,78 @@ fn main() {
             .takes_value(true)
             .default_value("127.0.0.1:7156")
         )
+        .arg(Arg::with_name("interpolation-delay")
+            .long("interpolation-delay")
+            .value_name("MS")
+            .help("Specifies the time in milliseconds by which remote players are rendered in the past.")
+            .takes_value(true)
+            .default_value("75")
+        )
+        .arg(Arg::with_name("replay")
+            .short("r")
+            .long("replay")
+            .value_name("FILE")
+            .help("Plays back a recorded match instead of connecting to a server.")
+            .takes_value(true)
+        )
+        .arg(Arg::with_name("query")
+            .short("q")
+            .long("query")
+            .value_name("ADDR")
+            .help("Queries and prints the status of the servers at the given host:port addresses.")
+            .takes_value(true)
+            .multiple(true)
+        )
+        .arg(Arg::with_name("log")
+            .long("log")
+            .value_name("FILTER")
+            .help("Specifies the log levels, e.g. \"info,client=debug\". Defaults to the SHOOTER_LOG environment variable.")
+            .takes_value(true)
+        )
+        .args(&shared::cli::conditioner_args())
         .get_matches();
 
+    // Also covers the server when running in local mode
+    if let Err(err) = shared::logging::init(matches.value_of("log"), None) {
+        println!("Failed to initialize logging: {}", err);
+        return;
+    }
+
+    if let Some(addrs) = matches.values_of("query") {
+        client::run_query(&addrs.collect::<Vec<&str>>());
+        return;
+    }
+
+    if let Some(path) = matches.value_of("replay") {
+        if let Err(err) = client::run_replay(path) {
+            error!("Failed to play replay {}: {}", path, err);
+        }
+        return;
+    }
+
+    let conditions = shared::cli::conditions_from_matches(&matches);
+
+    // In local mode both directions are conditioned, so the simulated round
+    // trip time is the same as with a remote server using the same conditions
     if matches.occurrences_of("local") == 1 {
         #[cfg(feature = "loopback")]
-        server::run(matches.value_of("addr").unwrap().to_string());
+        server::run(
+            matches.value_of("addr").unwrap().to_string(),
+            server::DEFAULT_SERVER_NAME.to_string(),
+            0,
+            server::BotConfig::default(),
+            None,
+            conditions,
+            server::MissingInputPolicy::default()
+        );
     }
 
-    client::run(UPDATES_PER_SECOND, matches.value_of("addr").unwrap());
+    client::run(
+        UPDATES_PER_SECOND,
+        matches.value_of("addr").unwrap(),
+        matches.value_of("interpolation-delay").unwrap().parse().expect("Invalid interpolation delay."),
+        conditions
+    );
 
 }
 
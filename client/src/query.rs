@@ -0,0 +1,42 @@
+// STD Dependencies -----------------------------------------------------------
+use std::io::Read;
+use std::time::Duration;
+
+
+// External Dependencies ------------------------------------------------------
+use hyper;
+use clock_ticks;
+
+
+// Internal Dependencies ------------------------------------------------------
+use shared::status::{ServerStatus, StatusError, STATUS_PATH};
+
+
+// Statics --------------------------------------------------------------------
+const QUERY_TIMEOUT: u64 = 2000;
+
+
+// Status Query ---------------------------------------------------------------
+
+/// Requests the status of the server at `addr`, returns it together with the
+/// time in milliseconds the request took.
+pub fn query_status(addr: &str) -> Result<(ServerStatus, u64), StatusError> {
+
+    let mut client = hyper::Client::new();
+    client.set_read_timeout(Some(Duration::from_millis(QUERY_TIMEOUT)));
+    client.set_write_timeout(Some(Duration::from_millis(QUERY_TIMEOUT)));
+
+    let t = clock_ticks::precise_time_ms();
+    let mut res = try!(client.get(format!("http://{}{}", addr, STATUS_PATH).as_str())
+        .header(hyper::header::Connection::close())
+        .send()
+        .map_err(|err| StatusError::Request(err.to_string())));
+
+    let mut body = String::new();
+    try!(res.read_to_string(&mut body).map_err(|err| StatusError::Request(err.to_string())));
+
+    let ping = clock_ticks::precise_time_ms() - t;
+    ServerStatus::from_json(body.as_str()).map(|status| (status, ping))
+
+}
+
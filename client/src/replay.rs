@@ -0,0 +1,638 @@
+// STD Dependencies -----------------------------------------------------------
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::collections::VecDeque;
+
+
+// External Dependencies ------------------------------------------------------
+use netsync::NetworkProperty;
+use piston::input::*;
+
+
+// Internal Dependencies ------------------------------------------------------
+use ::level::Level;
+use ::camera::Camera;
+use ::effect::{self, Effect, ParticleSystem};
+use ::entity::PlayerRenderer;
+use ::renderer::{Renderer, StencilMode, MAX_PARTICLES};
+
+use shared::action::Action;
+use shared::color::ColorName;
+use shared::collision::aabb_intersect_circle;
+use shared::entity::PlayerData;
+use shared::level::LevelVisibility;
+use shared::replay::{
+    ReplayError, ReplayFrame, ReplayEvent, ReplayHeader, ReplayReader,
+    ReplayVisibility
+};
+
+
+// Statics --------------------------------------------------------------------
+const REPLAY_SEEK_SECONDS: f32 = 5.0;
+const REPLAY_MIN_SPEED: f32 = 0.25;
+const REPLAY_MAX_SPEED: f32 = 8.0;
+const REPLAY_CAMERA_SPEED: f32 = 6.0;
+const REPLAY_TIMELINE_HEIGHT: f32 = 20.0;
+
+
+// Replay Camera Modes --------------------------------------------------------
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CameraMode {
+    Follow(u32),
+    Free
+}
+
+
+// Replay Playback ------------------------------------------------------------
+pub struct Replay {
+
+    // Inputs
+    buttons: u8,
+    screen_cursor: (f32, f32),
+
+    // Rendering
+    camera: Camera,
+    camera_mode: CameraMode,
+    camera_position: (f32, f32),
+    fog_of_war: bool,
+    effects: Vec<Box<Effect>>,
+    screen_effects: Vec<Box<Effect>>,
+    particle_system: ParticleSystem,
+    player_renderer: PlayerRenderer,
+
+    // Playback
+    header: ReplayHeader,
+    frames: ReplayFrames,
+    frame_count: usize,
+    kills: Vec<usize>,
+    applied: Option<usize>,
+    position: f32,
+    speed: f32,
+    paused: bool
+
+}
+
+impl Replay {
+
+    pub fn open<P: AsRef<Path>>(path: P, width: u32, height: u32) -> Result<Replay, ReplayError> {
+
+        // Build the timeline in a single pass over the file, frames are
+        // streamed again from their recorded offsets during playback
+        let mut reader = try!(ReplayReader::open(path.as_ref()));
+        let header = reader.header().clone();
+
+        let mut frame_count = 0;
+        let mut offsets = Vec::new();
+        let mut kills = Vec::new();
+        let mut first_player = None;
+        loop {
+
+            let offset = reader.offset();
+            let frame = match reader.next() {
+                Some(frame) => try!(frame),
+                None => break
+            };
+            offsets.push(offset);
+
+            // Mark kills on the timeline
+            if frame.events.iter().any(|event| match *event {
+                ReplayEvent::Action(ReplayVisibility::Entity(..), Action::PlayerKill(..)) => true,
+                _ => false
+
+            }) {
+                kills.push(frame_count);
+            }
+
+            if first_player.is_none() {
+                first_player = frame.players.first().map(|p| p.id);
+            }
+
+            frame_count += 1;
+
+        }
+
+        info!("Replay loaded with {} frames", frame_count);
+
+        let camera_mode = first_player.map_or(CameraMode::Free, CameraMode::Follow);
+        let frames = ReplayFrames::new(reader, offsets);
+
+        Ok(Replay {
+
+            // Inputs
+            buttons: 0,
+            screen_cursor: (0.0, 0.0),
+
+            // Rendering
+            camera: Camera::new(width, height),
+            camera_mode: camera_mode,
+            camera_position: (0.0, 0.0),
+            fog_of_war: false,
+            effects: Vec::new(),
+            screen_effects: Vec::new(),
+            particle_system: ParticleSystem::new(MAX_PARTICLES),
+            player_renderer: PlayerRenderer::new(),
+
+            // Playback
+            header: header,
+            frames: frames,
+            frame_count: frame_count,
+            kills: kills,
+            applied: None,
+            position: 0.0,
+            speed: 1.0,
+            paused: false
+
+        })
+
+    }
+
+    pub fn updates_per_second(&self) -> u64 {
+        self.header.updates_per_second
+    }
+
+    pub fn level(&self) -> &str {
+        &self.header.level
+    }
+
+    pub fn input(&mut self, renderer: &mut Renderer, e: &Input) {
+
+        e.mouse_cursor(|x, y| {
+            self.screen_cursor = (x as f32, y as f32);
+        });
+
+        // Seek by clicking on the timeline
+        if let Some(Button::Mouse(MouseButton::Left)) = e.press_args() {
+            if self.screen_cursor.1 >= renderer.height() - REPLAY_TIMELINE_HEIGHT {
+                let position = self.screen_cursor.0 / renderer.width() * self.last_frame() as f32;
+                self.seek(position);
+            }
+        }
+
+        if let Some(value) = e.mouse_scroll_args() {
+            self.camera.z = (self.camera.z + (value[1] as f32) * 0.1).min(10.0).max(0.0);
+        }
+
+        if let Some(Button::Keyboard(key)) = e.press_args() {
+
+            let seek_frames = REPLAY_SEEK_SECONDS * self.header.updates_per_second as f32;
+            match key {
+                Key::Space => self.paused = !self.paused,
+                Key::Left => {
+                    let position = self.position - seek_frames;
+                    self.seek(position);
+                },
+                Key::Right => {
+                    let position = self.position + seek_frames;
+                    self.seek(position);
+                },
+                Key::Comma if self.paused => {
+                    let position = self.position.floor() - 1.0;
+                    self.seek(position);
+                },
+                Key::Period if self.paused => {
+                    let position = self.position.floor() + 1.0;
+                    self.seek(position);
+                },
+                Key::Home => self.seek(0.0),
+                Key::Up => self.speed = (self.speed * 2.0).min(REPLAY_MAX_SPEED),
+                Key::Down => self.speed = (self.speed * 0.5).max(REPLAY_MIN_SPEED),
+                Key::Tab => self.follow_next(),
+                Key::F => self.camera_mode = CameraMode::Free,
+                Key::V => self.fog_of_war = !self.fog_of_war,
+                Key::P => {
+                    let enabled = renderer.wireframe();
+                    renderer.set_wireframe(!enabled);
+                },
+                _ => {}
+            }
+
+            self.buttons |= match key {
+                Key::W => 1,
+                Key::A => 8,
+                Key::S => 4,
+                Key::D => 2,
+                _ => 0
+
+            } as u8;
+
+        }
+
+        if let Some(Button::Keyboard(key)) = e.release_args() {
+            self.buttons &= !(match key {
+                Key::W => 1,
+                Key::A => 8,
+                Key::S => 4,
+                Key::D => 2,
+                _ => 0
+
+            } as u8);
+        }
+
+    }
+
+    pub fn update(&mut self, level: &Level) {
+
+        // Move the free camera, any movement detaches it from the followed player
+        if self.buttons != 0 {
+            if let Some(data) = self.followed_data() {
+                self.camera_position = (data.x, data.y);
+            }
+            self.camera_mode = CameraMode::Free;
+            self.camera_position.0 += self.axis(2, 8) * REPLAY_CAMERA_SPEED;
+            self.camera_position.1 += self.axis(4, 1) * REPLAY_CAMERA_SPEED;
+        }
+
+        if self.frame_count == 0 {
+            return;
+        }
+
+        if !self.paused {
+            self.position = (self.position + self.speed).min(self.last_frame() as f32);
+            if self.position >= self.last_frame() as f32 {
+                self.paused = true;
+            }
+        }
+
+        // Trigger the events of all frames which were reached since the last
+        // update, starting with the first frame of the replay
+        let next = self.position.floor() as usize;
+        let first = self.applied.map_or(0, |index| index + 1);
+        for index in first..(next + 1) {
+            self.apply_events(level, index);
+        }
+        self.applied = Some(next);
+
+    }
+
+    pub fn render(&mut self, renderer: &mut Renderer, level: &Level) {
+
+        // Clear
+        renderer.clear_stencil(0);
+        renderer.clear_color([0.0; 4]);
+
+        if self.frame_count == 0 {
+            return;
+        }
+
+        // Interpolate in between updates
+        let t = renderer.t();
+        let position = if self.paused {
+            self.position
+
+        } else {
+            (self.position + renderer.u() * self.speed).min(self.last_frame() as f32)
+        };
+
+        let players = self.players_at(position);
+        let viewer = match self.camera_mode {
+            CameraMode::Follow(id) => players.iter().find(|p| p.0 == id).map(|p| p.2.clone()),
+            CameraMode::Free => None
+        };
+
+        // Camera setup
+        if let Some(ref data) = viewer {
+            self.camera_position = (data.x, data.y);
+        }
+        self.camera.center(self.camera_position.0, self.camera_position.1);
+        self.camera.limit(level.bounds());
+        self.camera.apply(renderer);
+
+        // Level Background
+        level.render_background(
+            renderer,
+            &self.camera,
+            self.camera_position.0,
+            self.camera_position.1,
+            0
+        );
+
+        // Players
+        {
+            let context = self.camera.context();
+            for &(id, color, ref p) in &players {
+
+                // Hide players which the followed player can not see
+                let visible = p.hp > 0 && match viewer {
+                    Some(ref data) if self.fog_of_war => {
+                        Some(id) == self.followed_id() || level.player_within_visibility(data, p)
+                    },
+                    _ => true
+                };
+
+                if visible {
+                    let colors = PlayerRenderer::colors(ColorName::from_u8(color));
+                    self.player_renderer.render(renderer, context, p, colors);
+                }
+
+            }
+        }
+
+        // World Effects
+        for effect in &self.effects {
+            effect.render(renderer, &self.camera);
+        }
+
+        self.effects.retain(|e| e.alive(t));
+
+        // Particles
+        {
+            let (scale, context) = (self.camera.scalar(0.4), self.camera.context());
+            self.particle_system.render(scale, &context.transform, renderer);
+        }
+
+        // Lights
+        level.render_lights(renderer, &self.camera, 0);
+
+        // Fog of war from the perspective of the followed player
+        match viewer {
+            Some(ref data) if self.fog_of_war => {
+                level.render_shadow(renderer, &self.camera, data, 0);
+            },
+            _ => renderer.set_stencil_mode(StencilMode::None)
+        }
+
+        // Level Walls
+        level.render_walls(
+            renderer,
+            &self.camera,
+            self.camera_position.0,
+            self.camera_position.1,
+            0
+        );
+
+        // Screen Effects
+        for effect in &self.screen_effects {
+            effect.render(renderer, &self.camera);
+        }
+
+        self.screen_effects.retain(|e| e.alive(t));
+
+        // HUD
+        self.render_timeline(renderer, position);
+
+    }
+
+    fn render_timeline(&self, renderer: &mut Renderer, position: f32) {
+
+        let context = renderer.context().clone();
+        let (w, h) = (renderer.width(), renderer.height());
+        let y = h - REPLAY_TIMELINE_HEIGHT * 0.5;
+        let last = self.last_frame().max(1) as f32;
+
+        renderer.set_color([0.25, 0.25, 0.25, 1.0]);
+        renderer.line(&context, &[0.0, y, w, y], 4.0);
+
+        renderer.set_color([0.75, 0.75, 0.75, 1.0]);
+        renderer.line(&context, &[0.0, y, w * position / last, y], 4.0);
+
+        renderer.set_color([0.9, 0.1, 0.1, 1.0]);
+        for index in &self.kills {
+            let x = w * *index as f32 / last;
+            renderer.line(&context, &[x, y - 6.0, x, y + 6.0], 2.0);
+        }
+
+        // Playback state
+        if self.paused {
+            renderer.set_color([1.0, 1.0, 1.0, 1.0]);
+            renderer.rectangle(&context, &[10.0, 10.0, 4.0, 12.0]);
+            renderer.rectangle(&context, &[18.0, 10.0, 4.0, 12.0]);
+
+        } else {
+            renderer.set_color([1.0, 1.0, 1.0, 1.0]);
+            renderer.line(&context, &[10.0, 16.0, 10.0 + 8.0 * self.speed, 16.0], 4.0);
+        }
+
+    }
+
+    fn seek(&mut self, position: f32) {
+        self.position = position.max(0.0).min(self.last_frame() as f32);
+        self.effects.clear();
+        self.screen_effects.clear();
+
+        // Trigger the events of the frame seeked to on the next update
+        self.applied = (self.position.floor() as usize).checked_sub(1);
+    }
+
+    fn last_frame(&self) -> usize {
+        self.frame_count.saturating_sub(1)
+    }
+
+    fn axis(&self, positive: u8, negative: u8) -> f32 {
+        let mut value = 0.0;
+        if self.buttons & positive == positive {
+            value += 1.0;
+        }
+        if self.buttons & negative == negative {
+            value -= 1.0;
+        }
+        value
+    }
+
+    fn followed_id(&self) -> Option<u32> {
+        match self.camera_mode {
+            CameraMode::Follow(id) => Some(id),
+            CameraMode::Free => None
+        }
+    }
+
+    fn followed_data(&mut self) -> Option<PlayerData> {
+        let position = self.position;
+        self.followed_id().and_then(|id| {
+            self.players_at(position).into_iter().find(|p| p.0 == id).map(|p| p.2)
+        })
+    }
+
+    fn follow_next(&mut self) {
+        let position = self.position;
+        let ids: Vec<u32> = self.players_at(position).iter().map(|p| p.0).collect();
+        let next = match self.followed_id().and_then(|id| ids.iter().position(|i| *i == id)) {
+            Some(index) => ids.get((index + 1) % ids.len()).cloned(),
+            None => ids.first().cloned()
+        };
+        if let Some(id) = next {
+            self.camera_mode = CameraMode::Follow(id);
+        }
+    }
+
+    fn players_at(&mut self, position: f32) -> Vec<(u32, u8, PlayerData)> {
+
+        let index = position.floor() as usize;
+        let u = position - index as f32;
+
+        let (frame, next_frame) = self.frames.get(index);
+        let frame = if let Some(frame) = frame {
+            frame
+
+        } else {
+            return Vec::new();
+        };
+
+        frame.players.iter().map(|player| {
+
+            let next = next_frame.and_then(|next| {
+                next.players.iter().find(|p| p.id == player.id)
+            });
+
+            let data = if let Some(next) = next {
+                // Recorded states are always visible on the server
+                let mut last = player.data.clone();
+                last.visible = true;
+                next.data.interpolate_from(&last, u)
+
+            } else {
+                player.data.clone()
+            };
+
+            (player.id, player.color, data)
+
+        }).collect()
+
+    }
+
+    fn apply_events(&mut self, level: &Level, index: usize) {
+
+        let frame = match self.frames.get(index).0 {
+            Some(frame) => frame.clone(),
+            None => return
+        };
+
+        let viewer = self.followed_id().and_then(|id| {
+            frame.players.iter().find(|p| p.id == id).map(|p| (id, p.color, p.data.clone()))
+        });
+
+        let local_color = viewer.as_ref().map_or(ColorName::Black, |v| ColorName::from_u8(v.1));
+        let actions: Vec<Action> = frame.events.iter().filter_map(|event| {
+            if let ReplayEvent::Action(ref visibility, ref action) = *event {
+                if self.action_visible(level, visibility, viewer.as_ref()) {
+                    Some(action.clone())
+
+                } else {
+                    None
+                }
+
+            } else {
+                None
+            }
+
+        }).collect();
+
+        for action in actions {
+            effect::apply_action(
+                action,
+                level,
+                &mut self.particle_system,
+                &mut self.effects,
+                &mut self.screen_effects,
+                local_color
+            );
+        }
+
+    }
+
+    fn action_visible(
+        &self,
+        level: &Level,
+        visibility: &ReplayVisibility,
+        viewer: Option<&(u32, u8, PlayerData)>
+
+    ) -> bool {
+
+        match viewer {
+
+            // Apply the same filtering the server did for the viewing player
+            Some(&(id, _, ref data)) if self.fog_of_war => match *visibility {
+                ReplayVisibility::Any => true,
+                ReplayVisibility::Connection(filter_id) => filter_id == id,
+                ReplayVisibility::Entity(ref other_data, filter_id) => {
+                    filter_id != Some(id) && level.player_within_visibility(data, other_data)
+                },
+                ReplayVisibility::WithinRange(aabb, r) => {
+                    aabb_intersect_circle(&aabb, data.x, data.y, r)
+                }
+            },
+
+            // Without fog of war only the copies of actions which are sent
+            // to specific connections need to be skipped
+            _ => match *visibility {
+                ReplayVisibility::Connection(_) => false,
+                _ => true
+            }
+
+        }
+
+    }
+
+}
+
+
+// Frame Streaming ------------------------------------------------------------
+
+/// Streams the frames of a replay file, only the frames needed for the
+/// current playback position are kept in memory.
+struct ReplayFrames {
+    reader: ReplayReader<BufReader<File>>,
+    offsets: Vec<u64>,
+    frames: VecDeque<ReplayFrame>,
+    start: usize,
+    finished: bool
+}
+
+impl ReplayFrames {
+
+    fn new(reader: ReplayReader<BufReader<File>>, offsets: Vec<u64>) -> ReplayFrames {
+        let mut frames = ReplayFrames {
+            reader: reader,
+            offsets: offsets,
+            frames: VecDeque::new(),
+            start: 0,
+            finished: true
+        };
+        frames.seek(0);
+        frames
+    }
+
+    /// Returns the frame at `index` together with the one following it.
+    fn get(&mut self, index: usize) -> (Option<&ReplayFrame>, Option<&ReplayFrame>) {
+
+        // Frames which are not buffered or next in the file are seeked to
+        if index < self.start || index > self.start + self.frames.len() {
+            self.seek(index);
+        }
+
+        // Frames before the requested one are no longer needed
+        while self.start < index {
+            self.frames.pop_front();
+            self.start += 1;
+        }
+
+        while self.frames.len() < 2 && !self.finished {
+            match self.reader.next() {
+                Some(Ok(frame)) => self.frames.push_back(frame),
+                Some(Err(err)) => {
+                    error!("Failed to read replay frame: {}", err);
+                    self.finished = true;
+                },
+                None => self.finished = true
+            }
+        }
+
+        (self.frames.get(0), self.frames.get(1))
+
+    }
+
+    /// Continues reading at the byte offset recorded for the frame.
+    fn seek(&mut self, index: usize) {
+        self.frames.clear();
+        self.start = index;
+        self.finished = true;
+        if let Some(&offset) = self.offsets.get(index) {
+            match self.reader.seek(offset) {
+                Ok(()) => self.finished = false,
+                Err(err) => error!("Failed to seek to replay frame {}: {}", index, err)
+            }
+        }
+    }
+
+}
+
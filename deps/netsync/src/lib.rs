@@ -16,20 +16,45 @@ use std::collections::VecDeque;
 
 
 // Traits ---------------------------------------------------------------------
+pub trait Tick: Copy + Default + Eq + fmt::Debug {
+    fn bits() -> u32;
+    fn to_u32(self) -> u32;
+    fn from_u32(value: u32) -> Self;
+    fn to_bytes(&self) -> Vec<u8>;
+    fn from_bytes(bytes: &[u8]) -> Option<(usize, Self)> where Self: Sized;
+}
+
 pub trait NetworkInput: Default + fmt::Debug {
-    fn tick(&self) -> u8;
+    type Tick: Tick;
+    fn tick(&self) -> Self::Tick;
     fn to_bytes(&self) -> Vec<u8>;
-    fn from_bytes(&[u8]) -> Option<(usize, Self)> where Self: Sized;
+    fn from_bytes(bytes: &[u8]) -> Option<(usize, Self)> where Self: Sized;
+
+    // Creates a copy of the input for another tick, used by the server to
+    // fill in inputs which were lost in transit
+    fn repeat(&self, tick: Self::Tick) -> Self where Self: Sized;
 }
 
 pub trait NetworkProperty: Clone + Default + fmt::Debug {
-    fn interpolate_from(&self, &Self, f32) -> Self;
+    fn interpolate_from(&self, last: &Self, u: f32) -> Self;
     fn to_bytes(&self) -> Vec<u8>;
-    fn from_bytes(&[u8]) -> Self where Self: Sized;
+    fn from_bytes(bytes: &[u8]) -> Self where Self: Sized;
+
+    /// Magnitude of the difference between two states, used to measure
+    /// prediction errors. Properties which keep the default are never smoothed.
+    fn error_distance(&self, _other: &Self) -> f32 {
+        0.0
+    }
+
+    /// Returns a copy of the state shifted by `u` times the difference
+    /// between `from` and `to`.
+    fn apply_error(&self, _from: &Self, _to: &Self, _u: f32) -> Self {
+        self.clone()
+    }
 }
 
 pub trait NetworkState<P: NetworkProperty, I: NetworkInput>: fmt::Debug + Default {
-    fn new(usize) -> Self where Self: Sized;
+    fn new(buffer_size: usize) -> Self where Self: Sized;
     fn set(&mut self, base: P);
     fn update_with<F: FnMut(&mut P, Option<&P>, Option<&I>)>(
         &mut self,
@@ -39,23 +64,179 @@ pub trait NetworkState<P: NetworkProperty, I: NetworkInput>: fmt::Debug + Defaul
 }
 
 
+// Tick Widths ----------------------------------------------------------------
+impl Tick for u8 {
+
+    fn bits() -> u32 {
+        8
+    }
+
+    fn to_u32(self) -> u32 {
+        self as u32
+    }
+
+    fn from_u32(value: u32) -> u8 {
+        value as u8
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        vec![*self]
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<(usize, u8)> {
+        bytes.first().map(|b| (1, *b))
+    }
+
+}
+
+impl Tick for u16 {
+
+    fn bits() -> u32 {
+        16
+    }
+
+    fn to_u32(self) -> u32 {
+        self as u32
+    }
+
+    fn from_u32(value: u32) -> u16 {
+        value as u16
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        vec![*self as u8, (*self >> 8) as u8]
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<(usize, u16)> {
+        if bytes.len() >= 2 {
+            Some((2, bytes[0] as u16 | (bytes[1] as u16) << 8))
+
+        } else {
+            None
+        }
+    }
+
+}
+
+impl Tick for u32 {
+
+    fn bits() -> u32 {
+        32
+    }
+
+    fn to_u32(self) -> u32 {
+        self
+    }
+
+    fn from_u32(value: u32) -> u32 {
+        value
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        vec![*self as u8, (*self >> 8) as u8, (*self >> 16) as u8, (*self >> 24) as u8]
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<(usize, u32)> {
+        if bytes.len() >= 4 {
+            Some((4, bytes[0] as u32 | (bytes[1] as u32) << 8 | (bytes[2] as u32) << 16 | (bytes[3] as u32) << 24))
+
+        } else {
+            None
+        }
+    }
+
+}
+
+
+// Missing Input Policies -----------------------------------------------------
+
+/// Decides how the server advances a state for a tick in which no input was
+/// received from the client.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum MissingInputPolicy {
+
+    /// Repeat the last input received from the client
+    #[default]
+    RepeatLast,
+
+    /// Apply a default input
+    Zero,
+
+    /// Call the update callback without any input, leaving it up to the
+    /// callback to extrapolate the state
+    Extrapolate
+
+}
+
+
 // Client Side Logic ----------------------------------------------------------
+const PREDICTION_ERROR_AVERAGE_FACTOR: f32 = 0.1;
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct PredictionStats {
+
+    /// Number of confirmed states which disagreed with the local prediction
+    pub corrections: u32,
+
+    /// Number of corrections which were applied without smoothing
+    pub snaps: u32,
+
+    /// Error of the most recent correction
+    pub last_error: f32,
+
+    /// Largest error seen so far
+    pub max_error: f32,
+
+    /// Exponential moving average of the error of all corrections
+    pub average_error: f32
+}
+
 #[derive(Default, Debug)]
 pub struct ClientState<P: NetworkProperty, I: NetworkInput> {
     current: P,
     last: P,
     base: P,
-    confirmed_tick: u8,
+    confirmed_tick: I::Tick,
     confirmed_state: Option<P>,
     received_remote: bool,
+    acked_tick: Option<I::Tick>,
     buffered_inputs: VecDeque<I>,
-    input_buffer_size: usize
+    input_buffer_size: usize,
+    input_redundancy: usize,
+    predictions: VecDeque<(I::Tick, P)>,
+    error: Option<(P, P)>,
+    error_factor: f32,
+    last_error_factor: f32,
+    error_smoothing_ticks: usize,
+    error_snap_distance: f32,
+    prediction_stats: PredictionStats
 }
 
 impl<P: NetworkProperty, I: NetworkInput> ClientState<P, I> {
 
     pub fn interpolate(&self, u: f32) -> P {
-        self.current.interpolate_from(&self.last, u)
+        let state = self.current.interpolate_from(&self.last, u);
+        if let Some((ref from, ref to)) = self.error {
+            let factor = self.last_error_factor + (self.error_factor - self.last_error_factor) * u;
+            state.apply_error(from, to, factor)
+
+        } else {
+            state
+        }
+    }
+
+    /// Spreads corrections of the local prediction over the given number of
+    /// ticks instead of applying them at once, `0` disables smoothing.
+    ///
+    /// Errors larger than `snap_distance` (e.g. from a respawn) are always
+    /// applied immediately.
+    pub fn set_error_smoothing(&mut self, ticks: usize, snap_distance: f32) {
+        self.error_smoothing_ticks = ticks;
+        self.error_snap_distance = snap_distance;
+    }
+
+    pub fn prediction_stats(&self) -> PredictionStats {
+        self.prediction_stats
     }
 
     pub fn input(&mut self, input: I) {
@@ -68,10 +249,31 @@ impl<P: NetworkProperty, I: NetworkInput> ClientState<P, I> {
 
     }
 
+    pub fn set_input_redundancy(&mut self, inputs: usize) {
+        self.input_redundancy = cmp::max(inputs, 1);
+    }
+
+    pub fn acked_tick(&self) -> Option<I::Tick> {
+        self.acked_tick
+    }
+
     pub fn send(&mut self) -> Vec<u8> {
 
+        // Only send inputs which were not yet acknowledged by the server,
+        // re-sending up to the redundancy limit to cover for lost packets
+        let acked_tick = self.acked_tick;
+        let unacked: Vec<&I> = self.buffered_inputs.iter().filter(|input| {
+            match acked_tick {
+                Some(tick) => tick_is_more_recent(input.tick(), tick),
+                None => true
+            }
+
+        }).collect();
+
+        let skip = unacked.len().saturating_sub(self.input_redundancy);
+
         let mut serialized_inputs = Vec::new();
-        for input in &self.buffered_inputs {
+        for input in unacked.into_iter().skip(skip) {
             serialized_inputs.extend(input.to_bytes());
         }
 
@@ -79,42 +281,54 @@ impl<P: NetworkProperty, I: NetworkInput> ClientState<P, I> {
 
     }
 
-    pub fn receive(&mut self, bytes: &[u8], tick: Option<u8>) {
-
-        let state = P::from_bytes(bytes);
+    pub fn receive(&mut self, bytes: &[u8], tick: Option<I::Tick>) {
 
         // Confirmed remote state for locally controlled state
         if let Some(tick) = tick {
-            // TODO compare with local last tick to avoid receiving outdated state?
-            // tick_is_more_recent(tick, self.confirmed_tick)
-            self.confirmed_state = Some(state);
+            if self.is_outdated(tick) {
+                return;
+            }
+            self.acknowledge(tick);
+            self.confirmed_state = Some(P::from_bytes(bytes));
             self.confirmed_tick = tick;
 
         // Remote controlled state
         } else {
-            self.confirmed_state = Some(state);
-            self.confirmed_tick = 0;
+            self.confirmed_state = Some(P::from_bytes(bytes));
+            self.confirmed_tick = I::Tick::default();
         }
 
     }
 
-    pub fn receive_with<F: FnMut(&P, &mut P)>(&mut self, bytes: &[u8], tick: Option<u8>, mut modifier: F) {
+    /// Receives a state as serialized by `ServerState::send(None)`, prefixed
+    /// with the tick it was confirmed at.
+    pub fn receive_confirmed(&mut self, bytes: &[u8]) {
+        if let Some((size, tick)) = I::Tick::from_bytes(bytes) {
+            self.receive(&bytes[size..], Some(tick));
+        }
+    }
 
-        let mut state = P::from_bytes(bytes);
-        modifier(&self.current, &mut state);
+    pub fn receive_with<F: FnMut(&P, &mut P)>(&mut self, bytes: &[u8], tick: Option<I::Tick>, mut modifier: F) {
 
         // Confirmed remote state for locally controlled state
         if let Some(tick) = tick {
-            // TODO compare with local last tick to avoid receiving outdated state?
-            // tick_is_more_recent(tick, self.confirmed_tick)
+            if self.is_outdated(tick) {
+                return;
+            }
+
+            let mut state = P::from_bytes(bytes);
+            modifier(&self.current, &mut state);
+            self.acknowledge(tick);
             self.confirmed_state = Some(state);
             self.confirmed_tick = tick;
 
         // Remote controlled state
         } else {
+            let mut state = P::from_bytes(bytes);
+            modifier(&self.current, &mut state);
             self.received_remote = true;
             self.confirmed_state = Some(state);
-            self.confirmed_tick = 0;
+            self.confirmed_tick = I::Tick::default();
         }
 
     }
@@ -137,6 +351,27 @@ impl<P: NetworkProperty, I: NetworkInput> ClientState<P, I> {
 
     }
 
+    fn is_outdated(&self, tick: I::Tick) -> bool {
+        // States which arrive out of order would roll back the prediction
+        match self.acked_tick {
+            Some(acked) => tick_is_more_recent(acked, tick),
+            None => false
+        }
+    }
+
+    fn acknowledge(&mut self, tick: I::Tick) {
+        // The confirmed tick of a state also acknowledges all inputs up to it,
+        // ignore acks from states which arrived out of order
+        let is_newer = match self.acked_tick {
+            Some(acked) => tick_is_more_recent(tick, acked),
+            None => true
+        };
+
+        if is_newer {
+            self.acked_tick = Some(tick);
+        }
+    }
+
 }
 
 impl<P: NetworkProperty, I: NetworkInput> NetworkState<P, I> for ClientState<P, I> {
@@ -146,11 +381,20 @@ impl<P: NetworkProperty, I: NetworkInput> NetworkState<P, I> for ClientState<P,
             current: P::default(),
             last: P::default(),
             base: P::default(),
-            confirmed_tick: 0,
+            confirmed_tick: I::Tick::default(),
             confirmed_state: None,
             received_remote: false,
+            acked_tick: None,
             buffered_inputs: VecDeque::new(),
-            input_buffer_size: buffer_size
+            input_buffer_size: buffer_size,
+            input_redundancy: buffer_size,
+            predictions: VecDeque::new(),
+            error: None,
+            error_factor: 0.0,
+            last_error_factor: 0.0,
+            error_smoothing_ticks: 0,
+            error_snap_distance: 0.0,
+            prediction_stats: PredictionStats::default()
         }
     }
 
@@ -158,6 +402,8 @@ impl<P: NetworkProperty, I: NetworkInput> NetworkState<P, I> for ClientState<P,
         self.base = base;
         self.current = self.base.clone();
         self.last = self.current.clone();
+        self.predictions.clear();
+        self.clear_error();
     }
 
     fn update_with<F: FnMut(&mut P, Option<&P>, Option<&I>)>(
@@ -165,12 +411,27 @@ impl<P: NetworkProperty, I: NetworkInput> NetworkState<P, I> for ClientState<P,
         mut callback: F
     ) {
 
+        self.decay_error();
+
         // Check if we have a newly confirmed server state
         if let Some(confirmed_state) = self.confirmed_state.take() {
 
+            let predicted = if self.received_remote {
+                None
+
+            } else {
+                self.correct_error(&confirmed_state)
+            };
+
             // Set the current state as the last state and wtaketkae over the
             // confirmed state as new base state
             self.last = self.current.clone();
+
+            // Move the last state onto the corrected timeline so the smoothed
+            // state does not jump in between updates
+            if let Some(predicted) = predicted {
+                self.last = self.last.apply_error(&confirmed_state, &predicted, 1.0);
+            }
             self.base = confirmed_state.clone();
             self.current = confirmed_state;
 
@@ -198,6 +459,23 @@ impl<P: NetworkProperty, I: NetworkInput> NetworkState<P, I> for ClientState<P,
             for input in &self.buffered_inputs {
                 callback(&mut new_state, Some(&self.last), Some(input));
             }
+
+            // Remember the prediction so it can be compared against the
+            // server state once the tick gets confirmed
+            if let Some(input) = self.buffered_inputs.back() {
+                let tick = input.tick();
+                let is_new = match self.predictions.back() {
+                    Some(&(last, _)) => tick_is_more_recent(tick, last),
+                    None => true
+                };
+
+                if is_new {
+                    self.predictions.push_back((tick, new_state.clone()));
+                    if self.predictions.len() > self.input_buffer_size {
+                        self.predictions.pop_front();
+                    }
+                }
+            }
         }
 
         // Assign calculated state
@@ -207,17 +485,118 @@ impl<P: NetworkProperty, I: NetworkInput> NetworkState<P, I> for ClientState<P,
 
 }
 
+impl<P: NetworkProperty, I: NetworkInput> ClientState<P, I> {
+
+    fn take_prediction(&mut self, confirmed_tick: I::Tick) -> Option<P> {
+
+        // Drop predictions for all ticks the server has already confirmed
+        let mut predicted = None;
+        while let Some((tick, state)) = self.predictions.pop_front() {
+            if tick_is_more_recent(tick, confirmed_tick) {
+                self.predictions.push_front((tick, state));
+                break;
+
+            } else if tick == confirmed_tick {
+                predicted = Some(state);
+            }
+        }
+
+        predicted
+
+    }
+
+    fn correct_error(&mut self, confirmed_state: &P) -> Option<P> {
+
+        let confirmed_tick = self.confirmed_tick;
+        if let Some(predicted) = self.take_prediction(confirmed_tick) {
+            self.smooth_error(predicted, confirmed_state)
+
+        } else {
+            None
+        }
+
+    }
+
+    fn smooth_error(&mut self, predicted: P, confirmed_state: &P) -> Option<P> {
+
+        let error = predicted.error_distance(confirmed_state);
+        if error <= 0.0 {
+            return None;
+        }
+
+        let stats = &mut self.prediction_stats;
+        stats.average_error = if stats.corrections == 0 {
+            error
+
+        } else {
+            stats.average_error + (error - stats.average_error) * PREDICTION_ERROR_AVERAGE_FACTOR
+        };
+        stats.corrections = stats.corrections.saturating_add(1);
+        stats.last_error = error;
+        stats.max_error = stats.max_error.max(error);
+
+        if self.error_smoothing_ticks == 0 {
+            self.clear_error();
+            None
+
+        } else if error > self.error_snap_distance {
+            stats.snaps = stats.snaps.saturating_add(1);
+            self.clear_error();
+            None
+
+        } else {
+
+            // Fold the remaining offset, as displayed before this update, into
+            // the new one so the displayed state stays continuous
+            let from = match self.error {
+                Some((ref from, ref to)) => predicted.apply_error(from, to, self.last_error_factor),
+                None => predicted.clone()
+            };
+
+            self.error = Some((from, confirmed_state.clone()));
+            self.error_factor = 1.0;
+            self.last_error_factor = 1.0;
+            Some(predicted)
+
+        }
+
+    }
+
+    fn decay_error(&mut self) {
+        self.last_error_factor = self.error_factor;
+        if self.error.is_some() {
+            let step = 1.0 / cmp::max(self.error_smoothing_ticks, 1) as f32;
+            self.error_factor = (self.error_factor - step).max(0.0);
+            if self.last_error_factor <= 0.0 {
+                self.clear_error();
+            }
+        }
+    }
+
+    fn clear_error(&mut self) {
+        self.error = None;
+        self.error_factor = 0.0;
+        self.last_error_factor = 0.0;
+    }
+
+}
+
 
 // Server Side Logic ----------------------------------------------------------
 #[derive(Default, Debug)]
 pub struct ServerState<P: NetworkProperty, I: NetworkInput> {
     current: P,
-    confirmed_tick: u8,
+    confirmed_tick: I::Tick,
     buffered_inputs: VecDeque<I>,
     buffered_states: VecDeque<P>,
     state_buffer_size: usize,
     first_input: bool,
-    last_input_tick: u8
+    last_input: Option<I>,
+    last_input_tick: I::Tick,
+    max_input_gap: u8,
+    missing_inputs: u8,
+    missing_input_policy: MissingInputPolicy,
+    sequence: u16
 }
 
 impl<P: NetworkProperty, I: NetworkInput> ServerState<P, I> {
@@ -235,8 +614,10 @@ impl<P: NetworkProperty, I: NetworkInput> ServerState<P, I> {
 
     }
 
-    pub fn get_client_tick_diff(&self, tick: u8, tick_delay: u8) -> u8 {
-        let ticks_ago = cmp::max(0, self.last_input_tick as i16 - tick as i16) as u8;
+    pub fn get_client_tick_diff(&self, tick: I::Tick, tick_delay: u8) -> u8 {
+        // Relative to the tick of the current state, received inputs might
+        // still be waiting to be applied
+        let ticks_ago = tick_diff(self.confirmed_tick, tick).clamp(0, 255) as u8;
         ticks_ago.saturating_add(tick_delay)
     }
 
@@ -248,11 +629,36 @@ impl<P: NetworkProperty, I: NetworkInput> ServerState<P, I> {
         }
     }
 
+    pub fn set_max_input_gap(&mut self, ticks: u8) {
+        self.max_input_gap = ticks;
+    }
+
+    pub fn set_missing_input_policy(&mut self, policy: MissingInputPolicy) {
+        self.missing_input_policy = policy;
+    }
+
     fn receive_input(&mut self, input: I) {
         if self.first_input || tick_is_more_recent(input.tick(), self.last_input_tick) {
+
+            // Repeat the last known input for all ticks which were lost in
+            // transit, larger gaps are most likely caused by a client which
+            // stalled and are not filled in
+            let gap = tick_diff(input.tick(), self.last_input_tick) - 1;
+            if gap <= self.max_input_gap as i64 {
+                if let Some(ref last) = self.last_input {
+                    for i in 1..(gap + 1) {
+                        let tick = tick_offset(self.last_input_tick, i);
+                        self.buffered_inputs.push_back(last.repeat(tick));
+                    }
+                }
+            }
+
             self.first_input = false;
+            self.missing_inputs = 0;
             self.last_input_tick = input.tick();
+            self.last_input = Some(input.repeat(input.tick()));
             self.buffered_inputs.push_back(input);
+
         }
     }
 
@@ -262,7 +668,7 @@ impl<P: NetworkProperty, I: NetworkInput> ServerState<P, I> {
             self.get_relative(delay).to_bytes()
 
         } else {
-            let mut bytes = vec![self.confirmed_tick];
+            let mut bytes = self.confirmed_tick.to_bytes();
             bytes.append(&mut self.current.to_bytes());
             bytes
         }
@@ -277,7 +683,7 @@ impl<P: NetworkProperty, I: NetworkInput> ServerState<P, I> {
             state.to_bytes()
 
         } else {
-            let mut bytes = vec![self.confirmed_tick];
+            let mut bytes = self.confirmed_tick.to_bytes();
             let mut state = self.current.clone();
             modifier(&mut state);
             bytes.append(&mut state.to_bytes());
@@ -286,6 +692,15 @@ impl<P: NetworkProperty, I: NetworkInput> ServerState<P, I> {
 
     }
 
+    /// Serializes a past state prefixed with its sequence number, allowing
+    /// clients to place it on a timeline via a `SnapshotBuffer`.
+    pub fn send_snapshot_with<F: FnMut(&mut P)>(&self, delay: u8, modifier: F) -> Vec<u8> {
+        let delay = cmp::min(delay as usize, self.buffered_states.len()) as u16;
+        let mut bytes = self.sequence.wrapping_sub(delay).to_bytes();
+        bytes.append(&mut self.send_with(Some(delay as u8), modifier));
+        bytes
+    }
+
     pub fn apply<F: FnMut(&mut P)>(&mut self, mut callback: F) {
         callback(&mut self.current);
     }
@@ -297,12 +712,17 @@ impl<P: NetworkProperty, I: NetworkInput> NetworkState<P, I> for ServerState<P,
     fn new(buffer_size: usize) -> ServerState<P, I> {
         ServerState {
             current: P::default(),
-            confirmed_tick: 0,
+            confirmed_tick: I::Tick::default(),
             buffered_inputs: VecDeque::new(),
             buffered_states: VecDeque::new(),
             state_buffer_size: buffer_size,
             first_input: true,
-            last_input_tick: 0
+            last_input: None,
+            last_input_tick: I::Tick::default(),
+            max_input_gap: 8,
+            missing_inputs: 0,
+            missing_input_policy: MissingInputPolicy::default(),
+            sequence: 0
         }
     }
 
@@ -315,14 +735,47 @@ impl<P: NetworkProperty, I: NetworkInput> NetworkState<P, I> for ServerState<P,
         mut callback: F
     ) {
 
-        // TODO this will never run if we don't have any input
-        // TODO make input optional?
-        while let Some(input) = self.buffered_inputs.pop_front() {
+        // Advance the state with a substitute input in case none was
+        // received for this tick
+        if self.buffered_inputs.is_empty() {
+
+            // The substitute takes the place of the next expected input, so
+            // the late arrival of the real one is ignored. Clients which
+            // stalled for longer than the maximum gap no longer advance
+            // their tick.
+            let stalled = self.first_input || self.missing_inputs >= self.max_input_gap;
+            let tick = if stalled {
+                self.last_input_tick
+
+            } else {
+                self.missing_inputs += 1;
+                self.last_input_tick = tick_offset(self.last_input_tick, 1);
+                self.confirmed_tick = self.last_input_tick;
+                self.last_input_tick
+            };
+
+            let input = match self.missing_input_policy {
+                MissingInputPolicy::RepeatLast if !stalled => Some(match self.last_input {
+                    Some(ref last) => last.repeat(tick),
+                    None => I::default().repeat(tick)
+                }),
+                MissingInputPolicy::RepeatLast | MissingInputPolicy::Zero => {
+                    Some(I::default().repeat(tick))
+                },
+                MissingInputPolicy::Extrapolate => None
+            };
+
+            callback(&mut self.current, None, input.as_ref());
+
+        // Apply a single input per tick, inputs which arrived in bursts are
+        // kept for the following ticks
+        } else if let Some(input) = self.buffered_inputs.pop_front() {
             self.confirmed_tick = input.tick();
             callback(&mut self.current, None, Some(&input));
         }
 
         self.buffered_states.push_back(self.current.clone());
+        self.sequence = self.sequence.wrapping_add(1);
 
         if self.buffered_states.len() > self.state_buffer_size {
             self.buffered_states.pop_front();
@@ -333,8 +786,374 @@ impl<P: NetworkProperty, I: NetworkInput> NetworkState<P, I> for ServerState<P,
 }
 
 
+// Snapshot Interpolation -----------------------------------------------------
+const SNAPSHOT_BUFFER_SIZE: usize = 32;
+const SNAPSHOT_DRIFT_CORRECTION: f64 = 0.1;
+const SNAPSHOT_JITTER_SMOOTHING: f32 = 0.1;
+const SNAPSHOT_RESYNC_INTERVALS: f64 = 8.0;
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SnapshotStats {
+
+    /// Number of currently buffered snapshots
+    pub buffered: usize,
+
+    /// Milliseconds the newest snapshot is ahead of the render time
+    pub buffered_time: f32,
+
+    /// Smoothed deviation of snapshot arrival times in milliseconds
+    pub jitter: f32,
+
+    /// Number of times the buffer ran dry and had to be extrapolated
+    pub underruns: u32,
+
+    /// Number of snapshots which arrived out of order
+    pub dropped: u32,
+
+    /// Whether the last sample was extrapolated
+    pub extrapolating: bool
+
+}
+
+/// Jitter buffer for the states of remotely controlled entities.
+///
+/// Snapshots are placed on a local timeline based on their sequence number
+/// and are sampled `delay` milliseconds in the past, so late packets still
+/// arrive before they are needed. When the buffer runs dry the last two
+/// snapshots are extrapolated for at most `max_extrapolation` milliseconds.
+#[derive(Debug)]
+pub struct SnapshotBuffer<P: NetworkProperty> {
+    snapshots: VecDeque<(f64, P)>,
+    interval: f64,
+    delay: f64,
+    max_extrapolation: f64,
+    last: Option<(u16, f64)>,
+    stats: SnapshotStats
+}
+
+impl<P: NetworkProperty> SnapshotBuffer<P> {
+
+    pub fn new(interval: u64, delay: u64, max_extrapolation: u64) -> SnapshotBuffer<P> {
+        SnapshotBuffer {
+            snapshots: VecDeque::new(),
+            interval: interval as f64,
+            delay: delay as f64,
+            max_extrapolation: max_extrapolation as f64,
+            last: None,
+            stats: SnapshotStats::default()
+        }
+    }
+
+    pub fn set_delay(&mut self, delay: u64) {
+        self.delay = delay as f64;
+    }
+
+    pub fn stats(&self) -> SnapshotStats {
+        self.stats
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+        self.last = None;
+    }
+
+    /// Adds a snapshot which was received at the local time `t`.
+    pub fn push(&mut self, t: u64, sequence: u16, state: P) {
+
+        let arrival = t as f64;
+        let time = if let Some((last_sequence, last_time)) = self.last {
+
+            if !tick_is_more_recent(sequence, last_sequence) {
+                self.stats.dropped += 1;
+                return;
+            }
+
+            // Expected arrival based on the sequence, slowly corrected
+            // towards the actual arrival times to follow latency changes
+            let expected = last_time + tick_diff(sequence, last_sequence) as f64 * self.interval;
+            let lateness = arrival - expected;
+            self.stats.jitter += (lateness.abs() as f32 - self.stats.jitter) * SNAPSHOT_JITTER_SMOOTHING;
+
+            if lateness.abs() > self.interval * SNAPSHOT_RESYNC_INTERVALS {
+                self.snapshots.clear();
+                arrival
+
+            } else {
+                expected + lateness * SNAPSHOT_DRIFT_CORRECTION
+            }
+
+        } else {
+            arrival
+        };
+
+        self.last = Some((sequence, time));
+        self.snapshots.push_back((time, state));
+
+        if self.snapshots.len() > SNAPSHOT_BUFFER_SIZE {
+            self.snapshots.pop_front();
+        }
+
+    }
+
+    /// Returns the state at the local time `t` minus the buffer delay.
+    pub fn sample(&mut self, t: u64) -> Option<P> {
+
+        let render = t as f64 - self.delay;
+
+        // Keep the two snapshots surrounding the render time
+        while self.snapshots.len() > 2 && self.snapshots[1].0 <= render {
+            self.snapshots.pop_front();
+        }
+
+        let (state, extrapolating) = match self.snapshots.len() {
+            0 => return None,
+            1 => (self.snapshots[0].1.clone(), render > self.snapshots[0].0),
+            _ => {
+                let (t0, ref s0) = self.snapshots[0];
+                let (t1, ref s1) = self.snapshots[1];
+                if render <= t0 {
+                    (s0.clone(), false)
+
+                } else if render <= t1 {
+                    (s1.interpolate_from(s0, ((render - t0) / (t1 - t0)) as f32), false)
+
+                } else {
+                    let over = (render - t1).min(self.max_extrapolation);
+                    (s1.interpolate_from(s0, (1.0 + over / (t1 - t0)) as f32), true)
+                }
+            }
+        };
+
+        if extrapolating && !self.stats.extrapolating {
+            self.stats.underruns += 1;
+        }
+
+        self.stats.extrapolating = extrapolating;
+        self.stats.buffered = self.snapshots.len();
+        self.stats.buffered_time = self.snapshots.back().map_or(0.0, |s| (s.0 - render) as f32);
+
+        Some(state)
+
+    }
+
+}
+
+
+// Network Conditioning -------------------------------------------------------
+const CONDITIONER_REORDER_DELAY: u64 = 100;
+
+/// Network conditions simulated by a `NetworkConditioner`.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct NetworkConditions {
+
+    /// Base one way delay in milliseconds
+    pub latency: u64,
+
+    /// Maximum random delay in milliseconds added on top of the latency
+    pub jitter: u64,
+
+    /// Probability from 0.0 to 1.0 of a packet getting lost
+    pub loss: f32,
+
+    /// Probability from 0.0 to 1.0 of a packet being delivered twice
+    pub duplication: f32,
+
+    /// Probability from 0.0 to 1.0 of a packet being held back long enough
+    /// to arrive after packets sent later on
+    pub reordering: f32
+}
+
+impl NetworkConditions {
+    pub fn is_ideal(&self) -> bool {
+        *self == NetworkConditions::default()
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct ConditionerStats {
+    pub sent: u32,
+    pub delivered: u32,
+    pub dropped: u32,
+    pub duplicated: u32,
+    pub reordered: u32
+}
+
+/// Delays, drops, duplicates and reorders packets before they are actually
+/// sent in order to reproduce bad network conditions on a single machine.
+///
+/// Reliable packets are only ever delayed, since the transport already
+/// guarantees their delivery and order. Random decisions are taken from a
+/// seeded generator so runs can be reproduced.
+#[derive(Debug)]
+pub struct NetworkConditioner<T> {
+    conditions: NetworkConditions,
+    queue: Vec<(u64, u64, T)>,
+    sequence: u64,
+    last_reliable: u64,
+    random: u64,
+    stats: ConditionerStats
+}
+
+impl<T: Clone> NetworkConditioner<T> {
+
+    pub fn new(conditions: NetworkConditions, seed: u64) -> NetworkConditioner<T> {
+        NetworkConditioner {
+            conditions,
+            queue: Vec::new(),
+            sequence: 0,
+            last_reliable: 0,
+            random: cmp::max(seed, 1),
+            stats: ConditionerStats::default()
+        }
+    }
+
+    pub fn conditions(&self) -> NetworkConditions {
+        self.conditions
+    }
+
+    pub fn set_conditions(&mut self, conditions: NetworkConditions) {
+        self.conditions = conditions;
+    }
+
+    pub fn stats(&self) -> ConditionerStats {
+        self.stats
+    }
+
+    /// Number of packets which are still in transit.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub fn send(&mut self, t: u64, packet: T, reliable: bool) {
+
+        self.stats.sent = self.stats.sent.saturating_add(1);
+
+        if reliable {
+            // Keep reliable packets in order by never delivering them before
+            // any previously sent one
+            let delay = self.delay();
+            let deliver_at = cmp::max(t + delay, self.last_reliable);
+            self.last_reliable = deliver_at;
+            self.enqueue(deliver_at, packet);
+            return;
+        }
+
+        if self.chance(self.conditions.loss) {
+            self.stats.dropped = self.stats.dropped.saturating_add(1);
+            return;
+        }
+
+        if self.chance(self.conditions.duplication) {
+            let deliver_at = t + self.delay();
+            self.stats.duplicated = self.stats.duplicated.saturating_add(1);
+            self.enqueue(deliver_at, packet.clone());
+        }
+
+        let mut deliver_at = t + self.delay();
+        if self.chance(self.conditions.reordering) {
+            deliver_at += CONDITIONER_REORDER_DELAY / 2 + self.range(CONDITIONER_REORDER_DELAY / 2);
+            self.stats.reordered = self.stats.reordered.saturating_add(1);
+        }
+
+        self.enqueue(deliver_at, packet);
+
+    }
+
+    /// Returns the next packet due at time `t`, packets due at the same time
+    /// are returned in the order they were sent.
+    pub fn receive(&mut self, t: u64) -> Option<T> {
+
+        let next = self.queue.iter().enumerate().filter(|&(_, &(deliver_at, _, _))| {
+            deliver_at <= t
+
+        }).min_by_key(|&(_, &(deliver_at, sequence, _))| {
+            (deliver_at, sequence)
+
+        }).map(|(index, _)| index);
+
+        next.map(|index| {
+            self.stats.delivered = self.stats.delivered.saturating_add(1);
+            self.queue.swap_remove(index).2
+        })
+
+    }
+
+    /// Drops all packets in transit for which `f` returns `false`, e.g. the
+    /// ones of a closed connection.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        self.queue.retain(|item| f(&item.2));
+    }
+
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.last_reliable = 0;
+    }
+
+    fn enqueue(&mut self, deliver_at: u64, packet: T) {
+        self.queue.push((deliver_at, self.sequence, packet));
+        self.sequence = self.sequence.wrapping_add(1);
+    }
+
+    fn delay(&mut self) -> u64 {
+        let jitter = self.range(self.conditions.jitter);
+        self.conditions.latency + jitter
+    }
+
+    fn chance(&mut self, probability: f32) -> bool {
+        probability > 0.0 && (self.next_random() >> 40) as f32 / (1u64 << 24) as f32 <= probability
+    }
+
+    fn range(&mut self, max: u64) -> u64 {
+        if max > 0 {
+            self.next_random() % (max + 1)
+
+        } else {
+            0
+        }
+    }
+
+    fn next_random(&mut self) -> u64 {
+        // xorshift64*
+        self.random ^= self.random >> 12;
+        self.random ^= self.random << 25;
+        self.random ^= self.random >> 27;
+        self.random.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+}
+
+
 // Helpers --------------------------------------------------------------------
-pub fn tick_is_more_recent(a: u8, b: u8) -> bool {
-    (a > b) && (a - b <= 128) || (b > a) && (b - a > 128)
+
+/// Returns whether tick `a` is more recent than tick `b`, taking wrap around
+/// into account.
+pub fn tick_is_more_recent<T: Tick>(a: T, b: T) -> bool {
+    tick_diff(a, b) > 0
+}
+
+/// Returns the wrap around aware number of ticks from `b` to `a`.
+///
+/// For ticks which are exactly half the range apart the numerically larger
+/// one counts as the more recent one, which keeps the result antisymmetric.
+pub fn tick_diff<T: Tick>(a: T, b: T) -> i64 {
+    let range = 1i64 << T::bits();
+    let diff = (a.to_u32() as i64 - b.to_u32() as i64) & (range - 1);
+    if diff > range / 2 || diff == range / 2 && a.to_u32() < b.to_u32() {
+        diff - range
+
+    } else {
+        diff
+    }
+}
+
+/// Moves a tick by the given (possibly negative) number of ticks, wrapping
+/// around at the end of its range.
+pub fn tick_offset<T: Tick>(tick: T, offset: i64) -> T {
+    let range = 1i64 << T::bits();
+    T::from_u32(((tick.to_u32() as i64 + offset) & (range - 1)) as u32)
 }
 
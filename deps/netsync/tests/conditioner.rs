@@ -0,0 +1,207 @@
+// Library Dependencies -------------------------------------------------------
+extern crate netsync;
+use netsync::{
+    NetworkState, ClientState, ServerState, NetworkConditioner, NetworkConditions
+};
+
+
+// Mocks ----------------------------------------------------------------------
+mod mock;
+use mock::{TestInput, TestState};
+
+
+// Helpers --------------------------------------------------------------------
+fn conditions(latency: u64, jitter: u64, loss: f32, duplication: f32, reordering: f32) -> NetworkConditions {
+    NetworkConditions {
+        latency,
+        jitter,
+        loss,
+        duplication,
+        reordering
+    }
+}
+
+fn receive_all(conditioner: &mut NetworkConditioner<u32>, t: u64) -> Vec<u32> {
+    let mut packets = Vec::new();
+    while let Some(packet) = conditioner.receive(t) {
+        packets.push(packet);
+    }
+    packets
+}
+
+fn send_range(conditioner: &mut NetworkConditioner<u32>, count: u32, reliable: bool) {
+    for i in 0..count {
+        conditioner.send(i as u64, i, reliable);
+    }
+}
+
+
+// Conditioner Tests ----------------------------------------------------------
+#[test]
+fn test_ideal_conditions() {
+
+    let mut conditioner = NetworkConditioner::new(NetworkConditions::default(), 1);
+    assert!(conditioner.conditions().is_ideal());
+
+    conditioner.send(10, 1, false);
+    conditioner.send(10, 2, true);
+    conditioner.send(10, 3, false);
+    assert_eq!(receive_all(&mut conditioner, 10), vec![1, 2, 3], "should pass packets through in order");
+    assert!(conditioner.is_empty());
+
+}
+
+#[test]
+fn test_latency() {
+
+    let mut conditioner = NetworkConditioner::new(conditions(50, 0, 0.0, 0.0, 0.0), 1);
+    conditioner.send(10, 1, false);
+    conditioner.send(20, 2, true);
+
+    assert_eq!(receive_all(&mut conditioner, 59), vec![]);
+    assert_eq!(receive_all(&mut conditioner, 60), vec![1]);
+    assert_eq!(receive_all(&mut conditioner, 69), vec![]);
+    assert_eq!(receive_all(&mut conditioner, 70), vec![2]);
+
+}
+
+#[test]
+fn test_jitter() {
+
+    let mut conditioner = NetworkConditioner::new(conditions(50, 20, 0.0, 0.0, 0.0), 7);
+    for _ in 0..1000 {
+        conditioner.send(0, 0, false);
+    }
+
+    assert_eq!(receive_all(&mut conditioner, 49).len(), 0);
+
+    let early = receive_all(&mut conditioner, 59).len();
+    assert!(early > 300 && early < 700, "delays should be spread across the jitter range");
+    assert_eq!(receive_all(&mut conditioner, 70).len(), 1000 - early);
+
+}
+
+#[test]
+fn test_loss() {
+
+    let mut conditioner = NetworkConditioner::new(conditions(0, 0, 0.25, 0.0, 0.0), 3);
+    send_range(&mut conditioner, 1000, false);
+
+    let received = receive_all(&mut conditioner, 1000).len();
+    assert!(received > 700 && received < 800, "roughly a quarter of the packets should get lost");
+    assert_eq!(conditioner.stats().dropped as usize, 1000 - received);
+
+}
+
+#[test]
+fn test_duplication() {
+
+    let mut conditioner = NetworkConditioner::new(conditions(0, 0, 0.0, 0.5, 0.0), 5);
+    send_range(&mut conditioner, 1000, false);
+
+    let received = receive_all(&mut conditioner, 1000).len();
+    assert!(received > 1400 && received < 1600, "roughly half of the packets should be duplicated");
+    assert_eq!(conditioner.stats().duplicated as usize, received - 1000);
+
+}
+
+#[test]
+fn test_reordering() {
+
+    let mut conditioner = NetworkConditioner::new(conditions(0, 0, 0.0, 0.0, 0.1), 9);
+    send_range(&mut conditioner, 1000, false);
+
+    let received = receive_all(&mut conditioner, 2000);
+    assert_eq!(received.len(), 1000);
+
+    let out_of_order = received.windows(2).filter(|w| w[1] < w[0]).count();
+    assert!(out_of_order > 0, "some packets should arrive after later ones");
+    assert!(conditioner.stats().reordered > 50);
+
+}
+
+#[test]
+fn test_reliable_packets_are_only_delayed() {
+
+    let mut conditioner = NetworkConditioner::new(conditions(30, 50, 1.0, 1.0, 1.0), 11);
+    send_range(&mut conditioner, 100, true);
+
+    assert_eq!(receive_all(&mut conditioner, 29), vec![]);
+    assert_eq!(receive_all(&mut conditioner, 1000), (0..100).collect::<Vec<u32>>());
+
+    let stats = conditioner.stats();
+    assert_eq!(stats.dropped, 0);
+    assert_eq!(stats.duplicated, 0);
+    assert_eq!(stats.reordered, 0);
+
+}
+
+#[test]
+fn test_seeded_runs_are_reproducible() {
+
+    let mut a = NetworkConditioner::new(conditions(20, 40, 0.2, 0.2, 0.2), 42);
+    let mut b = NetworkConditioner::new(conditions(20, 40, 0.2, 0.2, 0.2), 42);
+    send_range(&mut a, 500, false);
+    send_range(&mut b, 500, false);
+    assert_eq!(receive_all(&mut a, 1000), receive_all(&mut b, 1000));
+
+}
+
+#[test]
+fn test_retain() {
+
+    let mut conditioner = NetworkConditioner::new(conditions(10, 0, 0.0, 0.0, 0.0), 1);
+    send_range(&mut conditioner, 10, false);
+    conditioner.retain(|packet| packet % 2 == 0);
+    assert_eq!(conditioner.len(), 5);
+    assert_eq!(receive_all(&mut conditioner, 100), vec![0, 2, 4, 6, 8]);
+
+}
+
+
+// Prediction Tests -----------------------------------------------------------
+#[test]
+fn test_prediction_converges_under_bad_conditions() {
+
+    let bad = conditions(60, 30, 0.2, 0.1, 0.1);
+    let mut upstream = NetworkConditioner::new(bad, 1);
+    let mut downstream = NetworkConditioner::new(bad, 2);
+
+    let mut client = ClientState::<TestState, TestInput>::new(30);
+    let mut server = ServerState::<TestState, TestInput>::new(30);
+
+    for tick in 0..200u32 {
+
+        let t = tick as u64 * 33;
+
+        // Move for a while and then stand still so both sides can settle
+        let buttons = if tick < 120 { (tick % 3) as u8 + 1 } else { 0 };
+        client.input(TestInput::new(tick as u8, buttons));
+        client.update_with(|state, _, input| {
+            if let Some(input) = input {
+                state.x = state.x.wrapping_add(input.buttons);
+            }
+        });
+        upstream.send(t, client.send(), false);
+
+        while let Some(inputs) = upstream.receive(t) {
+            server.receive(&inputs);
+        }
+
+        server.update_with(|state, _, input| {
+            if let Some(input) = input {
+                state.x = state.x.wrapping_add(input.buttons);
+            }
+        });
+        downstream.send(t, server.send(None), false);
+
+        while let Some(state) = downstream.receive(t) {
+            client.receive_confirmed(&state);
+        }
+
+    }
+
+    assert!(upstream.stats().dropped > 0 && downstream.stats().dropped > 0);
+    assert_eq!(client.interpolate(1.0), server.get_relative(0), "prediction should match the server");
+
+}
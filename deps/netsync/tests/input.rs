@@ -0,0 +1,235 @@
+// Library Dependencies -------------------------------------------------------
+extern crate netsync;
+use netsync::{NetworkState, ClientState, ServerState};
+
+
+// Mocks ----------------------------------------------------------------------
+mod mock;
+use mock::{TestInput, TestState};
+
+
+// Helpers --------------------------------------------------------------------
+fn decode_inputs(bytes: &[u8]) -> Vec<(u8, u8)> {
+    bytes.chunks(2).map(|c| (c[0], c[1])).collect()
+}
+
+fn server_update(server: &mut ServerState<TestState, TestInput>) -> Vec<(u8, u8)> {
+    let mut applied = Vec::new();
+    server.update_with(|state, _, input| {
+        let input = input.unwrap();
+        state.x = state.x.wrapping_add(input.buttons);
+        applied.push((input.tick, input.buttons));
+    });
+    applied
+}
+
+fn server_updates(server: &mut ServerState<TestState, TestInput>, ticks: usize) -> Vec<(u8, u8)> {
+    let mut applied = Vec::new();
+    for _ in 0..ticks {
+        applied.append(&mut server_update(server));
+    }
+    applied
+}
+
+fn server_ack(server: &ServerState<TestState, TestInput>, client: &mut ClientState<TestState, TestInput>) {
+    let bytes = server.send(None);
+    client.receive(&bytes[1..], Some(bytes[0]));
+}
+
+
+// Client Tests ---------------------------------------------------------------
+#[test]
+fn test_client_send_unacknowledged() {
+
+    let mut client = ClientState::<TestState, TestInput>::new(30);
+    for tick in 0..5 {
+        client.input(TestInput::new(tick, 1));
+    }
+
+    assert_eq!(client.acked_tick(), None);
+    assert_eq!(decode_inputs(&client.send()), vec![(0, 1), (1, 1), (2, 1), (3, 1), (4, 1)], "should send all inputs before any ack");
+
+    client.receive(&[0], Some(2));
+    assert_eq!(client.acked_tick(), Some(2));
+    assert_eq!(decode_inputs(&client.send()), vec![(3, 1), (4, 1)], "should not send acknowledged inputs");
+
+}
+
+#[test]
+fn test_client_ignore_outdated_ack() {
+
+    let mut client = ClientState::<TestState, TestInput>::new(30);
+    for tick in 0..5 {
+        client.input(TestInput::new(tick, 1));
+    }
+
+    client.receive(&[0], Some(3));
+    client.receive(&[0], Some(1));
+    assert_eq!(client.acked_tick(), Some(3), "should ignore acks which arrive out of order");
+    assert_eq!(decode_inputs(&client.send()), vec![(4, 1)]);
+
+}
+
+#[test]
+fn test_client_ack_wrap_around() {
+
+    let mut client = ClientState::<TestState, TestInput>::new(30);
+    for tick in 250..256 {
+        client.input(TestInput::new(tick as u8, 1));
+    }
+
+    for tick in 0..3 {
+        client.input(TestInput::new(tick, 2));
+    }
+
+    client.receive(&[0], Some(254));
+    assert_eq!(decode_inputs(&client.send()), vec![(255, 1), (0, 2), (1, 2), (2, 2)], "should handle acks across tick wrap around");
+
+    client.receive(&[0], Some(0));
+    assert_eq!(decode_inputs(&client.send()), vec![(1, 2), (2, 2)]);
+
+}
+
+#[test]
+fn test_client_redundancy_window() {
+
+    let mut client = ClientState::<TestState, TestInput>::new(30);
+    client.set_input_redundancy(3);
+    for tick in 0..10 {
+        client.input(TestInput::new(tick, tick));
+    }
+
+    assert_eq!(decode_inputs(&client.send()), vec![(7, 7), (8, 8), (9, 9)], "should only send the most recent inputs");
+
+    client.set_input_redundancy(0);
+    assert_eq!(decode_inputs(&client.send()), vec![(9, 9)], "should always send at least the latest input");
+
+}
+
+
+// Server Tests ---------------------------------------------------------------
+#[test]
+fn test_server_ignore_duplicate_inputs() {
+
+    let mut server = ServerState::<TestState, TestInput>::new(30);
+    server.receive(&[0, 1, 1, 1, 2, 1]);
+    server.receive(&[1, 1, 2, 1, 3, 1]);
+
+    assert_eq!(server_updates(&mut server, 4), vec![(0, 1), (1, 1), (2, 1), (3, 1)], "should apply each input exactly once");
+
+}
+
+#[test]
+fn test_server_fill_input_gap() {
+
+    let mut server = ServerState::<TestState, TestInput>::new(30);
+    server.receive(&[0, 4]);
+    server.receive(&[1, 5]);
+    server.receive(&[4, 7]);
+
+    assert_eq!(
+        server_updates(&mut server, 5),
+        vec![(0, 4), (1, 5), (2, 5), (3, 5), (4, 7)],
+        "should repeat the last input for missing ticks"
+    );
+
+    assert_eq!(server.send(None), vec![4, 26], "should confirm the most recent input");
+
+}
+
+#[test]
+fn test_server_fill_input_gap_wrap_around() {
+
+    let mut server = ServerState::<TestState, TestInput>::new(30);
+    server.receive(&[254, 1]);
+    server.receive(&[1, 2]);
+
+    assert_eq!(server_updates(&mut server, 4), vec![(254, 1), (255, 1), (0, 1), (1, 2)]);
+
+}
+
+#[test]
+fn test_server_max_input_gap() {
+
+    let mut server = ServerState::<TestState, TestInput>::new(30);
+    server.set_max_input_gap(2);
+    server.receive(&[0, 1]);
+    server.receive(&[3, 2]);
+    server.receive(&[10, 3]);
+
+    assert_eq!(
+        server_updates(&mut server, 5),
+        vec![(0, 1), (1, 1), (2, 1), (3, 2), (10, 3)],
+        "should not fill gaps exceeding the maximum"
+    );
+
+}
+
+
+// Packet Loss Tests ----------------------------------------------------------
+#[test]
+fn test_packet_loss_within_redundancy() {
+
+    let mut client = ClientState::<TestState, TestInput>::new(30);
+    let mut server = ServerState::<TestState, TestInput>::new(30);
+    client.set_input_redundancy(4);
+
+    let mut applied = Vec::new();
+    for tick in 0..60u8 {
+
+        client.input(TestInput::new(tick, 1));
+
+        // Drop two out of every three client packets
+        let bytes = client.send();
+        if tick % 3 == 0 {
+            server.receive(&bytes);
+        }
+
+        applied.append(&mut server_update(&mut server));
+
+        // Drop every other server packet
+        if tick % 2 == 0 {
+            server_ack(&server, &mut client);
+        }
+
+    }
+
+    let expected: Vec<(u8, u8)> = (0..58).map(|tick| (tick, 1)).collect();
+    assert_eq!(&applied[..58], &expected[..], "should recover all lost inputs via redundancy");
+
+}
+
+#[test]
+fn test_packet_loss_burst_exceeding_redundancy() {
+
+    let mut client = ClientState::<TestState, TestInput>::new(30);
+    let mut server = ServerState::<TestState, TestInput>::new(30);
+    client.set_input_redundancy(2);
+
+    let mut applied = Vec::new();
+    for tick in 0..20u8 {
+
+        client.input(TestInput::new(tick, if tick < 5 { 1 } else { 2 }));
+
+        // Lose a burst of packets which is longer than the redundancy window
+        let bytes = client.send();
+        if !(5..10).contains(&tick) {
+            server.receive(&bytes);
+        }
+
+        applied.append(&mut server_update(&mut server));
+        server_ack(&server, &mut client);
+
+    }
+
+    let ticks: Vec<u8> = applied.iter().map(|&(tick, _)| tick).collect();
+    assert_eq!(ticks, (0..20).collect::<Vec<u8>>(), "should apply an input for every tick");
+
+    // Ticks 5 to 9 never reached the server in time and were substituted
+    // with the last known input
+    assert_eq!(&applied[5..10], &[(5, 1), (6, 1), (7, 1), (8, 1), (9, 1)]);
+    assert_eq!(applied[10], (10, 2));
+    assert_eq!(client.acked_tick(), Some(19));
+    assert!(client.send().is_empty(), "should have no unacknowledged inputs left");
+
+}
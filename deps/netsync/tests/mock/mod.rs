@@ -0,0 +1,163 @@
+#![allow(dead_code)]
+
+// Library Dependencies -------------------------------------------------------
+extern crate netsync;
+use netsync::{NetworkInput, NetworkProperty};
+
+
+// Mocks ----------------------------------------------------------------------
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub struct TestInput {
+    pub tick: u8,
+    pub buttons: u8
+}
+
+impl TestInput {
+    pub fn new(tick: u8, buttons: u8) -> TestInput {
+        TestInput {
+            tick,
+            buttons
+        }
+    }
+}
+
+impl NetworkInput for TestInput {
+
+    type Tick = u8;
+
+    fn tick(&self) -> u8 {
+        self.tick
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        vec![self.tick, self.buttons]
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<(usize, Self)> where Self: Sized {
+        if bytes.len() >= 2 {
+            Some((2, TestInput {
+                tick: bytes[0],
+                buttons: bytes[1]
+            }))
+
+        } else {
+            None
+        }
+    }
+
+    fn repeat(&self, tick: u8) -> Self where Self: Sized {
+        TestInput {
+            tick,
+            buttons: self.buttons
+        }
+    }
+
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub struct WideInput {
+    pub tick: u16,
+    pub buttons: u8
+}
+
+impl WideInput {
+    pub fn new(tick: u16, buttons: u8) -> WideInput {
+        WideInput {
+            tick,
+            buttons
+        }
+    }
+}
+
+impl NetworkInput for WideInput {
+
+    type Tick = u16;
+
+    fn tick(&self) -> u16 {
+        self.tick
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        vec![self.tick as u8, (self.tick >> 8) as u8, self.buttons]
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<(usize, Self)> where Self: Sized {
+        if bytes.len() >= 3 {
+            Some((3, WideInput {
+                tick: bytes[0] as u16 | (bytes[1] as u16) << 8,
+                buttons: bytes[2]
+            }))
+
+        } else {
+            None
+        }
+    }
+
+    fn repeat(&self, tick: u16) -> Self where Self: Sized {
+        WideInput {
+            tick,
+            buttons: self.buttons
+        }
+    }
+
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+pub struct TestState {
+    pub x: u8
+}
+
+impl NetworkProperty for TestState {
+
+    fn interpolate_from(&self, last: &Self, u: f32) -> Self {
+        TestState {
+            x: (last.x as f32 + (self.x as f32 - last.x as f32) * u) as u8
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        vec![self.x]
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self where Self: Sized {
+        TestState {
+            x: bytes[0]
+        }
+    }
+
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct SmoothState {
+    pub x: f32
+}
+
+impl NetworkProperty for SmoothState {
+
+    fn interpolate_from(&self, last: &Self, u: f32) -> Self {
+        SmoothState {
+            x: last.x + (self.x - last.x) * u
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        vec![self.x as u8]
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self where Self: Sized {
+        SmoothState {
+            x: bytes[0] as f32
+        }
+    }
+
+    fn error_distance(&self, other: &Self) -> f32 {
+        (self.x - other.x).abs()
+    }
+
+    fn apply_error(&self, from: &Self, to: &Self, u: f32) -> Self {
+        SmoothState {
+            x: self.x + (from.x - to.x) * u
+        }
+    }
+
+}
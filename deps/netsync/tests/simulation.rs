@@ -0,0 +1,158 @@
+// Library Dependencies -------------------------------------------------------
+extern crate netsync;
+use netsync::{NetworkState, ServerState, MissingInputPolicy};
+
+
+// Mocks ----------------------------------------------------------------------
+mod mock;
+use mock::{TestInput, TestState};
+
+
+// Helpers --------------------------------------------------------------------
+fn server_update(server: &mut ServerState<TestState, TestInput>) -> Vec<Option<(u8, u8)>> {
+    let mut applied = Vec::new();
+    server.update_with(|state, _, input| {
+        if let Some(input) = input {
+            state.x = state.x.wrapping_add(input.buttons);
+            applied.push(Some((input.tick, input.buttons)));
+
+        } else {
+            state.x = state.x.wrapping_add(100);
+            applied.push(None);
+        }
+    });
+    applied
+}
+
+fn confirmed_tick(server: &ServerState<TestState, TestInput>) -> u8 {
+    server.send(None)[0]
+}
+
+
+// Missing Input Policies -----------------------------------------------------
+#[test]
+fn test_repeat_last_input() {
+
+    let mut server = ServerState::<TestState, TestInput>::new(30);
+    server.receive(&[0, 3]);
+
+    assert_eq!(server_update(&mut server), vec![Some((0, 3))]);
+    assert_eq!(server_update(&mut server), vec![Some((1, 3))], "should repeat the last input");
+    assert_eq!(server_update(&mut server), vec![Some((2, 3))]);
+    assert_eq!(server.get_relative(0).x, 9);
+    assert_eq!(confirmed_tick(&server), 2, "should confirm substituted ticks");
+
+}
+
+#[test]
+fn test_zero_input() {
+
+    let mut server = ServerState::<TestState, TestInput>::new(30);
+    server.set_missing_input_policy(MissingInputPolicy::Zero);
+    server.receive(&[0, 3]);
+
+    assert_eq!(server_update(&mut server), vec![Some((0, 3))]);
+    assert_eq!(server_update(&mut server), vec![Some((1, 0))], "should apply a default input");
+    assert_eq!(server.get_relative(0).x, 3);
+
+}
+
+#[test]
+fn test_extrapolate() {
+
+    let mut server = ServerState::<TestState, TestInput>::new(30);
+    server.set_missing_input_policy(MissingInputPolicy::Extrapolate);
+    server.receive(&[0, 3]);
+
+    assert_eq!(server_update(&mut server), vec![Some((0, 3))]);
+    assert_eq!(server_update(&mut server), vec![None], "should update without input");
+    assert_eq!(server.get_relative(0).x, 103);
+    assert_eq!(confirmed_tick(&server), 1);
+
+}
+
+
+// Simulation -----------------------------------------------------------------
+#[test]
+fn test_single_input_per_update() {
+
+    let mut server = ServerState::<TestState, TestInput>::new(30);
+    server.receive(&[0, 1, 1, 2, 2, 3]);
+
+    assert_eq!(server_update(&mut server), vec![Some((0, 1))], "should apply a single input per update");
+    assert_eq!(confirmed_tick(&server), 0);
+    assert_eq!(server_update(&mut server), vec![Some((1, 2))], "should keep the remaining inputs for later updates");
+    assert_eq!(server_update(&mut server), vec![Some((2, 3))]);
+    assert_eq!(confirmed_tick(&server), 2);
+
+    let states: Vec<u8> = (1..4).map(|ticks_ago| server.get_relative(ticks_ago).x).collect();
+    assert_eq!(states, vec![6, 3, 1], "should buffer a state for every applied input");
+
+}
+
+#[test]
+fn test_advance_before_first_input() {
+
+    let mut server = ServerState::<TestState, TestInput>::new(30);
+    assert_eq!(server_update(&mut server), vec![Some((0, 0))], "should advance without any input");
+    assert_eq!(server_update(&mut server), vec![Some((0, 0))]);
+
+    // Ticks do not advance until the client sent its first input
+    server.receive(&[0, 2]);
+    assert_eq!(server_update(&mut server), vec![Some((0, 2))]);
+
+}
+
+#[test]
+fn test_ignore_late_input() {
+
+    let mut server = ServerState::<TestState, TestInput>::new(30);
+    server.receive(&[0, 1]);
+    server_update(&mut server);
+    server_update(&mut server);
+
+    // Tick 1 was already substituted
+    server.receive(&[1, 5, 2, 5]);
+    assert_eq!(server_update(&mut server), vec![Some((2, 5))], "should apply each tick exactly once");
+    assert_eq!(server.get_relative(0).x, 7);
+
+}
+
+#[test]
+fn test_stalled_client() {
+
+    let mut server = ServerState::<TestState, TestInput>::new(30);
+    server.set_max_input_gap(2);
+    server.receive(&[0, 1]);
+
+    let mut applied = Vec::new();
+    for _ in 0..5 {
+        applied.append(&mut server_update(&mut server));
+    }
+
+    assert_eq!(
+        applied,
+        vec![Some((0, 1)), Some((1, 1)), Some((2, 1)), Some((2, 0)), Some((2, 0))],
+        "should stop repeating inputs and advancing ticks after the maximum gap"
+    );
+
+    // Resuming clients are accepted again
+    server.receive(&[20, 4]);
+    assert_eq!(server_update(&mut server), vec![Some((20, 4))]);
+    assert_eq!(server_update(&mut server), vec![Some((21, 4))]);
+
+}
+
+#[test]
+fn test_state_buffer_advances_every_tick() {
+
+    let mut server = ServerState::<TestState, TestInput>::new(30);
+    server.receive(&[0, 3]);
+    for _ in 0..4 {
+        server_update(&mut server);
+    }
+
+    let states: Vec<u8> = (1..5).map(|ticks_ago| server.get_relative(ticks_ago).x).collect();
+    assert_eq!(states, vec![12, 9, 6, 3], "should buffer a distinct state for every tick");
+
+}
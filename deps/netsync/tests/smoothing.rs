@@ -0,0 +1,135 @@
+// Library Dependencies -------------------------------------------------------
+extern crate netsync;
+use netsync::{NetworkState, ClientState};
+
+
+// Mocks ----------------------------------------------------------------------
+mod mock;
+use mock::{TestInput, SmoothState};
+
+
+// Helpers --------------------------------------------------------------------
+fn predict(client: &mut ClientState<SmoothState, TestInput>, tick: u8) -> f32 {
+    client.input(TestInput::new(tick, 1));
+    client.update_with(|state, _, input| {
+        if let Some(input) = input {
+            state.x += input.buttons as f32;
+        }
+    });
+    client.interpolate(1.0).x
+}
+
+fn confirm(client: &mut ClientState<SmoothState, TestInput>, tick: u8, x: u8) {
+    client.receive(&[x], Some(tick));
+}
+
+fn client(smoothing_ticks: usize, snap_distance: f32) -> ClientState<SmoothState, TestInput> {
+    let mut client = ClientState::new(30);
+    client.set_error_smoothing(smoothing_ticks, snap_distance);
+    for tick in 1..4 {
+        predict(&mut client, tick);
+    }
+    client
+}
+
+
+// Smoothing Tests ------------------------------------------------------------
+#[test]
+fn test_correction_without_smoothing() {
+
+    let mut client = client(0, 100.0);
+    confirm(&mut client, 2, 12);
+
+    // Confirmed tick 2 at 12 plus the replayed inputs of ticks 3 and 4
+    assert_eq!(predict(&mut client, 4), 14.0);
+
+    let stats = client.prediction_stats();
+    assert_eq!(stats.corrections, 1);
+    assert_eq!(stats.snaps, 0);
+    assert_eq!(stats.last_error, 10.0);
+
+}
+
+#[test]
+fn test_correction_decays_over_time() {
+
+    let mut client = client(4, 100.0);
+    confirm(&mut client, 2, 12);
+
+    // The displayed state continues where the old prediction would have been
+    assert_eq!(predict(&mut client, 4), 4.0);
+    assert_eq!(client.interpolate(0.0).x, 3.0);
+
+    assert_eq!(predict(&mut client, 5), 15.0 - 7.5);
+    assert_eq!(client.interpolate(0.5).x, 14.5 - 8.75);
+
+    assert_eq!(predict(&mut client, 6), 16.0 - 5.0);
+    assert_eq!(predict(&mut client, 7), 17.0 - 2.5);
+    assert_eq!(predict(&mut client, 8), 18.0);
+    assert_eq!(predict(&mut client, 9), 19.0);
+
+    assert_eq!(client.prediction_stats().corrections, 1);
+
+}
+
+#[test]
+fn test_large_errors_snap() {
+
+    let mut client = client(4, 5.0);
+    confirm(&mut client, 2, 12);
+    assert_eq!(predict(&mut client, 4), 14.0);
+
+    let stats = client.prediction_stats();
+    assert_eq!(stats.corrections, 1);
+    assert_eq!(stats.snaps, 1);
+
+}
+
+#[test]
+fn test_matching_prediction_is_no_correction() {
+
+    let mut client = client(4, 100.0);
+    confirm(&mut client, 2, 2);
+    assert_eq!(predict(&mut client, 4), 4.0);
+    assert_eq!(client.interpolate(0.5).x, 3.5);
+
+    let stats = client.prediction_stats();
+    assert_eq!(stats.corrections, 0);
+    assert_eq!(stats.max_error, 0.0);
+
+}
+
+#[test]
+fn test_corrections_are_folded() {
+
+    let mut client = client(4, 100.0);
+    confirm(&mut client, 2, 12);
+    assert_eq!(predict(&mut client, 4), 4.0);
+    assert_eq!(predict(&mut client, 5), 7.5);
+
+    // Tick 4 was predicted at 14 but confirmed at 10, the remaining offset of
+    // the first correction is kept on top of the new one
+    confirm(&mut client, 4, 10);
+    assert_eq!(predict(&mut client, 6), 12.0 + 4.0 - 7.5);
+    assert_eq!(client.interpolate(0.0).x, 7.5);
+    assert_eq!(predict(&mut client, 7), 13.0 + (4.0 - 7.5) * 0.75);
+
+    let stats = client.prediction_stats();
+    assert_eq!(stats.corrections, 2);
+    assert_eq!(stats.last_error, 4.0);
+    assert_eq!(stats.max_error, 10.0);
+    assert_eq!(stats.average_error, 10.0 - 0.6);
+
+}
+
+#[test]
+fn test_set_clears_offset() {
+
+    let mut client = client(4, 100.0);
+    confirm(&mut client, 2, 12);
+    predict(&mut client, 4);
+
+    client.set(SmoothState { x: 50.0 });
+    assert_eq!(client.interpolate(1.0).x, 50.0);
+
+}
@@ -0,0 +1,159 @@
+// Library Dependencies -------------------------------------------------------
+extern crate netsync;
+use netsync::{NetworkState, ServerState, SnapshotBuffer};
+
+
+// Mocks ----------------------------------------------------------------------
+mod mock;
+use mock::{TestInput, TestState};
+
+
+// Helpers --------------------------------------------------------------------
+fn state(x: u8) -> TestState {
+    TestState { x }
+}
+
+fn sample_x(buffer: &mut SnapshotBuffer<TestState>, t: u64) -> u8 {
+    buffer.sample(t).unwrap().x
+}
+
+
+// Buffer Tests ---------------------------------------------------------------
+#[test]
+fn test_empty_buffer() {
+    let mut buffer = SnapshotBuffer::<TestState>::new(10, 20, 30);
+    assert_eq!(buffer.sample(1000), None);
+}
+
+#[test]
+fn test_interpolate_at_delay() {
+
+    let mut buffer = SnapshotBuffer::new(10, 20, 30);
+    for i in 0..5 {
+        buffer.push(1000 + i * 10, i as u16, state(i as u8 * 10));
+    }
+
+    assert_eq!(sample_x(&mut buffer, 1020), 0, "should render delayed states");
+    assert_eq!(sample_x(&mut buffer, 1025), 5);
+    assert_eq!(sample_x(&mut buffer, 1040), 20);
+    assert_eq!(buffer.stats().buffered_time, 20.0);
+    assert!(!buffer.stats().extrapolating);
+
+}
+
+#[test]
+fn test_smooth_jitter() {
+
+    // Snapshots are sent every 10ms but arrive with up to 8ms of jitter
+    let mut buffer = SnapshotBuffer::new(10, 20, 30);
+    let jitter = [0, 8, 2, 7, 0, 5, 1, 8, 3, 0];
+    for i in 0..10 {
+        buffer.push(1000 + i * 10 + jitter[i as usize], i as u16, state(i as u8 * 10));
+    }
+
+    // The timeline follows the sequence numbers rather than arrival times
+    let mut last = sample_x(&mut buffer, 1030);
+    for t in 1031..1100 {
+        let x = sample_x(&mut buffer, t);
+        assert!(x >= last && x - last <= 2, "should move steadily despite jitter");
+        last = x;
+    }
+
+    assert_eq!(buffer.stats().underruns, 0);
+    assert!(buffer.stats().jitter > 0.0);
+
+}
+
+#[test]
+fn test_extrapolation_limit() {
+
+    let mut buffer = SnapshotBuffer::new(10, 20, 30);
+    buffer.push(1000, 0, state(10));
+    buffer.push(1010, 1, state(20));
+
+    assert_eq!(sample_x(&mut buffer, 1030), 20);
+    assert!(!buffer.stats().extrapolating);
+
+    assert_eq!(sample_x(&mut buffer, 1040), 30, "should extrapolate when running dry");
+    assert!(buffer.stats().extrapolating);
+    assert_eq!(buffer.stats().underruns, 1);
+
+    assert_eq!(sample_x(&mut buffer, 1100), 50, "should limit extrapolation");
+    assert_eq!(buffer.stats().underruns, 1, "should count each underrun once");
+
+    buffer.push(1105, 10, state(120));
+    sample_x(&mut buffer, 1110);
+    assert!(!buffer.stats().extrapolating);
+
+}
+
+#[test]
+fn test_lost_snapshots() {
+
+    let mut buffer = SnapshotBuffer::new(10, 20, 30);
+    buffer.push(1000, 0, state(0));
+    buffer.push(1030, 3, state(30));
+
+    assert_eq!(sample_x(&mut buffer, 1030), 10, "should interpolate across missing sequence numbers");
+
+}
+
+#[test]
+fn test_drop_out_of_order() {
+
+    let mut buffer = SnapshotBuffer::new(10, 20, 30);
+    buffer.push(1000, 5, state(50));
+    buffer.push(1010, 6, state(60));
+    buffer.push(1012, 4, state(40));
+    buffer.push(1013, 6, state(60));
+
+    assert_eq!(buffer.stats().dropped, 2);
+    assert_eq!(sample_x(&mut buffer, 1020), 50);
+
+}
+
+#[test]
+fn test_sequence_wrap_around() {
+
+    let mut buffer = SnapshotBuffer::new(10, 0, 30);
+    buffer.push(1000, 65535, state(0));
+    buffer.push(1010, 0, state(10));
+    buffer.push(1020, 1, state(20));
+
+    assert_eq!(buffer.stats().dropped, 0);
+    assert_eq!(sample_x(&mut buffer, 1015), 15);
+
+}
+
+#[test]
+fn test_resync_on_latency_spike() {
+
+    let mut buffer = SnapshotBuffer::new(10, 20, 30);
+    buffer.push(1000, 0, state(0));
+    buffer.push(1010, 1, state(10));
+    buffer.push(2000, 2, state(20));
+
+    assert_eq!(sample_x(&mut buffer, 2020), 20, "should restart the timeline after large gaps");
+
+}
+
+
+// Server Tests ---------------------------------------------------------------
+#[test]
+fn test_server_snapshot_sequence() {
+
+    let mut server = ServerState::<TestState, TestInput>::new(30);
+    for i in 0..10 {
+        server.receive(&[i, 1]);
+        server.update_with(|state, _, input| {
+            state.x += input.unwrap().buttons;
+        });
+    }
+
+    let bytes = server.send_snapshot_with(4, |_| {});
+    assert_eq!(bytes, vec![6, 0, 7], "should prefix the sequence number of the delayed state");
+
+    let bytes = server.send_snapshot_with(0, |state| state.x = 0);
+    assert_eq!(bytes, vec![10, 0, 0]);
+
+}
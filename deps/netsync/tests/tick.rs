@@ -0,0 +1,177 @@
+// Library Dependencies -------------------------------------------------------
+extern crate netsync;
+use netsync::{Tick, NetworkInput, NetworkState, ClientState, ServerState, tick_diff, tick_is_more_recent, tick_offset};
+
+
+// Mocks ----------------------------------------------------------------------
+mod mock;
+use mock::{TestInput, TestState, WideInput};
+
+
+// Helpers --------------------------------------------------------------------
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn check_properties<T: Tick>(rng: &mut XorShift) {
+
+    let half = 1i64 << (T::bits() - 1);
+    for _ in 0..10000 {
+
+        let a = T::from_u32(rng.next() as u32);
+        let b = T::from_u32(rng.next() as u32);
+        let d = tick_diff(a, b);
+
+        assert!(d >= -half && d <= half, "difference should lie within half the tick range");
+        assert_eq!(tick_offset(b, d), a, "offsetting by the difference should yield the other tick");
+        assert_eq!(tick_diff(b, a), -d, "difference should be antisymmetric");
+        assert_eq!(tick_is_more_recent(a, b), d > 0);
+
+        // Crossing the wrap boundary must not change the distance
+        let n = (rng.next() % half as u64) as i64;
+        let start = tick_offset(T::from_u32(0), -(n / 2));
+        let end = tick_offset(start, n);
+        assert_eq!(tick_diff(end, start), n);
+        assert_eq!(tick_diff(start, end), -n);
+
+    }
+
+}
+
+fn client_update<I: NetworkInput>(client: &mut ClientState<TestState, I>) -> TestState {
+    client.update_with(|_, _, _| {});
+    client.interpolate(1.0)
+}
+
+
+// Comparison Tests -----------------------------------------------------------
+#[test]
+fn test_u8_matches_legacy_comparison() {
+    for a in 0..256u16 {
+        for b in 0..256u16 {
+            let (a, b) = (a as u8, b as u8);
+            let legacy = (a > b) && (a - b <= 128) || (b > a) && (b - a > 128);
+            assert_eq!(tick_is_more_recent(a, b), legacy, "{} vs {}", a, b);
+        }
+    }
+}
+
+#[test]
+fn test_diff_across_wrap() {
+    assert_eq!(tick_diff(2u8, 254u8), 4);
+    assert_eq!(tick_diff(254u8, 2u8), -4);
+    assert_eq!(tick_diff(3u16, 65533u16), 6);
+    assert_eq!(tick_diff(0u32, 4294967295u32), 1);
+    assert_eq!(tick_offset(65534u16, 5), 3);
+    assert_eq!(tick_offset(3u16, -5), 65534);
+}
+
+#[test]
+fn test_properties_u8() {
+    check_properties::<u8>(&mut XorShift(0x2545_f491_4f6c_dd1d));
+}
+
+#[test]
+fn test_properties_u16() {
+    check_properties::<u16>(&mut XorShift(0x9e37_79b9_7f4a_7c15));
+}
+
+#[test]
+fn test_properties_u32() {
+    check_properties::<u32>(&mut XorShift(0xbf58_476d_1ce4_e5b9));
+}
+
+#[test]
+fn test_byte_roundtrip() {
+    let mut rng = XorShift(0x94d0_49bb_1331_11eb);
+    for _ in 0..1000 {
+        let value = rng.next() as u32;
+        assert_eq!(u32::from_bytes(&value.to_bytes()), Some((4, value)));
+        assert_eq!(u16::from_bytes(&(value as u16).to_bytes()), Some((2, value as u16)));
+        assert_eq!(u8::from_bytes(&(value as u8).to_bytes()), Some((1, value as u8)));
+    }
+    assert_eq!(u16::from_bytes(&[1]), None);
+    assert_eq!(u32::from_bytes(&[1, 2, 3]), None);
+}
+
+
+// State Tests ----------------------------------------------------------------
+#[test]
+fn test_client_reject_outdated_state() {
+
+    let mut client = ClientState::<TestState, TestInput>::new(30);
+    for tick in 0..5 {
+        client.input(TestInput::new(tick, 1));
+    }
+
+    client.receive(&[30], Some(3));
+    client.receive(&[10], Some(1));
+    assert_eq!(client.acked_tick(), Some(3));
+    assert_eq!(client_update(&mut client), TestState { x: 30 }, "should not roll back to an outdated confirmed state");
+
+    client.receive(&[40], Some(3));
+    assert_eq!(client_update(&mut client), TestState { x: 40 }, "should accept a repeated confirmation of the same tick");
+
+}
+
+#[test]
+fn test_client_reject_outdated_state_across_wrap() {
+
+    let mut client = ClientState::<TestState, WideInput>::new(30);
+    for tick in 65530..65536 {
+        client.input(WideInput::new(tick as u16, 1));
+    }
+    for tick in 0..4 {
+        client.input(WideInput::new(tick, 1));
+    }
+
+    client.receive(&[20], Some(2));
+    client.receive(&[10], Some(65534));
+    assert_eq!(client.acked_tick(), Some(2), "older ticks from before the wrap should be rejected");
+    assert_eq!(client_update(&mut client), TestState { x: 20 });
+
+}
+
+#[test]
+fn test_wide_ticks_across_wrap() {
+
+    let mut client = ClientState::<TestState, WideInput>::new(30);
+    let mut server = ServerState::<TestState, WideInput>::new(30);
+    let mut applied = Vec::new();
+
+    // Drop every third packet to force gap filling across the wrap boundary
+    for i in 0..40u32 {
+
+        let tick = 65520u16.wrapping_add(i as u16);
+        client.input(WideInput::new(tick, 1));
+
+        let bytes = client.send();
+        if i % 3 != 1 {
+            server.receive(&bytes);
+        }
+
+        server.update_with(|state, _, input| {
+            let input = input.unwrap();
+            state.x = state.x.wrapping_add(input.buttons);
+            applied.push(input.tick);
+        });
+
+        client.receive_confirmed(&server.send(None));
+        assert_eq!(client.acked_tick(), Some(applied[applied.len() - 1]));
+
+    }
+
+    let expected: Vec<u16> = (0..40u32).map(|i| 65520u16.wrapping_add(i as u16)).collect();
+    assert_eq!(applied, expected, "should apply every tick exactly once in order");
+    assert_eq!(server.get_client_tick_diff(65535, 0), 24);
+    assert_eq!(server.get_client_tick_diff(4, 2), 21);
+    assert_eq!(server.get_client_tick_diff(30, 2), 2, "should not report ticks ahead of the server");
+
+}
@@ -0,0 +1,360 @@
+// STD Dependencies -----------------------------------------------------------
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+
+// External Dependencies ------------------------------------------------------
+use rustc_serialize::json;
+
+
+// Internal Dependencies ------------------------------------------------------
+use shared::entity::PLAYER_RADIUS;
+use shared::level::{
+    Level, LevelCollision, LevelNavigationGrid, LevelVisibility,
+    NAVIGATION_GRID_SPACING
+};
+
+
+// Statics --------------------------------------------------------------------
+const ANALYSIS_MAX_GAP: f32 = PLAYER_RADIUS * 2.0;
+
+
+// Map Analysis ---------------------------------------------------------------
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Format {
+    Text,
+    Json
+}
+
+#[derive(Debug, RustcEncodable)]
+pub struct Report {
+    pub file: String,
+    pub cell_size: f32,
+    pub walkable_area: f32,
+    pub reachable_area: f32,
+    pub light_coverage: f32,
+    pub spawns: Vec<SpawnReport>,
+    pub spawn_sight_lines: Vec<SpawnSightLine>,
+    pub unreachable_regions: Vec<Region>,
+    pub wall_gaps: Vec<WallGap>,
+    pub invalid_solids: Vec<InvalidSolid>
+}
+
+#[derive(Debug, RustcEncodable)]
+pub struct SpawnReport {
+    pub x: f32,
+    pub y: f32,
+    pub reachable_area: f32,
+    pub reachable_percent: f32
+}
+
+#[derive(Debug, RustcEncodable)]
+pub struct SpawnSightLine {
+    pub a: usize,
+    pub b: usize,
+    pub distance: f32
+}
+
+#[derive(Debug, RustcEncodable)]
+pub struct Region {
+    pub x: f32,
+    pub y: f32,
+    pub area: f32
+}
+
+#[derive(Debug, RustcEncodable)]
+pub struct WallGap {
+    pub wall: usize,
+    pub other: usize,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32
+}
+
+#[derive(Debug, RustcEncodable)]
+pub struct InvalidSolid {
+    pub solid: usize,
+    pub error: String
+}
+
+pub fn analyze(input: &Path, output: Option<&Path>, format: Format) {
+
+    let mut toml = String::new();
+    if let Err(err) = File::open(input).and_then(|mut f| f.read_to_string(&mut toml)) {
+        println!("[Editor] Failed to open {}: {}", input.display(), err);
+        return;
+    }
+
+    let level = Level::from_toml_string(&toml);
+    if level.walls.is_empty() {
+        println!("[Editor] No walls found in {}", input.display());
+        return;
+    }
+
+    let report = Report::new(input, &level);
+    let text = match format {
+        Format::Text => report.to_text(),
+        Format::Json => format!("{}\n", json::as_pretty_json(&report))
+    };
+
+    if let Some(output) = output {
+        if let Ok(mut file) = File::create(output) {
+            write!(file, "{}", text).expect("Failed to write map analysis.");
+            println!("[Editor] Analyzed {} -> {}", input.display(), output.display());
+
+        } else {
+            println!("[Editor] Failed to create {}", output.display());
+        }
+
+    } else {
+        print!("{}", text);
+    }
+
+}
+
+impl Report {
+
+    pub fn new(input: &Path, level: &Level) -> Report {
+
+        let grid = level.navigation_grid();
+        let cells = grid.cells();
+        let walkable = grid.walkable_cells();
+        let cell_area = NAVIGATION_GRID_SPACING * NAVIGATION_GRID_SPACING;
+
+        // Flood fill the walkable area from each spawn
+        let mut reached = vec![false; cells.len()];
+        let mut spawns = Vec::new();
+        for spawn in &level.spawns {
+
+            let filled = grid.nearest_walkable(spawn.x, spawn.y).map_or_else(Vec::new, |start| {
+                grid.flood_fill(start)
+            });
+
+            for &index in &filled {
+                reached[index] = true;
+            }
+
+            spawns.push(SpawnReport {
+                x: spawn.x,
+                y: spawn.y,
+                reachable_area: filled.len() as f32 * cell_area,
+                reachable_percent: percent(filled.len(), walkable)
+            });
+
+        }
+
+        // Spawns which can see each other
+        let mut spawn_sight_lines = Vec::new();
+        for (i, a) in level.spawns.iter().enumerate() {
+            for (j, b) in level.spawns.iter().enumerate().skip(i + 1) {
+                if level.collide_line(&[a.x, a.y, b.x, b.y]).is_none() {
+                    let (dx, dy) = (b.x - a.x, b.y - a.y);
+                    spawn_sight_lines.push(SpawnSightLine {
+                        a: i,
+                        b: j,
+                        distance: (dx * dx + dy * dy).sqrt()
+                    });
+                }
+            }
+        }
+
+        // Light coverage of the reachable area
+        let reached_count = reached.iter().filter(|r| **r).count();
+        let lit_count = reached.iter().enumerate().filter(|&(index, r)| {
+            let p = grid.position(index);
+            *r && level.circle_in_light(p[0], p[1], PLAYER_RADIUS)
+
+        }).count();
+
+        Report {
+            file: input.display().to_string(),
+            cell_size: NAVIGATION_GRID_SPACING,
+            walkable_area: walkable as f32 * cell_area,
+            reachable_area: reached_count as f32 * cell_area,
+            light_coverage: percent(lit_count, reached_count),
+            spawns: spawns,
+            spawn_sight_lines: spawn_sight_lines,
+            unreachable_regions: find_unreachable_regions(level, grid, &reached),
+            wall_gaps: find_wall_gaps(level),
+            invalid_solids: level.invalid_solids().iter().map(|&(index, err)| {
+                InvalidSolid {
+                    solid: index,
+                    error: format!("{:?}", err)
+                }
+
+            }).collect()
+        }
+
+    }
+
+    pub fn to_text(&self) -> String {
+
+        let mut text = String::new();
+        text.push_str(&format!("Map: {}\n", self.file));
+        text.push_str(&format!("  Walkable area: {:.0}\n", self.walkable_area));
+        text.push_str(&format!("  Reachable area: {:.0}\n", self.reachable_area));
+        text.push_str(&format!("  Light coverage: {:.1}%\n", self.light_coverage));
+
+        text.push_str(&format!("\nSpawns ({}):\n", self.spawns.len()));
+        for (i, spawn) in self.spawns.iter().enumerate() {
+            text.push_str(&format!(
+                "  #{} at {:.2}, {:.2} reaches {:.0} ({:.1}%)\n",
+                i, spawn.x, spawn.y, spawn.reachable_area, spawn.reachable_percent
+            ));
+        }
+
+        text.push_str(&format!("\nSpawn sight lines ({}):\n", self.spawn_sight_lines.len()));
+        for line in &self.spawn_sight_lines {
+            text.push_str(&format!("  #{} <-> #{} over {:.2}\n", line.a, line.b, line.distance));
+        }
+
+        text.push_str(&format!("\nUnreachable regions ({}):\n", self.unreachable_regions.len()));
+        for region in &self.unreachable_regions {
+            text.push_str(&format!("  {:.0} around {:.2}, {:.2}\n", region.area, region.x, region.y));
+        }
+
+        text.push_str(&format!("\nWall gaps ({}):\n", self.wall_gaps.len()));
+        for gap in &self.wall_gaps {
+            text.push_str(&format!(
+                "  wall #{} -> #{} at {:.2}, {:.2} is {:.2} wide\n",
+                gap.wall, gap.other, gap.x, gap.y, gap.width
+            ));
+        }
+
+        // Solids are skipped by the game, so they neither block movement nor
+        // sight
+        text.push_str(&format!("\nInvalid solids ({}):\n", self.invalid_solids.len()));
+        for solid in &self.invalid_solids {
+            text.push_str(&format!("  solid #{} was skipped: {}\n", solid.solid, solid.error));
+        }
+
+        text
+
+    }
+
+}
+
+
+// Helpers --------------------------------------------------------------------
+fn find_unreachable_regions(level: &Level, grid: &LevelNavigationGrid, reached: &[bool]) -> Vec<Region> {
+
+    let cells = grid.cells();
+    let mut visited = reached.to_vec();
+    let mut regions = Vec::new();
+    for index in 0..cells.len() {
+
+        if !cells[index] || visited[index] {
+            continue;
+        }
+
+        let filled = grid.flood_fill(index);
+        for &i in &filled {
+            visited[i] = true;
+        }
+
+        // Regions touching the bounds lie outside of the outer walls
+        let b = &level.bounds;
+        let outside = filled.iter().any(|&i| {
+            let p = grid.position(i);
+            p[0] - b[0] < NAVIGATION_GRID_SPACING || b[2] - p[0] < NAVIGATION_GRID_SPACING
+                || p[1] - b[1] < NAVIGATION_GRID_SPACING || b[3] - p[1] < NAVIGATION_GRID_SPACING
+        });
+
+        if !outside {
+            let (sx, sy) = filled.iter().fold((0.0, 0.0), |(sx, sy), &i| {
+                let p = grid.position(i);
+                (sx + p[0], sy + p[1])
+            });
+            let count = filled.len() as f32;
+            regions.push(Region {
+                x: sx / count,
+                y: sy / count,
+                area: count * NAVIGATION_GRID_SPACING * NAVIGATION_GRID_SPACING
+            });
+        }
+
+    }
+
+    regions
+
+}
+
+fn find_wall_gaps(level: &Level) -> Vec<WallGap> {
+
+    let mut gaps = Vec::new();
+    for (i, wall) in level.walls.iter().enumerate() {
+        for &(x, y) in &[(wall.points[0], wall.points[1]), (wall.points[2], wall.points[3])] {
+
+            // Find the closest other wall of each end point
+            let mut closest = None;
+            let mut min_distance = ANALYSIS_MAX_GAP;
+            for (j, other) in level.walls.iter().enumerate() {
+                if j != i {
+                    let distance = other.distance_from_point(x, y);
+                    if distance < min_distance {
+                        closest = Some(j);
+                        min_distance = distance;
+                    }
+                }
+            }
+
+            // End points which are connected do not leak, everything which is
+            // too close for a player to pass through but still lets the
+            // visibility polygon through does
+            if let Some(j) = closest {
+                if min_distance > 0.01
+                    && !gaps.iter().any(|g: &WallGap| g.wall == j && g.other == i)
+                    && leaks_visibility(level, [x, y], level.walls[j].closest_point(x, y)) {
+                    gaps.push(WallGap {
+                        wall: i,
+                        other: j,
+                        x: x,
+                        y: y,
+                        width: min_distance
+                    });
+                }
+            }
+
+        }
+    }
+
+    gaps
+
+}
+
+fn leaks_visibility(level: &Level, a: [f32; 2], b: (f32, f32)) -> bool {
+
+    // Look across the gap from a point on one of its sides to the opposite
+    // point on its other side
+    let (dx, dy) = (b.0 - a[0], b.1 - a[1]);
+    let l = (dx * dx + dy * dy).sqrt();
+    let (nx, ny) = (-dy / l * ANALYSIS_MAX_GAP, dx / l * ANALYSIS_MAX_GAP);
+    let (mx, my) = ((a[0] + b.0) * 0.5, (a[1] + b.1) * 0.5);
+
+    let polygon = level.visibility_polygon(mx + nx, my + ny, ANALYSIS_MAX_GAP * 2.0);
+    polygon.chunks(6).any(|t| {
+        t.len() == 6 && triangle_contains_point(t, mx - nx, my - ny)
+    })
+
+}
+
+fn triangle_contains_point(t: &[f32], x: f32, y: f32) -> bool {
+    let side = |ax: f32, ay: f32, bx: f32, by: f32| (bx - ax) * (y - ay) - (by - ay) * (x - ax);
+    let (d1, d2, d3) = (
+        side(t[0], t[1], t[2], t[3]),
+        side(t[2], t[3], t[4], t[5]),
+        side(t[4], t[5], t[0], t[1])
+    );
+    !((d1 < 0.0 || d2 < 0.0 || d3 < 0.0) && (d1 > 0.0 || d2 > 0.0 || d3 > 0.0))
+}
+
+fn percent(count: usize, total: usize) -> f32 {
+    if total > 0 {
+        count as f32 / total as f32 * 100.0
+
+    } else {
+        0.0
+    }
+}
+
@@ -0,0 +1,162 @@
+// STD Dependencies -----------------------------------------------------------
+use std::collections::{HashMap, HashSet};
+
+
+// Path Simplification --------------------------------------------------------
+
+/// Removes all points which deviate less than `tolerance` from the straight
+/// line between their neighbours (Ramer–Douglas–Peucker).
+pub fn simplify_path(points: &[[f32; 2]], tolerance: f32) -> Vec<[f32; 2]> {
+
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    // Find the point furthest away from the line between both end points
+    let (a, b) = (points[0], points[points.len() - 1]);
+    let (mut index, mut max_distance) = (0, 0.0);
+    for (i, p) in points.iter().enumerate().take(points.len() - 1).skip(1) {
+        let distance = point_line_distance(p, &a, &b);
+        if distance > max_distance {
+            index = i;
+            max_distance = distance;
+        }
+    }
+
+    // Keep it and recursively simplify both halves of the path
+    if max_distance > tolerance {
+        let mut left = simplify_path(&points[..index + 1], tolerance);
+        let right = simplify_path(&points[index..], tolerance);
+        left.pop();
+        left.extend(right);
+        left
+
+    } else {
+        vec![a, b]
+    }
+
+}
+
+/// Distance of `p` from the infinite line through `a` and `b`.
+pub fn point_line_distance(p: &[f32; 2], a: &[f32; 2], b: &[f32; 2]) -> f32 {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    let l = (dx * dx + dy * dy).sqrt();
+    if l == 0.0 {
+        let (ex, ey) = (p[0] - a[0], p[1] - a[1]);
+        (ex * ex + ey * ey).sqrt()
+
+    } else {
+        (dy * p[0] - dx * p[1] + b[0] * a[1] - b[1] * a[0]).abs() / l
+    }
+}
+
+
+// Line Merging ---------------------------------------------------------------
+
+/// Orders the end points of a line by their x and then their y coordinate,
+/// so axis aligned lines always start at their minimum.
+pub fn normalize_line(line: [f32; 4]) -> [f32; 4] {
+    if line[2] < line[0] || (line[2] == line[0] && line[3] < line[1]) {
+        [line[2], line[3], line[0], line[1]]
+
+    } else {
+        line
+    }
+}
+
+/// Combines two lines which lie on the same infinite line and either overlap
+/// or are separated by at most `max_gap` pixels.
+///
+/// The gap is measured in pixel steps along the major axis of the lines, so a
+/// gap of `1.0` joins the paths of adjacent pixels in any direction while any
+/// wider opening (e.g. a doorway) is kept.
+pub fn merge_collinear_lines(a: &[f32; 4], b: &[f32; 4], max_gap: f32) -> Option<[f32; 4]> {
+
+    let (pa, pb) = ([a[0], a[1]], [a[2], a[3]]);
+    let (qa, qb) = ([b[0], b[1]], [b[2], b[3]]);
+
+    let (dx, dy) = (pb[0] - pa[0], pb[1] - pa[1]);
+    let l = dx * dx + dy * dy;
+    if l == 0.0 {
+        return None;
+    }
+
+    // Both lines must lie on the same infinite line
+    if point_line_distance(&qa, &pa, &pb) > 0.01 || point_line_distance(&qb, &pa, &pb) > 0.01 {
+        return None;
+    }
+
+    // Position of all end points along the first line, which spans 0 to 1
+    let project = |p: &[f32; 2]| ((p[0] - pa[0]) * dx + (p[1] - pa[1]) * dy) / l;
+    let points = [(0.0, pa), (1.0, pb), (project(&qa), qa), (project(&qb), qb)];
+    let (start, end) = (points[2].0.min(points[3].0), points[2].0.max(points[3].0));
+
+    let gap = if start > 1.0 {
+        start - 1.0
+
+    } else if end < 0.0 {
+        -end
+
+    } else {
+        0.0
+    };
+
+    if gap * dx.abs().max(dy.abs()) > max_gap + 0.001 {
+        return None;
+    }
+
+    // Span from the first to the last of all end points
+    let first = points.iter().fold(points[0], |m, p| if p.0 < m.0 { *p } else { m });
+    let last = points.iter().fold(points[0], |m, p| if p.0 > m.0 { *p } else { m });
+    Some(normalize_line([first.1[0], first.1[1], last.1[0], last.1[1]]))
+
+}
+
+
+// Endpoint Extension ---------------------------------------------------------
+
+/// Extends lines by a single pixel step along their direction where they end
+/// right in front of a corner at which other lines meet up.
+///
+/// This closes the cracks between traced paths which would otherwise let
+/// visibility and light cones shine through.
+pub fn extend_line_endpoints(lines: &mut [[f32; 4]]) {
+
+    let mut count: HashMap<(i32, i32), usize> = HashMap::new();
+    for l in lines.iter() {
+        *count.entry((l[0] as i32, l[1] as i32)).or_insert(0) += 1;
+        *count.entry((l[2] as i32, l[3] as i32)).or_insert(0) += 1;
+    }
+
+    // Extract all endpoints where lines actually meet up
+    let corners: HashSet<(i32, i32)> = count.into_iter().filter(|&(_, c)| c >= 2).map(|(e, _)| e).collect();
+
+    for l in lines.iter_mut() {
+
+        // A single pixel step along the major axis of the line, diagonal lines
+        // are thus extended onto the next pixel of their direction
+        let (dx, dy) = (l[2] - l[0], l[3] - l[1]);
+        let steps = dx.abs().max(dy.abs());
+        if steps == 0.0 {
+            continue;
+        }
+
+        let (sx, sy) = (dx / steps, dy / steps);
+        let (px, py) = (sx.round() as i32, sy.round() as i32);
+
+        let a = (l[0] as i32 - px, l[1] as i32 - py);
+        if corners.contains(&a) {
+            l[0] -= sx;
+            l[1] -= sy;
+        }
+
+        let b = (l[2] as i32 + px, l[3] as i32 + py);
+        if corners.contains(&b) {
+            l[2] += sx;
+            l[3] += sy;
+        }
+
+    }
+
+}
+
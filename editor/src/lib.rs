@@ -0,0 +1,12 @@
+//! **editor**
+
+
+// Crates ---------------------------------------------------------------------
+extern crate rustc_serialize;
+extern crate shared;
+
+
+// Modules --------------------------------------------------------------------
+pub mod analysis;
+pub mod geometry;
+
@@ -2,49 +2,262 @@
 extern crate rustc_serialize;
 extern crate image;
 extern crate toml;
+extern crate clap;
+extern crate shared;
+extern crate editor;
 
 
 // STD Dependencies -----------------------------------------------------------
 use std::cmp;
+use std::fs;
 use std::fs::File;
 use std::io::Write;
-use std::path::Path;
-use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::collections::HashSet;
 
 
 // External Dependencies ------------------------------------------------------
+use clap::{Arg, App};
 use toml::Encoder;
 use image::GenericImage;
 use rustc_serialize::Encodable;
 
 
+// Modules --------------------------------------------------------------------
+mod render;
+use render::Visibility;
+use editor::analysis;
+use editor::geometry::{extend_line_endpoints, merge_collinear_lines, normalize_line, simplify_path};
+
+
+// Statics --------------------------------------------------------------------
+const PALETTE: [(&'static str, &'static str, TracedPathType, &'static str); 13] = [
+    ("wall-color", "ffffff", TracedPathType::Wall, "Pixel color of walls."),
+    ("mirror-color", "a0a0ff", TracedPathType::MirrorWall, "Pixel color of mirror walls."),
+    ("door-color", "c08040", TracedPathType::Door, "Pixel color of doors."),
+    ("light-color", "ffff00", TracedPathType::Light, "Pixel color of lights."),
+    ("spawn-color", "00ffff", TracedPathType::Spawn, "Pixel color of spawns."),
+    ("team-red-spawn-color", "ff4040", TracedPathType::TeamSpawn(0), "Pixel color of red team spawns."),
+    ("team-blue-spawn-color", "4040ff", TracedPathType::TeamSpawn(1), "Pixel color of blue team spawns."),
+    ("capture-zone-color", "ff8000", TracedPathType::CaptureZone, "Pixel color of capture zone outlines."),
+    ("health-pickup-color", "80ff80", TracedPathType::Pickup(PickupType::Health), "Pixel color of health pickup spawners."),
+    ("damage-pickup-color", "ff8080", TracedPathType::Pickup(PickupType::DamageBoost), "Pixel color of damage boost pickup spawners."),
+    ("speed-pickup-color", "ffc000", TracedPathType::Pickup(PickupType::Speed), "Pixel color of speed pickup spawners."),
+    ("shield-pickup-color", "8080ff", TracedPathType::Pickup(PickupType::Shield), "Pixel color of shield pickup spawners."),
+    ("solid-color", "ff00ff", TracedPathType::Solid, "Pixel color of solids.")
+];
+
+
 // PNG -> Map TOML Parser -----------------------------------------------------
 fn main() {
 
-    let img = image::open(&Path::new("map.png")).unwrap();
+    let mut app = App::new("Shooter Map Editor")
+        .version("0.1")
+        .author("Ivo Wetzel <ivo.wetzel@googlemail.com>")
+        .about("Converts PNG map images into level TOML files and renders level TOML files back into PNG previews")
+        .arg(Arg::with_name("input")
+            .value_name("INPUT")
+            .help("PNG file to convert, TOML file to render or directory containing PNG files.")
+            .index(1)
+            .default_value("map.png")
+        )
+        .arg(Arg::with_name("output")
+            .short("o")
+            .long("output")
+            .value_name("OUTPUT")
+            .help("TOML / PNG file or directory to write to, defaults to the input path with a .toml / .png extension.")
+            .takes_value(true)
+        )
+        .arg(Arg::with_name("scale")
+            .short("s")
+            .long("scale")
+            .value_name("FACTOR")
+            .help("Factor by which all level coordinates are scaled, rendering divides by it.")
+            .takes_value(true)
+            .default_value("1.50")
+            .validator(|v| v.parse::<f32>().map(|_| ()).map_err(|_| "Scale must be a number.".to_string()))
+        )
+        .arg(Arg::with_name("tolerance")
+            .short("t")
+            .long("tolerance")
+            .value_name("PIXELS")
+            .help("Maximum distance in pixels by which simplified walls may deviate from the traced path, 0 disables simplification.")
+            .takes_value(true)
+            .default_value("1.0")
+            .validator(|v| v.parse::<f32>().map(|_| ()).map_err(|_| "Tolerance must be a number.".to_string()))
+        )
+        .arg(Arg::with_name("merge-gap")
+            .long("merge-gap")
+            .value_name("PIXELS")
+            .help("Maximum gap in pixels across which collinear walls are merged, 0 only merges overlapping or touching walls.")
+            .takes_value(true)
+            .default_value("1.0")
+            .validator(|v| v.parse::<f32>().map(|_| ()).map_err(|_| "Merge gap must be a number.".to_string()))
+        )
+        .arg(Arg::with_name("origin")
+            .long("origin")
+            .value_name("MODE")
+            .help("Places the level origin either at the center or the top left corner of the map.")
+            .takes_value(true)
+            .possible_values(&["center", "top-left"])
+            .default_value("center")
+        )
+        .arg(Arg::with_name("visibility")
+            .long("visibility")
+            .value_name("X,Y")
+            .help("Overlays the visibility polygon from the given level coordinates when rendering.")
+            .takes_value(true)
+            .validator(|v| parse_point(&v).map(|_| ()).ok_or_else(|| "Visibility must be a point in the form X,Y.".to_string()))
+        )
+        .arg(Arg::with_name("visibility-radius")
+            .long("visibility-radius")
+            .value_name("RADIUS")
+            .help("Maximum distance of the visibility overlay.")
+            .takes_value(true)
+            .default_value("130.0")
+            .validator(|v| v.parse::<f32>().map(|_| ()).map_err(|_| "Visibility radius must be a number.".to_string()))
+        )
+        .arg(Arg::with_name("analyze")
+            .short("a")
+            .long("analyze")
+            .help("Analyzes a level TOML file instead of rendering it and reports reachability, sight lines, light coverage and wall gaps.")
+        )
+        .arg(Arg::with_name("format")
+            .long("format")
+            .value_name("FORMAT")
+            .help("Output format of the analysis report.")
+            .takes_value(true)
+            .possible_values(&["text", "json"])
+            .default_value("text")
+        );
+
+    // Allow overriding the pixel color of every path type
+    for &(name, color, _, help) in PALETTE.iter() {
+        app = app.arg(Arg::with_name(name)
+            .long(name)
+            .value_name("RRGGBB")
+            .help(help)
+            .takes_value(true)
+            .default_value(color)
+            .validator(validate_color)
+        );
+    }
+
+    let matches = app.get_matches();
+
+    let options = Options {
+        scale: matches.value_of("scale").unwrap().parse().unwrap(),
+        tolerance: matches.value_of("tolerance").unwrap().parse().unwrap(),
+        merge_gap: matches.value_of("merge-gap").unwrap().parse().unwrap(),
+        origin: if matches.value_of("origin") == Some("top-left") {
+            Origin::TopLeft
+
+        } else {
+            Origin::Center
+        },
+        palette: Palette {
+            colors: PALETTE.iter().map(|&(name, _, typ, _)| {
+                (parse_color(matches.value_of(name).unwrap()).unwrap(), typ)
+
+            }).collect()
+        }
+    };
+
+    let visibility = matches.value_of("visibility").and_then(parse_point).map(|(x, y)| {
+        Visibility {
+            x: x,
+            y: y,
+            radius: matches.value_of("visibility-radius").unwrap().parse().unwrap()
+        }
+    });
+
+    let input = Path::new(matches.value_of("input").unwrap());
+    let output = matches.value_of("output").map(PathBuf::from);
+
+    // Analyze or render level TOML files back into PNG previews
+    if input.extension().map_or(false, |ext| ext.to_string_lossy().to_lowercase() == "toml") {
+
+        if matches.is_present("analyze") {
+            let format = if matches.value_of("format") == Some("json") {
+                analysis::Format::Json
+
+            } else {
+                analysis::Format::Text
+            };
+            analysis::analyze(input, output.as_ref().map(|p| p.as_path()), format);
+            return;
+        }
+
+        let png_file = output.unwrap_or_else(|| input.with_extension("png"));
+        render::render(input, &png_file, &options, visibility.as_ref());
+        return;
+    }
+
+    // Batch convert all PNG files in a directory
+    if input.is_dir() {
 
-    let (bounds, paths) = find_paths(&img);
-    let mut level = parse_paths(&bounds, paths);
-    level.scale(1.50);
+        let output_dir = output.unwrap_or_else(|| input.to_path_buf());
+        fs::create_dir_all(&output_dir).expect("Failed to create output directory.");
+
+        let mut files: Vec<PathBuf> = fs::read_dir(input).expect("Failed to read input directory.").filter_map(|entry| {
+            entry.ok().map(|e| e.path())
+
+        }).filter(|path| {
+            path.extension().map_or(false, |ext| ext.to_string_lossy().to_lowercase() == "png")
+
+        }).collect();
+
+        files.sort();
+
+        for file in files {
+            let toml_file = output_dir.join(file.with_extension("toml").file_name().unwrap());
+            convert(&file, &toml_file, &options);
+        }
+
+    } else {
+        let toml_file = output.unwrap_or_else(|| input.with_extension("toml"));
+        convert(input, &toml_file, &options);
+    }
+
+}
+
+fn convert(input: &Path, output: &Path, options: &Options) {
+
+    let img = match image::open(input) {
+        Ok(img) => img,
+        Err(err) => {
+            println!("[Editor] Failed to open {}: {}", input.display(), err);
+            return;
+        }
+    };
+
+    let (bounds, paths) = find_paths(&img, &options.palette);
+    let mut level = parse_paths(&bounds, paths, options);
+    level.scale(options.scale);
 
     let mut e = Encoder::new();
     level.encode(&mut e).unwrap();
 
     let toml = toml::Value::Table(e.toml);
-    if let Ok(mut file) = File::create("map.toml") {
+    if let Ok(mut file) = File::create(output) {
         write!(file, "{}", toml).expect("Failed to write map toml.");
+        println!("[Editor] Converted {} -> {}", input.display(), output.display());
+
+    } else {
+        println!("[Editor] Failed to create {}", output.display());
     }
 
 }
 
-fn find_paths(img: &image::DynamicImage) -> ([i32; 4], Vec<TracedPath>) {
+fn find_paths(img: &image::DynamicImage, palette: &Palette) -> ([i32; 4], Vec<TracedPath>) {
 
     let (w, h) = img.dimensions();
     let mut pixel_usage: HashSet<(i32, i32)> = HashSet::new();
 
-    // Find walls, spawns and lights
+    // Find all features except for solids
     let (bounds, mut paths) = extract_paths(
         img,
+        palette,
         &mut pixel_usage,
         false,
         &[0, 0, w as i32, h as i32]
@@ -53,6 +266,7 @@ fn find_paths(img: &image::DynamicImage) -> ([i32; 4], Vec<TracedPath>) {
     // Find solids
     let (_, mut solid_paths) = extract_paths(
         img,
+        palette,
         &mut pixel_usage,
         true,
         &[
@@ -69,71 +283,30 @@ fn find_paths(img: &image::DynamicImage) -> ([i32; 4], Vec<TracedPath>) {
 
 }
 
-fn parse_paths(bounds: &[i32; 4], paths: Vec<TracedPath>) -> Level {
+fn parse_paths(bounds: &[i32; 4], paths: Vec<TracedPath>, options: &Options) -> Level {
 
     let mut level = Level::default();
+    let tolerance = options.tolerance;
 
-    let (lw, lh) = ((bounds[2] - bounds[0]) as f32 / 2.0, (bounds[3] - bounds[1]) as f32 / 2.0);
+    let (lw, lh) = match options.origin {
+        Origin::Center => ((bounds[2] - bounds[0]) as f32 / 2.0, (bounds[3] - bounds[1]) as f32 / 2.0),
+        Origin::TopLeft => (bounds[0] as f32, bounds[1] as f32)
+    };
     for p in paths {
 
-        if p.typ == TracedPathType::Wall {
-
-            // Split wall paths into line segments
-            let mut lines = Vec::new();
-            let (mut ox, mut oy, mut or, mut dr) = p.pixels[0];
-            let (mut lx, mut ly, _, _) = p.pixels[0];
-            for &(x, y, r, d) in &p.pixels {
-                if r != or {
-                    let (dx, dy) = (ox - lx, oy - ly);
-                    if ((dx * dx + dy * dy) as f32).sqrt() > 0.0 {
-                        let line = [ox as u32, oy as u32, lx as u32, ly as u32, r];
-                        lines.push((line, d));
-                    }
-                    ox = lx;
-                    oy = ly;
-                    or = r;
-                    dr = d;
-                }
-                lx = x;
-                ly = y;
-            }
-
-            let (dx, dy) = (ox - lx, oy - ly);
-            if ((dx * dx + dy * dy) as f32).sqrt() > 0.0 {
-                let line = [ox as u32, oy as u32, lx as u32, ly as u32, or];
-                lines.push((line, dr));
+        if p.typ == TracedPathType::Wall || p.typ == TracedPathType::MirrorWall {
+            for line in path_to_lines(&p, lw, lh, tolerance) {
+                level.walls.push(Wall {
+                    line: line,
+                    mirror: p.typ == TracedPathType::MirrorWall
+                });
             }
 
-            for (l, _) in lines {
-
-                let p = [
-                    (l[0] as f32 - lw),
-                    (l[1] as f32 - lh),
-                    (l[2] as f32 - lw),
-                    (l[3] as f32 - lh)
-                ];
-
-                let line = if p[0] == p[2] || p[1] == p[3] {
-                    [
-                        p[0].min(p[2]),
-                        p[1].min(p[3]),
-                        p[0].max(p[2]),
-                        p[1].max(p[3])
-                    ]
-
-                } else {
-                    p
-                };
-
-                let (dx, dy) = (line[2] - line[0], line[3] - line[1]);
-
-                // Avoid adding diagonal walls with spanning just two pixels
-                if (dx * dx + dy * dy).sqrt() > 1.5 {
-                    level.walls.push(Wall {
-                        line: line
-                    });
-                }
-
+        } else if p.typ == TracedPathType::Door {
+            for line in path_to_lines(&p, lw, lh, tolerance) {
+                level.doors.push(Door {
+                    line: line
+                });
             }
 
         // Construct concave polygons from solids
@@ -180,7 +353,7 @@ fn parse_paths(bounds: &[i32; 4], paths: Vec<TracedPath>) -> Level {
                 level.solids.push(points);
             }
 
-        // Calculate bounds and center of lights and spawns
+        // Calculate bounds and center of lights, spawns, pickups and zones
         } else {
 
             let mut path_bounds = [
@@ -210,70 +383,139 @@ fn parse_paths(bounds: &[i32; 4], paths: Vec<TracedPath>) -> Level {
                     y: y as f32 - lh,
                     radius: (cmp::max(w, h) as f32 * (2.0f32).sqrt()).round()
                 });
+
+            } else if let TracedPathType::TeamSpawn(team) = p.typ {
+                level.team_spawns.push(TeamSpawn {
+                    team: team,
+                    x: x as f32 - lw,
+                    y: y as f32 - lh
+                });
+
+            } else if let TracedPathType::Pickup(kind) = p.typ {
+                level.pickups.push(Pickup {
+                    kind: kind.name().to_string(),
+                    x: x as f32 - lw,
+                    y: y as f32 - lh
+                });
+
+            } else if p.typ == TracedPathType::CaptureZone {
+                level.capture_zones.push(CaptureZone {
+                    x: x as f32 - lw,
+                    y: y as f32 - lh,
+                    width: w as f32,
+                    height: h as f32
+                });
             }
 
         }
 
     }
 
+    // Combine walls which continue each other in the same direction, this
+    // mostly happens where separately traced paths meet up
+    let walls = level.walls.drain(..).collect();
+    level.walls = merge_collinear_walls(walls, options.merge_gap);
+
     // Next up merge wall endpoints which meet up, this is done in order to avoid
     // visual glitches with visibility and light cone rendering (they would
     // otherwise shine through the cracks)
-    let endpoint_list: Vec<(i32, i32)> = level.walls.iter().flat_map(|w| vec![(w.line[0] as i32, w.line[1] as i32), (w.line[2] as i32, w.line[3] as i32)]).collect();
-    let mut count: HashMap<(i32, i32), usize> = HashMap::new();
-    for e in &endpoint_list {
-        count.insert(*e, 0);
+    let mut lines: Vec<[f32; 4]> = level.walls.iter().map(|w| w.line).collect();
+    extend_line_endpoints(&mut lines);
+    for (w, line) in level.walls.iter_mut().zip(lines) {
+        w.line = line;
+    }
+
+    level
+
+}
+
+fn path_to_lines(p: &TracedPath, lw: f32, lh: f32, tolerance: f32) -> Vec<[f32; 4]> {
+
+    // Collect the corners at which the path changes its direction
+    let mut points = Vec::new();
+    let (mut ox, mut oy, mut or, _) = p.pixels[0];
+    let (mut lx, mut ly, _, _) = p.pixels[0];
+    points.push([ox as f32, oy as f32]);
+
+    for &(x, y, r, _) in &p.pixels {
+        if r != or {
+            if lx != ox || ly != oy {
+                points.push([lx as f32, ly as f32]);
+            }
+            ox = lx;
+            oy = ly;
+            or = r;
+        }
+        lx = x;
+        ly = y;
     }
 
-    for e in endpoint_list {
-        *count.get_mut(&e).unwrap() += 1;
+    if lx != ox || ly != oy {
+        points.push([lx as f32, ly as f32]);
     }
 
-    // Extract all endpoints where lines actually meet up
-    let mut endpoints = HashSet::new();
-    for (e, c) in count {
-        if c >= 2 {
-            endpoints.insert(e);
+    // Collapse pixel staircases into straight lines
+    if tolerance > 0.0 {
+        points = simplify_path(&points, tolerance);
+    }
+
+    // Split paths into line segments
+    let mut segments = Vec::new();
+    for l in points.windows(2) {
+
+        let p = [
+            l[0][0] - lw,
+            l[0][1] - lh,
+            l[1][0] - lw,
+            l[1][1] - lh
+        ];
+
+        let line = normalize_line(p);
+
+        let (dx, dy) = (line[2] - line[0], line[3] - line[1]);
+
+        // Avoid adding diagonal walls with spanning just two pixels
+        if (dx * dx + dy * dy).sqrt() > 1.5 {
+            segments.push(line);
         }
+
     }
-    for w in &mut level.walls {
 
-        let a = (w.line[0] as i32, w.line[1] as i32);
-        let b = (w.line[2] as i32, w.line[3] as i32);
+    segments
 
-        // Vertical
-        if a.0 == b.0 {
-            let d = (a.0, a.1 - 1);
-            if endpoints.contains(&d) {
-                w.line[1] -= 1.0;
-            }
+}
 
-            let e = (b.0, b.1 + 1);
-            if endpoints.contains(&e) {
-                w.line[3] += 1.0;
-            }
+fn merge_collinear_walls(walls: Vec<Wall>, max_gap: f32) -> Vec<Wall> {
 
-        // Horizontal
-        } else {
-            let d = (a.0 - 1, a.1);
-            if endpoints.contains(&d) {
-                w.line[0] -= 1.0;
-            }
+    let mut walls = walls;
+    let mut merged = true;
+    while merged {
+
+        merged = false;
 
-            let e = (b.0 + 1, b.1);
-            if endpoints.contains(&e) {
-                w.line[2] += 1.0;
+        'outer: for i in 0..walls.len() {
+            for j in (i + 1)..walls.len() {
+                if walls[i].mirror == walls[j].mirror {
+                    let line = merge_collinear_lines(&walls[i].line, &walls[j].line, max_gap);
+                    if let Some(line) = line {
+                        walls[i].line = line;
+                        walls.remove(j);
+                        merged = true;
+                        break 'outer;
+                    }
+                }
             }
         }
 
     }
 
-    level
+    walls
 
 }
 
 fn extract_paths(
     img: &image::DynamicImage,
+    palette: &Palette,
     pixel_usage: &mut HashSet<(i32, i32)>,
     solids: bool,
     outer_bounds: &[i32; 4]
@@ -293,7 +535,7 @@ fn extract_paths(
             if !pixel_usage.contains(&(x as i32, y as i32)) {
 
                 // Start tracing a new path
-                let path_type = get_path_type(x, y, img, solids, outer_bounds);
+                let path_type = get_path_type(x, y, img, palette, solids, outer_bounds);
                 if let Some(path_type) = path_type {
 
                     let mut px = x as i32;
@@ -309,29 +551,29 @@ fn extract_paths(
                     loop {
 
                         // Check top right bottom left first
-                        let m = if is_valid_path_pixel(px, py - 1, &img, &pixel_usage, path_type, solids, outer_bounds) {
+                        let m = if is_valid_path_pixel(px, py - 1, &img, palette, &pixel_usage, path_type, solids, outer_bounds) {
                             Some((px, py - 1, 0, Direction::Vertical))
 
-                        } else if is_valid_path_pixel(px + 1, py, &img, &pixel_usage, path_type, solids, outer_bounds) {
+                        } else if is_valid_path_pixel(px + 1, py, &img, palette, &pixel_usage, path_type, solids, outer_bounds) {
                             Some((px + 1, py, 90, Direction::Horizontal))
 
-                        } else if is_valid_path_pixel(px, py + 1, &img, &pixel_usage, path_type, solids, outer_bounds) {
+                        } else if is_valid_path_pixel(px, py + 1, &img, palette, &pixel_usage, path_type, solids, outer_bounds) {
                             Some((px, py + 1, 180, Direction::Vertical))
 
-                        } else if is_valid_path_pixel(px - 1, py, &img, &pixel_usage, path_type, solids, outer_bounds) {
+                        } else if is_valid_path_pixel(px - 1, py, &img, palette, &pixel_usage, path_type, solids, outer_bounds) {
                             Some((px - 1, py, 270, Direction::Horizontal))
 
                         // Check topright bottomright bottomleft topleft second
-                        } else if is_valid_path_pixel(px + 1, py - 1, &img, &pixel_usage, path_type, solids, outer_bounds) {
+                        } else if is_valid_path_pixel(px + 1, py - 1, &img, palette, &pixel_usage, path_type, solids, outer_bounds) {
                             Some((px + 1, py - 1, 45, Direction::DiagonalOne))
 
-                        } else if is_valid_path_pixel(px + 1, py + 1, &img, &pixel_usage, path_type, solids, outer_bounds) {
+                        } else if is_valid_path_pixel(px + 1, py + 1, &img, palette, &pixel_usage, path_type, solids, outer_bounds) {
                             Some((px + 1, py + 1, 135, Direction::DiagonalTwo))
 
-                        } else if is_valid_path_pixel(px - 1, py + 1, &img, &pixel_usage, path_type, solids, outer_bounds) {
+                        } else if is_valid_path_pixel(px - 1, py + 1, &img, palette, &pixel_usage, path_type, solids, outer_bounds) {
                             Some((px - 1, py + 1, 225, Direction::DiagonalOne))
 
-                        } else if is_valid_path_pixel(px - 1, py - 1, &img, &pixel_usage, path_type, solids, outer_bounds) {
+                        } else if is_valid_path_pixel(px - 1, py - 1, &img, palette, &pixel_usage, path_type, solids, outer_bounds) {
                             Some((px - 1, py - 1, 315, Direction::DiagonalTwo))
 
                         } else {
@@ -359,29 +601,29 @@ fn extract_paths(
                         } else {
 
                             // Merge with adjacent paths at the end
-                            let m = if lr == 0 && is_potential_path_pixel(px, py - 1, &img, path_type, solids, outer_bounds) {
+                            let m = if lr == 0 && is_potential_path_pixel(px, py - 1, &img, palette, path_type, solids, outer_bounds) {
                                 Some((px, py - 1, 0, Direction::Vertical))
 
-                            } else if lr == 90 && is_potential_path_pixel(px + 1, py, &img, path_type, solids, outer_bounds) {
+                            } else if lr == 90 && is_potential_path_pixel(px + 1, py, &img, palette, path_type, solids, outer_bounds) {
                                 Some((px + 1, py, 90, Direction::Horizontal))
 
-                            } else if lr == 180 && is_potential_path_pixel(px, py + 1, &img, path_type, solids, outer_bounds) {
+                            } else if lr == 180 && is_potential_path_pixel(px, py + 1, &img, palette, path_type, solids, outer_bounds) {
                                 Some((px, py + 1, 180, Direction::Vertical))
 
-                            } else if lr == 270 && is_potential_path_pixel(px - 1, py, &img, path_type, solids, outer_bounds) {
+                            } else if lr == 270 && is_potential_path_pixel(px - 1, py, &img, palette, path_type, solids, outer_bounds) {
                                 Some((px - 1, py, 270, Direction::Horizontal))
 
                             // Check topright bottomright bottomleft topleft second
-                            } else if lr == 45 && is_potential_path_pixel(px + 1, py - 1, &img, path_type, solids, outer_bounds) {
+                            } else if lr == 45 && is_potential_path_pixel(px + 1, py - 1, &img, palette, path_type, solids, outer_bounds) {
                                 Some((px + 1, py - 1, 45, Direction::DiagonalOne))
 
-                            } else if lr == 135 && is_potential_path_pixel(px + 1, py + 1, &img, path_type, solids, outer_bounds) {
+                            } else if lr == 135 && is_potential_path_pixel(px + 1, py + 1, &img, palette, path_type, solids, outer_bounds) {
                                 Some((px + 1, py + 1, 135, Direction::DiagonalTwo))
 
-                            } else if lr == 225 && is_potential_path_pixel(px - 1, py + 1, &img, path_type, solids, outer_bounds) {
+                            } else if lr == 225 && is_potential_path_pixel(px - 1, py + 1, &img, palette, path_type, solids, outer_bounds) {
                                 Some((px - 1, py + 1, 225, Direction::DiagonalOne))
 
-                            } else if lr == 315 && is_potential_path_pixel(px - 1, py - 1, &img, path_type, solids, outer_bounds) {
+                            } else if lr == 315 && is_potential_path_pixel(px - 1, py - 1, &img, palette, path_type, solids, outer_bounds) {
                                 Some((px - 1, py - 1, 315, Direction::DiagonalTwo))
 
                             } else {
@@ -394,29 +636,29 @@ fn extract_paths(
 
                             // Merge with adjacent paths at the end
                             let (px, py, lr, _) = pixels[0];
-                            let m = if lr == 180 && is_potential_path_pixel(px, py - 1, &img, path_type, solids, outer_bounds) {
+                            let m = if lr == 180 && is_potential_path_pixel(px, py - 1, &img, palette, path_type, solids, outer_bounds) {
                                 Some((px, py - 1, 0, Direction::Vertical))
 
-                            } else if lr == 270 && is_potential_path_pixel(px + 1, py, &img, path_type, solids, outer_bounds) {
+                            } else if lr == 270 && is_potential_path_pixel(px + 1, py, &img, palette, path_type, solids, outer_bounds) {
                                 Some((px + 1, py, 90, Direction::Horizontal))
 
-                            } else if lr == 00 && is_potential_path_pixel(px, py + 1, &img, path_type, solids, outer_bounds) {
+                            } else if lr == 00 && is_potential_path_pixel(px, py + 1, &img, palette, path_type, solids, outer_bounds) {
                                 Some((px, py + 1, 180, Direction::Vertical))
 
-                            } else if lr == 90 && is_potential_path_pixel(px - 1, py, &img, path_type, solids, outer_bounds) {
+                            } else if lr == 90 && is_potential_path_pixel(px - 1, py, &img, palette, path_type, solids, outer_bounds) {
                                 Some((px - 1, py, 270, Direction::Horizontal))
 
                             // Check topright bottomright bottomleft topleft second
-                            } else if lr == 225 && is_potential_path_pixel(px + 1, py - 1, &img, path_type, solids, outer_bounds) {
+                            } else if lr == 225 && is_potential_path_pixel(px + 1, py - 1, &img, palette, path_type, solids, outer_bounds) {
                                 Some((px + 1, py - 1, 45, Direction::DiagonalOne))
 
-                            } else if lr == 315 && is_potential_path_pixel(px + 1, py + 1, &img, path_type, solids, outer_bounds) {
+                            } else if lr == 315 && is_potential_path_pixel(px + 1, py + 1, &img, palette, path_type, solids, outer_bounds) {
                                 Some((px + 1, py + 1, 135, Direction::DiagonalTwo))
 
-                            } else if lr == 45 && is_potential_path_pixel(px - 1, py + 1, &img, path_type, solids, outer_bounds) {
+                            } else if lr == 45 && is_potential_path_pixel(px - 1, py + 1, &img, palette, path_type, solids, outer_bounds) {
                                 Some((px - 1, py + 1, 225, Direction::DiagonalOne))
 
-                            } else if lr == 135 && is_potential_path_pixel(px - 1, py - 1, &img, path_type, solids, outer_bounds) {
+                            } else if lr == 135 && is_potential_path_pixel(px - 1, py - 1, &img, palette, path_type, solids, outer_bounds) {
                                 Some((px - 1, py - 1, 315, Direction::DiagonalTwo))
 
                             } else {
@@ -452,14 +694,77 @@ fn extract_paths(
 
 
 // Types -----------------------------------------------------------------------
+#[derive(Debug)]
+struct Options {
+    scale: f32,
+    tolerance: f32,
+    merge_gap: f32,
+    origin: Origin,
+    palette: Palette
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Origin {
+    Center,
+    TopLeft
+}
+
+#[derive(Debug)]
+struct Palette {
+    colors: Vec<([u8; 3], TracedPathType)>
+}
+
+impl Palette {
+
+    fn get(&self, color: [u8; 3]) -> Option<TracedPathType> {
+        self.colors.iter().find(|&&(c, _)| c == color).map(|&(_, typ)| typ)
+    }
+
+    fn color(&self, typ: TracedPathType) -> [u8; 3] {
+        self.colors.iter().find(|&&(_, t)| t == typ).map_or([255, 255, 255], |&(c, _)| c)
+    }
+
+    fn is_wall(&self, color: [u8; 3]) -> bool {
+        match self.get(color) {
+            Some(TracedPathType::Wall) | Some(TracedPathType::MirrorWall) => true,
+            _ => false
+        }
+    }
+
+}
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 enum TracedPathType {
     Wall,
+    MirrorWall,
+    Door,
     Light,
     Spawn,
+    TeamSpawn(u8),
+    Pickup(PickupType),
+    CaptureZone,
     Solid
 }
 
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum PickupType {
+    Health,
+    DamageBoost,
+    Speed,
+    Shield
+}
+
+impl PickupType {
+    fn name(&self) -> &'static str {
+        match *self {
+            PickupType::Health => "health",
+            PickupType::DamageBoost => "damage",
+            PickupType::Speed => "speed",
+            PickupType::Shield => "shield"
+        }
+    }
+}
+
 #[derive(Debug)]
 struct TracedPath {
     typ: TracedPathType,
@@ -478,8 +783,12 @@ enum Direction {
 #[derive(Debug, Default, RustcEncodable)]
 struct Level {
     walls: Vec<Wall>,
+    doors: Vec<Door>,
     spawns: Vec<Spawn>,
+    team_spawns: Vec<TeamSpawn>,
     lights: Vec<Light>,
+    pickups: Vec<Pickup>,
+    capture_zones: Vec<CaptureZone>,
     solids: Vec<Vec<f32>>
 }
 
@@ -494,11 +803,35 @@ impl Level {
             w.line[3] *= factor;
         }
 
+        for d in &mut self.doors {
+            d.line[0] *= factor;
+            d.line[1] *= factor;
+            d.line[2] *= factor;
+            d.line[3] *= factor;
+        }
+
         for s in &mut self.spawns {
             s.x *= factor;
             s.y *= factor;
         }
 
+        for s in &mut self.team_spawns {
+            s.x *= factor;
+            s.y *= factor;
+        }
+
+        for p in &mut self.pickups {
+            p.x *= factor;
+            p.y *= factor;
+        }
+
+        for z in &mut self.capture_zones {
+            z.x *= factor;
+            z.y *= factor;
+            z.width *= factor;
+            z.height *= factor;
+        }
+
         for l in &mut self.lights {
             l.x *= factor;
             l.y *= factor;
@@ -518,6 +851,12 @@ impl Level {
 
 #[derive(Debug, RustcEncodable)]
 struct Wall {
+    line: [f32; 4],
+    mirror: bool
+}
+
+#[derive(Debug, RustcEncodable)]
+struct Door {
     line: [f32; 4]
 }
 
@@ -527,6 +866,28 @@ struct Spawn {
     y: f32
 }
 
+#[derive(Debug, RustcEncodable)]
+struct TeamSpawn {
+    team: u8,
+    x: f32,
+    y: f32
+}
+
+#[derive(Debug, RustcEncodable)]
+struct Pickup {
+    kind: String,
+    x: f32,
+    y: f32
+}
+
+#[derive(Debug, RustcEncodable)]
+struct CaptureZone {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32
+}
+
 #[derive(Debug, RustcEncodable)]
 struct Light {
     x: f32,
@@ -624,6 +985,7 @@ fn extrusion(pr: u32, nr: u32) -> (i32, i32) {
 fn is_valid_path_pixel(
     x: i32, y: i32,
     img: &image::DynamicImage,
+    palette: &Palette,
     usage: &HashSet<(i32, i32)>,
     path_type: TracedPathType,
     check_solids: bool,
@@ -633,7 +995,7 @@ fn is_valid_path_pixel(
 
     if x >= bounds[0] && x < bounds[2] && y >= bounds[1] && y < bounds[3] {
         if !usage.contains(&(x, y)) {
-            if let Some(p) = get_path_type(x, y, img, check_solids, bounds) {
+            if let Some(p) = get_path_type(x, y, img, palette, check_solids, bounds) {
                 p == path_type
 
             } else {
@@ -653,6 +1015,7 @@ fn is_valid_path_pixel(
 fn is_potential_path_pixel(
     x: i32, y: i32,
     img: &image::DynamicImage,
+    palette: &Palette,
     path_type: TracedPathType,
     check_solids: bool,
     bounds: &[i32; 4]
@@ -660,7 +1023,7 @@ fn is_potential_path_pixel(
 ) -> bool {
 
     if x >= bounds[0] && x < bounds[2] && y >= bounds[1] && y < bounds[3] {
-        if let Some(p) = get_path_type(x, y, img, check_solids, bounds) {
+        if let Some(p) = get_path_type(x, y, img, palette, check_solids, bounds) {
             p == path_type
 
         } else {
@@ -673,47 +1036,74 @@ fn is_potential_path_pixel(
 
 }
 
-fn get_path_type(x: i32, y: i32, img: &image::DynamicImage, solids: bool, bounds: &[i32; 4]) -> Option<TracedPathType> {
+fn get_path_type(
+    x: i32, y: i32,
+    img: &image::DynamicImage,
+    palette: &Palette,
+    solids: bool,
+    bounds: &[i32; 4]
 
-    let pixel = img.get_pixel(x as u32, y as u32);
-    let (r, g, b) = (pixel.data[0], pixel.data[1], pixel.data[2]);
-    if solids && r == 255 && g == 0 && b == 255 {
-        if any_wall_or_edge(x, y, img, bounds) {
-            Some(TracedPathType::Solid)
+) -> Option<TracedPathType> {
 
-        } else {
-            None
-        }
+    let pixel = img.get_pixel(x as u32, y as u32);
+    match palette.get([pixel.data[0], pixel.data[1], pixel.data[2]]) {
+        Some(TracedPathType::Solid) => {
+            if solids && any_wall_or_edge(x, y, img, palette, bounds) {
+                Some(TracedPathType::Solid)
 
-    } else if r == 255 && g == 255 && b == 255 {
-        Some(TracedPathType::Wall)
+            } else {
+                None
+            }
+        },
+        path_type => path_type
+    }
+}
 
-    } else if r == 255 && g == 255 && b == 0 {
-        Some(TracedPathType::Light)
+fn any_wall_or_edge(x: i32, y: i32, img: &image::DynamicImage, palette: &Palette, bounds: &[i32; 4]) -> bool {
+    is_wall_or_edge(x, y - 1, img, palette, bounds) ||
+    is_wall_or_edge(x - 1, y, img, palette, bounds) ||
+    is_wall_or_edge(x + 1, y, img, palette, bounds) ||
+    is_wall_or_edge(x, y + 1, img, palette, bounds)
+}
 
-    } else if r == 0 && g == 255 && b == 255 {
-        Some(TracedPathType::Spawn)
+fn is_wall_or_edge(x: i32, y: i32, img: &image::DynamicImage, palette: &Palette, bounds: &[i32; 4]) -> bool {
+    if x < bounds[0] || x >= bounds[2] || y < bounds[1] || y >= bounds[3] {
+        true
 
     } else {
-        None
+        let pixel = img.get_pixel(x as u32, y as u32);
+        palette.is_wall([pixel.data[0], pixel.data[1], pixel.data[2]])
     }
+
 }
 
-fn any_wall_or_edge(x: i32, y: i32, img: &image::DynamicImage, bounds: &[i32; 4]) -> bool {
-    is_wall_or_edge(x, y - 1, img, bounds) ||
-    is_wall_or_edge(x - 1, y, img, bounds) ||
-    is_wall_or_edge(x + 1, y, img, bounds) ||
-    is_wall_or_edge(x, y + 1, img, bounds)
+fn parse_point(value: &str) -> Option<(f32, f32)> {
+    let mut parts = value.split(',').map(|v| v.trim().parse::<f32>());
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(x)), Some(Ok(y)), None) => Some((x, y)),
+        _ => None
+    }
 }
 
-fn is_wall_or_edge(x: i32, y: i32, img: &image::DynamicImage, bounds: &[i32; 4]) -> bool {
-    if x < bounds[0] || x >= bounds[2] || y < bounds[1] || y >= bounds[3] {
-        true
+fn parse_color(value: &str) -> Option<[u8; 3]> {
+    let value = value.trim_left_matches('#');
+    if value.len() == 6 {
+        let r = u8::from_str_radix(&value[0..2], 16);
+        let g = u8::from_str_radix(&value[2..4], 16);
+        let b = u8::from_str_radix(&value[4..6], 16);
+        match (r, g, b) {
+            (Ok(r), Ok(g), Ok(b)) => Some([r, g, b]),
+            _ => None
+        }
 
     } else {
-        let pixel = img.get_pixel(x as u32, y as u32);
-        pixel.data[0] == 255 && pixel.data[1] == 255 && pixel.data[2] == 255
+        None
     }
+}
 
+fn validate_color(value: String) -> Result<(), String> {
+    parse_color(&value).map(|_| ()).ok_or_else(|| {
+        format!("Invalid color \"{}\", expected RRGGBB.", value)
+    })
 }
 
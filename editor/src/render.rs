@@ -0,0 +1,223 @@
+// STD Dependencies -----------------------------------------------------------
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+
+// External Dependencies ------------------------------------------------------
+use image::{ImageBuffer, Rgb};
+
+
+// Internal Dependencies ------------------------------------------------------
+use shared::entity::PickupKind;
+use shared::level::{Level, LevelVisibility};
+use super::{Options, PickupType, TracedPathType};
+
+
+// Statics --------------------------------------------------------------------
+const RENDER_MARGIN: f32 = 8.0;
+const BACKGROUND_COLOR: [u8; 3] = [0, 0, 0];
+const VISIBILITY_COLOR: [u8; 3] = [255, 255, 255];
+
+
+// Map TOML -> PNG Renderer ---------------------------------------------------
+pub struct Visibility {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32
+}
+
+pub fn render(input: &Path, output: &Path, options: &Options, visibility: Option<&Visibility>) {
+
+    let mut toml = String::new();
+    if let Err(err) = File::open(input).and_then(|mut f| f.read_to_string(&mut toml)) {
+        println!("[Editor] Failed to open {}: {}", input.display(), err);
+        return;
+    }
+
+    let level = Level::from_toml_string(&toml);
+    if level.walls.is_empty() {
+        println!("[Editor] No walls found in {}", input.display());
+        return;
+    }
+
+    for &(index, ref err) in level.invalid_solids() {
+        println!("[Editor] Skipped invalid solid #{} in {}: {:?}", index, input.display(), err);
+    }
+
+    let canvas = Canvas::new(&level, options.scale);
+    let mut img = ImageBuffer::from_pixel(canvas.width, canvas.height, Rgb(BACKGROUND_COLOR));
+
+    // Solids
+    let solid_color = options.palette.color(TracedPathType::Solid);
+    for solid in &level.solids {
+        let points: Vec<[f32; 2]> = solid.iter().map(|p| canvas.w2p_point(p[0], p[1])).collect();
+        fill_polygon(&mut img, &points, solid_color, 1.0);
+    }
+
+    // Light radii, followed by their centers
+    let light_color = options.palette.color(TracedPathType::Light);
+    for light in &level.lights {
+        let (x, y) = canvas.w2p(light.x, light.y);
+        fill_circle(&mut img, x, y, light.radius / options.scale, light_color, 0.15);
+    }
+
+    // Walls
+    let wall_color = options.palette.color(TracedPathType::Wall);
+    for wall in &level.walls {
+        let (ax, ay) = canvas.w2p(wall.points[0], wall.points[1]);
+        let (bx, by) = canvas.w2p(wall.points[2], wall.points[3]);
+        draw_line(&mut img, ax, ay, bx, by, wall_color);
+    }
+
+    // Visibility overlay
+    if let Some(v) = visibility {
+        let points = level.visibility_polygon(v.x, v.y, v.radius);
+        for t in points.chunks(6).filter(|t| t.len() == 6) {
+            fill_polygon(&mut img, &[
+                canvas.w2p_point(t[0], t[1]),
+                canvas.w2p_point(t[2], t[3]),
+                canvas.w2p_point(t[4], t[5])
+
+            ], VISIBILITY_COLOR, 0.35);
+        }
+    }
+
+    // Markers
+    for light in &level.lights {
+        let (x, y) = canvas.w2p(light.x, light.y);
+        fill_circle(&mut img, x, y, 1.5, light_color, 1.0);
+    }
+
+    let spawn_color = options.palette.color(TracedPathType::Spawn);
+    for spawn in &level.spawns {
+        let (x, y) = canvas.w2p(spawn.x, spawn.y);
+        fill_circle(&mut img, x, y, 1.5, spawn_color, 1.0);
+    }
+
+    for pickup in &level.pickups {
+        let typ = TracedPathType::Pickup(match pickup.kind {
+            PickupKind::Health => PickupType::Health,
+            PickupKind::DamageBoost => PickupType::DamageBoost,
+            PickupKind::Speed => PickupType::Speed,
+            PickupKind::Shield => PickupType::Shield
+        });
+        let (x, y) = canvas.w2p(pickup.x, pickup.y);
+        fill_circle(&mut img, x, y, 1.5, options.palette.color(typ), 1.0);
+    }
+
+    if let Some(v) = visibility {
+        let (x, y) = canvas.w2p(v.x, v.y);
+        fill_circle(&mut img, x, y, 1.5, VISIBILITY_COLOR, 1.0);
+    }
+
+    match img.save(output) {
+        Ok(_) => println!("[Editor] Rendered {} -> {}", input.display(), output.display()),
+        Err(err) => println!("[Editor] Failed to write {}: {}", output.display(), err)
+    }
+
+}
+
+
+// Helpers --------------------------------------------------------------------
+type Image = ImageBuffer<Rgb<u8>, Vec<u8>>;
+
+struct Canvas {
+    width: u32,
+    height: u32,
+    origin: [f32; 2],
+    scale: f32
+}
+
+impl Canvas {
+
+    fn new(level: &Level, scale: f32) -> Canvas {
+        let b = &level.bounds;
+        Canvas {
+            width: ((b[2] - b[0]) / scale + RENDER_MARGIN * 2.0).ceil() as u32 + 1,
+            height: ((b[3] - b[1]) / scale + RENDER_MARGIN * 2.0).ceil() as u32 + 1,
+            origin: [b[0], b[1]],
+            scale: scale
+        }
+    }
+
+    fn w2p(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (x - self.origin[0]) / self.scale + RENDER_MARGIN,
+            (y - self.origin[1]) / self.scale + RENDER_MARGIN
+        )
+    }
+
+    fn w2p_point(&self, x: f32, y: f32) -> [f32; 2] {
+        let (px, py) = self.w2p(x, y);
+        [px, py]
+    }
+
+}
+
+fn blend_pixel(img: &mut Image, x: i32, y: i32, color: [u8; 3], alpha: f32) {
+    if x >= 0 && y >= 0 && (x as u32) < img.width() && (y as u32) < img.height() {
+        let pixel = img.get_pixel_mut(x as u32, y as u32);
+        for i in 0..3 {
+            let c = pixel.data[i] as f32 * (1.0 - alpha) + color[i] as f32 * alpha;
+            pixel.data[i] = c.round().max(0.0).min(255.0) as u8;
+        }
+    }
+}
+
+fn draw_line(img: &mut Image, ax: f32, ay: f32, bx: f32, by: f32, color: [u8; 3]) {
+    let (dx, dy) = (bx - ax, by - ay);
+    let steps = dx.abs().max(dy.abs()).ceil().max(1.0) as i32;
+    for i in 0..steps + 1 {
+        let f = i as f32 / steps as f32;
+        blend_pixel(img, (ax + dx * f).round() as i32, (ay + dy * f).round() as i32, color, 1.0);
+    }
+}
+
+fn fill_circle(img: &mut Image, cx: f32, cy: f32, radius: f32, color: [u8; 3], alpha: f32) {
+    let r = radius.ceil() as i32;
+    let (px, py) = (cx.round() as i32, cy.round() as i32);
+    for y in (py - r)..(py + r + 1) {
+        for x in (px - r)..(px + r + 1) {
+            let (dx, dy) = (x as f32 - cx, y as f32 - cy);
+            if dx * dx + dy * dy <= radius * radius {
+                blend_pixel(img, x, y, color, alpha);
+            }
+        }
+    }
+}
+
+fn fill_polygon(img: &mut Image, points: &[[f32; 2]], color: [u8; 3], alpha: f32) {
+
+    if points.len() < 3 {
+        return;
+    }
+
+    let min_y = points.iter().fold(points[0][1], |m, p| m.min(p[1])).floor() as i32;
+    let max_y = points.iter().fold(points[0][1], |m, p| m.max(p[1])).ceil() as i32;
+
+    // Even-odd scanline fill sampled at pixel centers
+    for y in min_y..(max_y + 1) {
+
+        let sy = y as f32 + 0.5;
+        let mut crossings = Vec::new();
+        for i in 0..points.len() {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            if (a[1] <= sy && b[1] > sy) || (b[1] <= sy && a[1] > sy) {
+                crossings.push(a[0] + (sy - a[1]) / (b[1] - a[1]) * (b[0] - a[0]));
+            }
+        }
+
+        crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        for span in crossings.chunks(2).filter(|s| s.len() == 2) {
+            let (from, to) = ((span[0] - 0.5).ceil() as i32, (span[1] - 0.5).floor() as i32);
+            for x in from..(to + 1) {
+                blend_pixel(img, x, y, color, alpha);
+            }
+        }
+
+    }
+
+}
+
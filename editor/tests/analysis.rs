@@ -0,0 +1,159 @@
+extern crate editor;
+extern crate shared;
+
+use std::path::Path;
+
+use editor::analysis::Report;
+use shared::level::Level;
+
+
+// Helpers --------------------------------------------------------------------
+fn box_walls(divider: &str) -> String {
+    format!(r#"
+        walls = [
+            {{ line = [0.0, 0.0, 100.0, 0.0] }},
+            {{ line = [100.0, 0.0, 100.0, 100.0] }},
+            {{ line = [0.0, 100.0, 100.0, 100.0] }},
+            {{ line = [0.0, 0.0, 0.0, 100.0] }},
+            {}
+        ]
+    "#, divider)
+}
+
+fn report(toml: &str) -> Report {
+    Report::new(Path::new("test.toml"), &Level::from_toml_string(toml))
+}
+
+fn divided_level(extra: &str) -> String {
+    // A 100x100 box split by a wall with a passage at its bottom
+    format!(r#"
+        {}
+        spawns = [
+            {{ x = 25.0, y = 20.0 }},
+            {{ x = 75.0, y = 20.0 }},
+            {{ x = 25.0, y = 90.0 }},
+            {{ x = 75.0, y = 90.0 }}
+        ]
+        {}
+    "#, box_walls("{ line = [50.0, 0.0, 50.0, 70.0] }"), extra)
+}
+
+
+// Reachability ---------------------------------------------------------------
+#[test]
+fn test_reachable_area() {
+    let report = report(&divided_level(""));
+    assert!(report.walkable_area > 0.0);
+    assert_eq!(report.reachable_area, report.walkable_area);
+    assert_eq!(report.spawns.len(), 4);
+    for spawn in &report.spawns {
+        assert_eq!(spawn.reachable_area, report.reachable_area);
+        assert_eq!(spawn.reachable_percent, 100.0);
+    }
+    assert!(report.unreachable_regions.is_empty());
+}
+
+#[test]
+fn test_unreachable_regions() {
+    let report = report(r#"
+        walls = [
+            { line = [0.0, 0.0, 100.0, 0.0] },
+            { line = [100.0, 0.0, 100.0, 100.0] },
+            { line = [0.0, 100.0, 100.0, 100.0] },
+            { line = [0.0, 0.0, 0.0, 100.0] },
+            { line = [50.0, 50.0, 80.0, 50.0] },
+            { line = [80.0, 50.0, 80.0, 80.0] },
+            { line = [50.0, 80.0, 80.0, 80.0] },
+            { line = [50.0, 50.0, 50.0, 80.0] }
+        ]
+        spawns = [{ x = 25.0, y = 25.0 }]
+    "#);
+
+    assert!(report.reachable_area < report.walkable_area);
+    assert!(report.spawns[0].reachable_percent < 100.0);
+    assert_eq!(report.unreachable_regions.len(), 1);
+
+    let region = &report.unreachable_regions[0];
+    assert!((region.x - 65.0).abs() < 2.0);
+    assert!((region.y - 65.0).abs() < 2.0);
+    assert!(region.area > 0.0 && region.area < 30.0 * 30.0);
+}
+
+#[test]
+fn test_unreachable_solids() {
+    let open = report(&divided_level(""));
+    let solid = report(&divided_level("solids = [[10.0, 30.0, 40.0, 30.0, 40.0, 50.0, 10.0, 50.0]]"));
+    assert!(solid.walkable_area < open.walkable_area - 30.0 * 20.0);
+}
+
+
+// Sight Lines ----------------------------------------------------------------
+#[test]
+fn test_spawn_sight_lines() {
+    let report = report(&divided_level(""));
+    let lines: Vec<(usize, usize)> = report.spawn_sight_lines.iter().map(|l| (l.a, l.b)).collect();
+    assert_eq!(lines, vec![(0, 2), (1, 3), (2, 3)]);
+    assert_eq!(report.spawn_sight_lines[2].distance, 50.0);
+}
+
+
+// Light Coverage -------------------------------------------------------------
+#[test]
+fn test_light_coverage() {
+    assert_eq!(report(&divided_level("")).light_coverage, 0.0);
+
+    let report = report(&divided_level("lights = [{ x = 25.0, y = 20.0, radius = 30.0 }]"));
+    assert!(report.light_coverage > 10.0);
+    assert!(report.light_coverage < 50.0);
+}
+
+
+// Wall Gaps ------------------------------------------------------------------
+#[test]
+fn test_no_wall_gaps() {
+    assert!(report(&divided_level("")).wall_gaps.is_empty());
+}
+
+#[test]
+fn test_wall_gap() {
+    let report = report(&box_walls("{ line = [50.0, 0.0, 50.0, 95.0] }"));
+    assert_eq!(report.wall_gaps.len(), 1);
+
+    let gap = &report.wall_gaps[0];
+    assert_eq!((gap.wall, gap.other), (4, 2));
+    assert_eq!((gap.x, gap.y), (50.0, 95.0));
+    assert_eq!(gap.width, 5.0);
+}
+
+#[test]
+fn test_covered_wall_gap() {
+    // The gap between both halves of the middle wall is covered by another
+    // wall, only the narrow passages on the sides of the cover leak
+    let report = report(&box_walls(r#"
+        { line = [0.0, 50.0, 48.0, 50.0] },
+        { line = [52.0, 50.0, 100.0, 50.0] },
+        { line = [45.0, 55.0, 55.0, 55.0] }
+    "#));
+
+    let gaps: Vec<(usize, usize)> = report.wall_gaps.iter().map(|g| (g.wall, g.other)).collect();
+    assert_eq!(gaps, vec![(6, 4), (6, 5)]);
+}
+
+
+// Invalid Solids -------------------------------------------------------------
+#[test]
+fn test_invalid_solids() {
+    let report = report(&format!(r#"
+        {}
+        solids = [
+            [10.0, 10.0, 20.0, 10.0, 20.0, 20.0, 10.0, 20.0],
+            [30.0, 10.0, 40.0, 20.0, 40.0, 10.0, 30.0, 20.0]
+        ]
+    "#, box_walls("")));
+
+    assert_eq!(report.invalid_solids.len(), 1);
+    assert_eq!(report.invalid_solids[0].solid, 1);
+    assert_eq!(report.invalid_solids[0].error, "SelfIntersection(0, 2)");
+    assert!(report.to_text().contains("solid #1 was skipped: SelfIntersection(0, 2)"));
+}
+
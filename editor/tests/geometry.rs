@@ -0,0 +1,162 @@
+extern crate editor;
+
+use editor::geometry::{extend_line_endpoints, merge_collinear_lines, normalize_line, point_line_distance, simplify_path};
+
+
+// Path Simplification --------------------------------------------------------
+#[test]
+fn test_point_line_distance() {
+    assert_eq!(point_line_distance(&[5.0, 3.0], &[0.0, 0.0], &[10.0, 0.0]), 3.0);
+    assert_eq!(point_line_distance(&[20.0, 0.0], &[0.0, 0.0], &[10.0, 0.0]), 0.0);
+    assert_eq!(point_line_distance(&[3.0, 4.0], &[0.0, 0.0], &[0.0, 0.0]), 5.0);
+}
+
+#[test]
+fn test_simplify_path_short() {
+    let points = vec![[0.0, 0.0], [4.0, 2.0]];
+    assert_eq!(simplify_path(&points, 1.0), points);
+}
+
+#[test]
+fn test_simplify_path_staircase() {
+    let points = vec![
+        [0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [2.0, 1.0], [2.0, 2.0], [3.0, 2.0], [3.0, 3.0]
+    ];
+    assert_eq!(simplify_path(&points, 1.0), vec![[0.0, 0.0], [3.0, 3.0]]);
+    assert_eq!(simplify_path(&points, 0.1), points);
+}
+
+#[test]
+fn test_simplify_path_keeps_corners() {
+    let points = vec![[0.0, 0.0], [5.0, 0.2], [10.0, 0.0], [10.0, 10.0]];
+    assert_eq!(simplify_path(&points, 1.0), vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0]]);
+}
+
+
+// Line Merging ---------------------------------------------------------------
+#[test]
+fn test_normalize_line() {
+    assert_eq!(normalize_line([5.0, 0.0, 1.0, 0.0]), [1.0, 0.0, 5.0, 0.0]);
+    assert_eq!(normalize_line([0.0, 5.0, 0.0, 1.0]), [0.0, 1.0, 0.0, 5.0]);
+    assert_eq!(normalize_line([4.0, 0.0, 0.0, 4.0]), [0.0, 4.0, 4.0, 0.0]);
+    assert_eq!(normalize_line([0.0, 4.0, 4.0, 0.0]), [0.0, 4.0, 4.0, 0.0]);
+}
+
+#[test]
+fn test_merge_overlapping_lines() {
+    assert_eq!(
+        merge_collinear_lines(&[0.0, 0.0, 6.0, 0.0], &[4.0, 0.0, 10.0, 0.0], 0.0),
+        Some([0.0, 0.0, 10.0, 0.0])
+    );
+    assert_eq!(
+        merge_collinear_lines(&[0.0, 0.0, 10.0, 0.0], &[2.0, 0.0, 4.0, 0.0], 0.0),
+        Some([0.0, 0.0, 10.0, 0.0])
+    );
+}
+
+#[test]
+fn test_merge_touching_lines() {
+    assert_eq!(
+        merge_collinear_lines(&[0.0, 0.0, 0.0, 5.0], &[0.0, 5.0, 0.0, 9.0], 0.0),
+        Some([0.0, 0.0, 0.0, 9.0])
+    );
+}
+
+#[test]
+fn test_merge_normalizes_end_points() {
+    // Reversed horizontal lines
+    assert_eq!(
+        merge_collinear_lines(&[10.0, 0.0, 6.0, 0.0], &[5.0, 0.0, 0.0, 0.0], 1.0),
+        Some([0.0, 0.0, 10.0, 0.0])
+    );
+
+    // Reversed diagonal lines
+    assert_eq!(
+        merge_collinear_lines(&[4.0, 4.0, 2.0, 2.0], &[2.0, 2.0, 0.0, 0.0], 0.0),
+        Some([0.0, 0.0, 4.0, 4.0])
+    );
+    assert_eq!(
+        merge_collinear_lines(&[0.0, 0.0, 2.0, 2.0], &[4.0, 4.0, 2.0, 2.0], 0.0),
+        Some([0.0, 0.0, 4.0, 4.0])
+    );
+}
+
+#[test]
+fn test_merge_gap() {
+    let (a, b) = ([0.0, 0.0, 5.0, 0.0], [6.0, 0.0, 10.0, 0.0]);
+    assert_eq!(merge_collinear_lines(&a, &b, 0.0), None);
+    assert_eq!(merge_collinear_lines(&a, &b, 1.0), Some([0.0, 0.0, 10.0, 0.0]));
+    assert_eq!(merge_collinear_lines(&b, &a, 1.0), Some([0.0, 0.0, 10.0, 0.0]));
+
+    // Adjacent pixels on a diagonal are a single step apart
+    let (a, b) = ([0.0, 0.0, 3.0, 3.0], [4.0, 4.0, 6.0, 6.0]);
+    assert_eq!(merge_collinear_lines(&a, &b, 1.0), Some([0.0, 0.0, 6.0, 6.0]));
+}
+
+#[test]
+fn test_merge_keeps_doorways() {
+    let (a, b) = ([0.0, 0.0, 5.0, 0.0], [7.0, 0.0, 10.0, 0.0]);
+    assert_eq!(merge_collinear_lines(&a, &b, 1.0), None);
+    assert_eq!(merge_collinear_lines(&a, &b, 2.0), Some([0.0, 0.0, 10.0, 0.0]));
+}
+
+#[test]
+fn test_merge_requires_collinear_lines() {
+    // Parallel
+    assert_eq!(merge_collinear_lines(&[0.0, 0.0, 5.0, 0.0], &[0.0, 1.0, 5.0, 1.0], 1.0), None);
+
+    // Perpendicular
+    assert_eq!(merge_collinear_lines(&[0.0, 0.0, 5.0, 0.0], &[5.0, 0.0, 5.0, 5.0], 1.0), None);
+
+    // Degenerate
+    assert_eq!(merge_collinear_lines(&[1.0, 1.0, 1.0, 1.0], &[0.0, 1.0, 5.0, 1.0], 1.0), None);
+}
+
+
+// Endpoint Extension ---------------------------------------------------------
+#[test]
+fn test_extend_line_endpoints() {
+
+    // A vertical wall ending right in front of a corner
+    let mut lines = [
+        [0.0, 0.0, 0.0, 4.0],
+        [0.0, 5.0, 5.0, 5.0],
+        [0.0, 5.0, 0.0, 10.0]
+    ];
+    extend_line_endpoints(&mut lines);
+    assert_eq!(lines[0], [0.0, 0.0, 0.0, 5.0]);
+    assert_eq!(lines[1], [0.0, 5.0, 5.0, 5.0]);
+
+}
+
+#[test]
+fn test_extend_diagonal_line_endpoints() {
+
+    // Diagonal walls are extended along their direction onto the corner
+    let mut lines = [
+        [0.0, 0.0, 4.0, 4.0],
+        [5.0, 5.0, 10.0, 5.0],
+        [5.0, 5.0, 5.0, 10.0]
+    ];
+    extend_line_endpoints(&mut lines);
+    assert_eq!(lines[0], [0.0, 0.0, 5.0, 5.0]);
+
+    let mut lines = [
+        [0.0, 4.0, 4.0, 0.0],
+        [-1.0, 5.0, -1.0, 10.0],
+        [-1.0, 5.0, -6.0, 5.0]
+    ];
+    extend_line_endpoints(&mut lines);
+    assert_eq!(lines[0], [-1.0, 5.0, 4.0, 0.0]);
+
+    // Corners next to the end of a diagonal wall do not bend it
+    let mut lines = [
+        [0.0, 0.0, 4.0, 4.0],
+        [5.0, 4.0, 10.0, 4.0],
+        [5.0, 4.0, 5.0, 10.0]
+    ];
+    extend_line_endpoints(&mut lines);
+    assert_eq!(lines[0], [0.0, 0.0, 4.0, 4.0]);
+
+}
+
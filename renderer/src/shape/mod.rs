@@ -3,7 +3,6 @@ mod line;
 mod circle;
 mod circle_arc;
 mod polygon;
-mod util;
 
 
 // Re-Exports -----------------------------------------------------------------
@@ -4,7 +4,6 @@ use graphics::Context;
 
 // Internal Dependencies ------------------------------------------------------
 use ::Renderer;
-use super::util::triangulate;
 
 
 // Concave Polygon ------------------------------------------------------------
@@ -16,10 +15,10 @@ pub struct Polygon {
 
 impl Polygon {
 
-    pub fn new(points: &[[f32; 2]]) -> Polygon {
+    pub fn new(vertices: Vec<f32>) -> Polygon {
 
         let mut bounds = [1000000.0f32, 1000000.0, -100000.0, -1000000.0];
-        for p in points {
+        for p in vertices.chunks(2) {
             bounds[0] = bounds[0].min(p[0]);
             bounds[1] = bounds[1].min(p[1]);
             bounds[2] = bounds[2].max(p[0]);
@@ -28,7 +27,7 @@ impl Polygon {
 
         Polygon {
             aabb: bounds,
-            vertices: Polygon::vertices(points)
+            vertices: vertices
         }
     }
 
@@ -36,10 +35,6 @@ impl Polygon {
         renderer.draw_triangle_list(&context.transform, &self.vertices);
     }
 
-    pub fn vertices(points: &[[f32; 2]]) -> Vec<f32> {
-        triangulate(points.to_vec())
-    }
-
 }
 
 
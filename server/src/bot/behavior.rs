@@ -0,0 +1,97 @@
+// External Dependencies ------------------------------------------------------
+use rand;
+use rand::Rng;
+use cobalt::ConnectionID;
+
+
+// Internal Dependencies ------------------------------------------------------
+use shared::level::{Level, LevelNavigation, LevelVisibility};
+use shared::entity::PLAYER_RADIUS;
+
+
+// Statics --------------------------------------------------------------------
+const SHADOW_SAMPLE_ATTEMPTS: usize = 16;
+
+
+// Bot Behaviors --------------------------------------------------------------
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Preference {
+    Patrol,
+    Light,
+    Shadow
+}
+
+impl Preference {
+    pub fn random() -> Preference {
+        match rand::thread_rng().gen_range(0, 3) {
+            0 => Preference::Patrol,
+            1 => Preference::Light,
+            _ => Preference::Shadow
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Behavior {
+
+    /// Walk between spawns and pickups
+    Patrol,
+
+    /// Walk towards lights in order to spot other players
+    SeekLight,
+
+    /// Walk towards unlit areas in order to stay hidden
+    SeekShadow,
+
+    /// Follow another player to the last position it was seen at
+    Chase {
+        target: ConnectionID,
+        x: f32,
+        y: f32
+    }
+
+}
+
+impl Behavior {
+
+    pub fn idle(preference: Preference) -> Behavior {
+        match preference {
+            Preference::Patrol => Behavior::Patrol,
+            Preference::Light => Behavior::SeekLight,
+            Preference::Shadow => Behavior::SeekShadow
+        }
+    }
+
+    pub fn destination(&self, level: &Level) -> Option<(f32, f32)> {
+        match *self {
+            Behavior::Patrol => {
+                let mut points: Vec<(f32, f32)> = level.spawns.iter().map(|s| (s.x, s.y)).collect();
+                points.extend(level.pickups.iter().map(|p| (p.x, p.y)));
+                rand::thread_rng().choose(&points).cloned()
+            },
+            Behavior::SeekLight => {
+                let points: Vec<(f32, f32)> = level.lights.iter().map(|l| (l.x, l.y)).collect();
+                rand::thread_rng().choose(&points).cloned()
+            },
+            Behavior::SeekShadow => {
+                let mut rng = rand::thread_rng();
+                let b = level.bounds;
+
+                // Levels without any walls have empty bounds
+                if b[0] >= b[2] || b[1] >= b[3] {
+                    return None;
+                }
+
+                (0..SHADOW_SAMPLE_ATTEMPTS).map(|_| {
+                    (rng.gen_range(b[0], b[2]), rng.gen_range(b[1], b[3]))
+
+                }).find(|&(x, y)| {
+                    level.is_walkable(x, y) && !level.circle_in_light(x, y, PLAYER_RADIUS)
+                })
+            },
+            Behavior::Chase { x, y, .. } => Some((x, y))
+        }
+    }
+
+}
+
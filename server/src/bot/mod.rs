@@ -0,0 +1,262 @@
+// STD Dependencies -----------------------------------------------------------
+use std::f32::consts;
+use std::collections::VecDeque;
+
+
+// External Dependencies ------------------------------------------------------
+use rand;
+use rand::Rng;
+use hexahydrate;
+use cobalt::ConnectionID;
+
+
+// Internal Dependencies ------------------------------------------------------
+use shared::color::ColorName;
+use shared::util;
+use shared::weapon::Weapon;
+use shared::action::Action;
+use shared::level::{Level, LevelCollision, LevelNavigation, LevelVisibility};
+use shared::entity::{PlayerData, PlayerInput};
+
+
+// Modules --------------------------------------------------------------------
+mod behavior;
+pub use self::behavior::{Behavior, Preference};
+
+
+// Statics --------------------------------------------------------------------
+const BOT_MAX_AIM_ERROR: f32 = consts::PI * 0.1;
+const BOT_REPATH_INTERVAL: u64 = 500;
+const BOT_ENGAGE_DISTANCE: f32 = 50.0;
+const BOT_WAYPOINT_DISTANCE: f32 = 3.0;
+
+
+// Bot Configuration ----------------------------------------------------------
+#[derive(Debug, Copy, Clone)]
+pub struct BotConfig {
+
+    /// Time in milliseconds before a bot fires at a newly spotted player
+    pub reaction_time: u64,
+
+    /// Aiming accuracy from 0.0 (up to `BOT_MAX_AIM_ERROR` off) to 1.0 (perfect)
+    pub accuracy: f32
+
+}
+
+impl Default for BotConfig {
+    fn default() -> BotConfig {
+        BotConfig {
+            reaction_time: 350,
+            accuracy: 0.75
+        }
+    }
+}
+
+
+// Server Side Bots -----------------------------------------------------------
+pub struct Bot {
+    pub id: ConnectionID,
+    pub entity_slot: hexahydrate::ServerEntitySlot,
+    pub color: ColorName,
+    pub actions: VecDeque<Action>,
+    config: BotConfig,
+    weapon: Weapon,
+    preference: Preference,
+    behavior: Behavior,
+    path: Vec<[f32; 2]>,
+    path_time: u64,
+    spotted: Option<(ConnectionID, u64)>,
+    aim_error: f32,
+    last_weapon_fire: u64,
+    tick: u16
+}
+
+impl Bot {
+
+    pub fn new(
+        id: ConnectionID,
+        entity_slot: hexahydrate::ServerEntitySlot,
+        color: ColorName,
+        config: BotConfig
+
+    ) -> Bot {
+        let weapon = *rand::thread_rng().choose(&[
+            Weapon::LaserBeam, Weapon::Rocket, Weapon::Shotgun
+
+        ]).unwrap();
+        let preference = Preference::random();
+        Bot {
+            id: id,
+            entity_slot: entity_slot,
+            color: color,
+            actions: VecDeque::new(),
+            config: config,
+            weapon: weapon,
+            preference: preference,
+            behavior: Behavior::idle(preference),
+            path: Vec::new(),
+            path_time: 0,
+            spotted: None,
+            aim_error: 0.0,
+            last_weapon_fire: 0,
+            tick: 0
+        }
+    }
+
+    /// Generates the bot's input for the next tick.
+    ///
+    /// Bots only know about players which are within their own visibility,
+    /// the exact same check which decides whether a player's entity is
+    /// synchronized to a human client.
+    pub fn update(
+        &mut self,
+        t: u64,
+        server_tick: u16,
+        level: &Level,
+        data: &PlayerData,
+        players: &[(Option<ConnectionID>, PlayerData)]
+
+    ) -> PlayerInput {
+
+        self.tick = self.tick.wrapping_add(1);
+
+        // Forget everything while dead
+        if data.hp == 0 {
+            self.behavior = Behavior::idle(self.preference);
+            self.path.clear();
+            self.spotted = None;
+            return PlayerInput::new(self.tick, 0, data.r, 0.0);
+        }
+
+        // Find the closest visible opponent
+        let visible = players.iter().filter(|&&(id, ref other)| {
+            id.is_some() && id != Some(self.id) && other.hp > 0
+                && level.player_within_visibility(data, other)
+
+        }).fold(None, |nearest: Option<(f32, ConnectionID, &PlayerData)>, &(id, ref other)| {
+            let d = util::distance(data.x, data.y, other.x, other.y);
+            match nearest {
+                Some(n) if n.0 <= d => Some(n),
+                _ => Some((d, id.unwrap(), other))
+            }
+        });
+
+        let mut r = data.r;
+        if let Some((distance, target, other)) = visible {
+
+            // Newly spotted players need to be reacted to first
+            let spotted = self.spotted;
+            let spotted_since = match spotted {
+                Some((id, since)) if id == target => since,
+                _ => {
+                    self.aim_error = self.random_aim_error();
+                    self.spotted = Some((target, t));
+                    t
+                }
+            };
+
+            self.set_behavior(Behavior::Chase {
+                target: target,
+                x: other.x,
+                y: other.y
+            });
+
+            r = util::angle(other.x, other.y, data.x, data.y) + self.aim_error;
+
+            let stats = self.weapon.stats();
+            if t >= spotted_since + self.config.reaction_time
+                && t >= self.last_weapon_fire + stats.fire_interval
+                && distance <= stats.range
+                && level.collide_line(&[data.x, data.y, other.x, other.y]).is_none() {
+
+                // Bots see the current server state, their view tick is the
+                // server tick and shots are not rewound by the state delay
+                self.actions.push_back(Action::FiredWeapon(self.weapon.to_u8(), self.tick, server_tick, r));
+                self.last_weapon_fire = t;
+                self.aim_error = self.random_aim_error();
+
+            }
+
+            // Stand ground once close enough
+            if distance < BOT_ENGAGE_DISTANCE.min(stats.range) {
+                self.path.clear();
+                return PlayerInput::new(self.tick, 0, r, 0.0);
+            }
+
+        } else {
+            self.spotted = None;
+        }
+
+        // Re-plan when there is nowhere left to go or a chased player moved
+        let chasing = match self.behavior {
+            Behavior::Chase { .. } => true,
+            _ => false
+        };
+
+        if (self.path.is_empty() || chasing) && t >= self.path_time + BOT_REPATH_INTERVAL {
+            if chasing && self.path.is_empty() && visible.is_none() {
+                self.behavior = Behavior::idle(self.preference);
+            }
+            self.plan_path(t, level, data);
+        }
+
+        // Follow the path
+        while !self.path.is_empty() && util::distance(data.x, data.y, self.path[0][0], self.path[0][1]) < BOT_WAYPOINT_DISTANCE {
+            self.path.remove(0);
+        }
+
+        let buttons = if let Some(next) = self.path.first() {
+            let move_r = util::angle(next[0], next[1], data.x, data.y);
+            if visible.is_none() {
+                r = move_r;
+            }
+            direction_buttons(move_r)
+
+        } else {
+            0
+        };
+
+        PlayerInput::new(self.tick, buttons, r, 0.0)
+
+    }
+
+    fn set_behavior(&mut self, behavior: Behavior) {
+        let target_changed = match (self.behavior, behavior) {
+            (Behavior::Chase { target: a, .. }, Behavior::Chase { target: b, .. }) => a != b,
+            _ => true
+        };
+        if target_changed {
+            self.path.clear();
+            self.path_time = 0;
+        }
+        self.behavior = behavior;
+    }
+
+    fn plan_path(&mut self, t: u64, level: &Level, data: &PlayerData) {
+        self.path_time = t;
+        self.path = self.behavior.destination(level).and_then(|(x, y)| {
+            level.find_path(data.x, data.y, x, y)
+
+        }).unwrap_or_else(Vec::new);
+    }
+
+    fn random_aim_error(&self) -> f32 {
+        let error = (1.0 - self.config.accuracy.max(0.0).min(1.0)) * BOT_MAX_AIM_ERROR;
+        if error > 0.0 {
+            rand::thread_rng().gen_range(-error, error)
+
+        } else {
+            0.0
+        }
+    }
+
+}
+
+
+// Helpers --------------------------------------------------------------------
+fn direction_buttons(r: f32) -> u8 {
+    // Buttons for right, down-right, down, down-left, left, up-left, up and up-right
+    let sector = ((r / (consts::PI * 0.25)).round() as i32 + 8) % 8;
+    [2, 6, 4, 12, 8, 9, 1, 3][sector as usize]
+}
+
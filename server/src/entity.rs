@@ -1,39 +1,95 @@
 // External Dependencies ------------------------------------------------------
 use hexahydrate;
 use cobalt::ConnectionID;
-use netsync::{ServerState, NetworkState};
+use netsync::{ServerState, NetworkState, NetworkInput};
 
 
 // Internal Dependencies ------------------------------------------------------
 use ::shared::color::ColorName;
+use ::shared::weapon::Weapon;
 use ::shared::level::{Level, LevelSpawn};
 use ::shared::entity::{
     PlayerInput, PlayerData, PlayerEntity,
+    PickupEntity, PickupKind,
+    ProjectileInput, ProjectileData, ProjectileEntity,
     PLAYER_MAX_HP,
-    PLAYER_BEAM_FIRE_INTERVAL
+    PLAYER_BUFF_SHIELD, PLAYER_BUFF_SHIELD_FACTOR,
+    PICKUP_HEALTH_AMOUNT, PICKUP_BUFF_DURATION
 };
 
 
 // Server Entity --------------------------------------------------------------
-type ServerPlayerEntity = PlayerEntity<ServerState<PlayerData, PlayerInput>>;
+pub type ServerPlayerEntity = PlayerEntity<ServerState<PlayerData, PlayerInput>>;
+pub type ServerProjectileEntity = ProjectileEntity<ServerState<ProjectileData, ProjectileInput>>;
 
 pub trait Entity: hexahydrate::Entity<ConnectionID> {
+
+    fn update(&mut self, dt: f32, level: &Level);
+
+    fn as_player(&self) -> Option<&Player> {
+        None
+    }
+
+    fn as_player_mut(&mut self) -> Option<&mut Player> {
+        None
+    }
+
+    fn as_pickup_mut(&mut self) -> Option<&mut PickupEntity> {
+        None
+    }
+
+    fn as_projectile_mut(&mut self) -> Option<&mut ServerProjectileEntity> {
+        None
+    }
+
+}
+
+pub trait Player {
     fn owner(&self) -> Option<ConnectionID>;
     fn is_alive(&self) -> bool;
-    fn tick_diff(&self, tick: u8, tick_delay: u8) -> u8;
+    fn tick_diff(&self, tick: u16, tick_delay: u8) -> u8;
     fn relative_data(&self, ticks_ago: u8) -> PlayerData;
     fn current_data(&self) -> PlayerData;
     fn color_name(&self) -> ColorName;
     fn set_visibility(&mut self, ConnectionID, bool);
     fn get_visibility(&self, connection_id: ConnectionID) -> bool;
-    fn fire_beam(&mut self, t: u64) -> bool;
+    fn fire_weapon(&mut self, weapon: Weapon, t: u64) -> bool;
     fn damage(&mut self, amount: u8);
     fn respawn(&mut self, spawn: LevelSpawn);
-    fn update(&mut self, dt: f32, level: &Level);
+    fn buffs(&self) -> u8;
+    fn apply_pickup(&mut self, kind: PickupKind, t: u64) -> bool;
+    fn update_buffs(&mut self, t: u64);
+    fn input(&mut self, input: PlayerInput);
 }
 
 impl Entity for ServerPlayerEntity {
 
+    fn update(&mut self, dt: f32, level: &Level) {
+        self.state.update_with(|state, _, input| {
+            // Dead players stay in place until they respawn
+            if state.hp > 0 {
+                if let Some(input) = input {
+                    PlayerData::update(dt, state, input, level);
+
+                } else {
+                    PlayerData::update_extrapolated(state, level);
+                }
+            }
+        });
+    }
+
+    fn as_player(&self) -> Option<&Player> {
+        Some(self)
+    }
+
+    fn as_player_mut(&mut self) -> Option<&mut Player> {
+        Some(self)
+    }
+
+}
+
+impl Player for ServerPlayerEntity {
+
     fn owner(&self) -> Option<ConnectionID> {
         self.owner
     }
@@ -42,7 +98,7 @@ impl Entity for ServerPlayerEntity {
         self.state.get_relative(0).hp > 0
     }
 
-    fn tick_diff(&self, tick: u8, tick_delay: u8) -> u8 {
+    fn tick_diff(&self, tick: u16, tick_delay: u8) -> u8 {
         self.state.get_client_tick_diff(tick, tick_delay)
     }
 
@@ -71,31 +127,117 @@ impl Entity for ServerPlayerEntity {
         }
     }
 
-    fn fire_beam(&mut self, t: u64) -> bool {
+    fn fire_weapon(&mut self, weapon: Weapon, t: u64) -> bool {
         // The client also limits the firing rate, however we want to make sure
         // that we always accept the firing command if the client limited correclty
-        self.fire_beam(PLAYER_BEAM_FIRE_INTERVAL - 15, t)
+        self.fire_weapon(weapon.stats().fire_interval - 15, t)
     }
 
     fn damage(&mut self, amount: u8) {
         self.state.apply(|data| {
+
+            // Shields absorb parts of the incoming damage
+            let amount = if data.buffs & PLAYER_BUFF_SHIELD == PLAYER_BUFF_SHIELD {
+                (amount as f32 * PLAYER_BUFF_SHIELD_FACTOR).round() as u8
+
+            } else {
+                amount
+            };
+
             data.hp = data.hp.saturating_sub(amount);
+
         });
     }
 
     fn respawn(&mut self, spawn: LevelSpawn) {
+        self.buff_timers.clear();
         self.state.apply(|data| {
             data.hp = PLAYER_MAX_HP;
+            data.buffs = 0;
             data.x = spawn.x;
             data.y = spawn.y;
         });
     }
 
-    fn update(&mut self, dt: f32, level: &Level) {
-        self.state.update_with(|state, _, input| {
-            PlayerData::update(dt, state, input.unwrap(), level);
+    fn buffs(&self) -> u8 {
+        self.state.get_relative(0).buffs
+    }
+
+    fn apply_pickup(&mut self, kind: PickupKind, t: u64) -> bool {
+
+        if let Some(buff) = kind.buff() {
+
+            // Collecting the same buff again extends its duration
+            self.buff_timers.retain(|&(b, _)| b != buff);
+            self.buff_timers.push((buff, t + PICKUP_BUFF_DURATION));
+            self.state.apply(|data| {
+                data.buffs |= buff;
+            });
+            true
+
+        // Health is applied instantly, but only if the player is actually
+        // missing any
+        } else if self.state.get_relative(0).hp < PLAYER_MAX_HP {
+            self.state.apply(|data| {
+                data.hp = data.hp.saturating_add(PICKUP_HEALTH_AMOUNT);
+            });
+            true
+
+        } else {
+            false
+        }
+
+    }
+
+    fn update_buffs(&mut self, t: u64) {
+
+        let mut expired = 0;
+        self.buff_timers.retain(|&(buff, expires)| {
+            if t >= expires {
+                expired |= buff;
+                false
+
+            } else {
+                true
+            }
         });
+
+        if expired != 0 {
+            self.state.apply(|data| {
+                data.buffs &= !expired;
+            });
+        }
+
+    }
+
+    fn input(&mut self, input: PlayerInput) {
+        // Inputs of entities without a connection go through the same path
+        // as the ones received over the network
+        if self.state.get_relative(0).hp > 0 {
+            self.state.receive(&input.to_bytes());
+        }
+    }
+
+}
+
+impl Entity for PickupEntity {
+
+    fn update(&mut self, _: f32, _: &Level) {
+    }
+
+    fn as_pickup_mut(&mut self) -> Option<&mut PickupEntity> {
+        Some(self)
     }
 
 }
 
+impl Entity for ServerProjectileEntity {
+
+    fn update(&mut self, _: f32, _: &Level) {
+    }
+
+    fn as_projectile_mut(&mut self) -> Option<&mut ServerProjectileEntity> {
+        Some(self)
+    }
+
+}
@@ -14,24 +14,21 @@ use shared::level::{Level, LevelCollision};
 use shared::collision::line_segment_intersect_circle;
 
 
-// Statics --------------------------------------------------------------------
-const LASER_BEAM_LENGTH: f32 = 90.0;
-
-
 // Laser Beam Helpers ---------------------------------------------------------
 pub fn create(
     level: &Level,
-    p: &PlayerData
+    p: &PlayerData,
+    r: f32,
+    length: f32
 
 ) -> ([f32; 4], f32, f32, Option<usize>) {
 
-    let (mut x, mut y, r, mut l) = (
+    let (mut x, mut y, mut l) = (
         // We move the origin of the beam into the player
         // in order to avoid wall clipping
-        p.x + p.r.cos() * (PLAYER_RADIUS - 0.5),
-        p.y + p.r.sin() * (PLAYER_RADIUS - 0.5),
-        p.r,
-        LASER_BEAM_LENGTH
+        p.x + r.cos() * (PLAYER_RADIUS - 0.5),
+        p.y + r.sin() * (PLAYER_RADIUS - 0.5),
+        length
     );
 
     // Collide with level walls
@@ -9,6 +9,8 @@
 
 
 // Crates ---------------------------------------------------------------------
+#[macro_use]
+extern crate log;
 extern crate rand;
 extern crate hyper;
 extern crate clock_ticks;
@@ -20,23 +22,32 @@ extern crate shared;
 
 // STD Dependencies -----------------------------------------------------------
 use std::thread;
+use std::sync::{Arc, Mutex};
 
 
 // External Dependencies ------------------------------------------------------
 use cobalt::ConnectionID;
+use netsync::NetworkConditions;
 
 
 // Internal Dependencies ------------------------------------------------------
-use ::entity::Entity;
-use ::server::Server;
+use shared::replay::ReplayRecorder;
 use shared::Timer as SharedTimer;
 use shared::level::Level;
+use shared::metrics::ServerMetrics;
+use shared::status::{ServerStatus, STATUS_PATH};
+use shared::color::ColorName;
 
 
 // Modules --------------------------------------------------------------------
+mod bot;
 mod entity;
 mod laser_beam;
 mod server;
+pub use ::bot::BotConfig;
+pub use ::entity::Entity;
+pub use ::server::Server;
+pub use netsync::MissingInputPolicy;
 
 
 // Types ----------------------------------------------------------------------
@@ -45,16 +56,30 @@ pub type Timer = SharedTimer<Server, hexahydrate::Server<Entity, ConnectionID>,
 
 // Statics --------------------------------------------------------------------
 pub const DEFAULT_LEVEL_DATA: &'static str = include_str!("../../editor/map.toml");
+pub const DEFAULT_LEVEL_NAME: &'static str = "map";
+pub const DEFAULT_SERVER_NAME: &'static str = "Shooter Server";
+pub const GAME_MODE: &'static str = "deathmatch";
 
 
 // Server Runner ---------------------------------------------------------------
-pub fn run(addr: String) -> thread::JoinHandle<()> {
-
+pub fn run(
+    addr: String,
+    name: String,
+    bots: usize,
+    bot_config: BotConfig,
+    replay: Option<String>,
+    conditions: NetworkConditions,
+    missing_input_policy: MissingInputPolicy
+
+) -> thread::JoinHandle<()> {
+
+    let metrics = Arc::new(Mutex::new(ServerMetrics::new(clock_ticks::precise_time_ms())));
+    let http_metrics = metrics.clone();
     let http_addr = addr.clone();
     thread::spawn(move || {
         let server = hyper::server::Server::http(http_addr.as_str()).unwrap();
-        server.handle(|_: hyper::server::Request, res: hyper::server::Response| {
-            res.send(&DEFAULT_LEVEL_DATA.to_string().into_bytes()).ok();
+        server.handle(move |req: hyper::server::Request, res: hyper::server::Response| {
+            handle_http(req, res, name.as_str(), &http_metrics);
 
         }).ok();
     });
@@ -75,10 +100,26 @@ pub fn run(addr: String) -> thread::JoinHandle<()> {
 
         let mut timer = Timer::new();
         let mut server = Server::new(addr, shared::UPDATES_PER_SECOND);
+        server.set_bot_fill(bots, bot_config);
+        server.set_network_conditions(conditions);
+        server.set_missing_input_policy(missing_input_policy);
+        server.set_metrics_output(metrics);
+
+        if let Some(path) = replay {
+            match ReplayRecorder::create(&path, DEFAULT_LEVEL_DATA, shared::UPDATES_PER_SECOND) {
+                Ok(recorder) => {
+                    info!("Recording replay to {}", path);
+                    server.set_recorder(recorder);
+                },
+                Err(err) => error!("Failed to create replay {}: {}", path, err)
+            }
+        }
         let mut entity_server = hexahydrate::Server::<Entity, ConnectionID>::new(
             (shared::UPDATES_PER_SECOND * 2) as usize
         );
 
+        server.init(&mut entity_server, &level);
+
         loop {
             server.update(&mut timer, &mut entity_server, &mut network, &level);
             timer.run(&mut server, &mut entity_server, &mut network, &level);
@@ -88,3 +129,46 @@ pub fn run(addr: String) -> thread::JoinHandle<()> {
 
 }
 
+
+// HTTP Interface -------------------------------------------------------------
+fn handle_http(
+    req: hyper::server::Request,
+    mut res: hyper::server::Response,
+    name: &str,
+    metrics: &Arc<Mutex<ServerMetrics>>
+) {
+
+    let path = match req.uri {
+        hyper::uri::RequestUri::AbsolutePath(ref path) => path.split('?').next().unwrap_or("/").to_string(),
+        _ => "/".to_string()
+    };
+
+    match path.as_str() {
+        STATUS_PATH => {
+            let (players, bots) = metrics.lock().map(|m| (m.players, m.bots)).unwrap_or((0, 0));
+            let status = ServerStatus {
+                name: name.to_string(),
+                map: DEFAULT_LEVEL_NAME.to_string(),
+                mode: GAME_MODE.to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                players: players,
+                bots: bots,
+                max_players: ColorName::all_colored().len()
+            };
+            res.headers_mut().set_raw("Content-Type", vec![b"application/json".to_vec()]);
+            res.send(status.to_json().as_bytes()).ok();
+        },
+        "/metrics" => {
+            let text = metrics.lock().map(|m| m.to_prometheus()).unwrap_or_else(|_| String::new());
+            res.headers_mut().set_raw("Content-Type", vec![b"text/plain; version=0.0.4".to_vec()]);
+            res.send(text.as_bytes()).ok();
+        },
+
+        // Clients download the map from any other path
+        _ => {
+            res.send(DEFAULT_LEVEL_DATA.as_bytes()).ok();
+        }
+    }
+
+}
+
@@ -1,5 +1,6 @@
 // Crates ---------------------------------------------------------------------
 extern crate server;
+extern crate shared;
 extern crate clap;
 
 
@@ -18,10 +19,91 @@ fn main() {
             .takes_value(true)
             .default_value("0.0.0.0:7156")
         )
+        .arg(clap::Arg::with_name("name")
+            .short("n")
+            .long("name")
+            .value_name("NAME")
+            .help("Specifies the server name reported to server browsers.")
+            .takes_value(true)
+            .default_value(server::DEFAULT_SERVER_NAME)
+        )
+        .arg(clap::Arg::with_name("bots")
+            .short("b")
+            .long("bots")
+            .value_name("PLAYERS")
+            .help("Fills up empty slots with bots until the given number of players is reached.")
+            .takes_value(true)
+            .default_value("0")
+        )
+        .arg(clap::Arg::with_name("bot-reaction")
+            .long("bot-reaction")
+            .value_name("MS")
+            .help("Specifies the time in milliseconds before bots fire at a newly spotted player.")
+            .takes_value(true)
+            .default_value("350")
+        )
+        .arg(clap::Arg::with_name("bot-accuracy")
+            .long("bot-accuracy")
+            .value_name("ACCURACY")
+            .help("Specifies the aiming accuracy of bots from 0.0 to 1.0.")
+            .takes_value(true)
+            .default_value("0.75")
+        )
+        .arg(clap::Arg::with_name("record")
+            .short("r")
+            .long("record")
+            .value_name("FILE")
+            .help("Records the match to the specified replay file.")
+            .takes_value(true)
+        )
+        .arg(clap::Arg::with_name("log")
+            .long("log")
+            .value_name("FILTER")
+            .help("Specifies the log levels, e.g. \"info,server::bot=debug\". Defaults to the SHOOTER_LOG environment variable.")
+            .takes_value(true)
+        )
+        .arg(clap::Arg::with_name("log-file")
+            .long("log-file")
+            .value_name("FILE")
+            .help("Additionally writes all log records as JSON lines to the specified file.")
+            .takes_value(true)
+        )
+        .arg(clap::Arg::with_name("missing-input")
+            .long("missing-input")
+            .value_name("POLICY")
+            .help("Specifies how players are moved on ticks for which their input did not arrive in time.")
+            .takes_value(true)
+            .possible_values(&["repeat", "zero", "extrapolate"])
+            .default_value("repeat")
+        )
+        .args(&shared::cli::conditioner_args())
         .get_matches();
 
+    if let Err(err) = shared::logging::init(matches.value_of("log"), matches.value_of("log-file")) {
+        println!("Failed to initialize logging: {}", err);
+        return;
+    }
+
+    let bot_config = server::BotConfig {
+        reaction_time: matches.value_of("bot-reaction").unwrap().parse().expect("Invalid bot reaction time."),
+        accuracy: matches.value_of("bot-accuracy").unwrap().parse().expect("Invalid bot accuracy.")
+    };
+
+    let conditions = shared::cli::conditions_from_matches(&matches);
+    let missing_input_policy = match matches.value_of("missing-input").unwrap() {
+        "zero" => server::MissingInputPolicy::Zero,
+        "extrapolate" => server::MissingInputPolicy::Extrapolate,
+        _ => server::MissingInputPolicy::RepeatLast
+    };
+
     server::run(
-        matches.value_of("addr").unwrap().to_string()
+        matches.value_of("addr").unwrap().to_string(),
+        matches.value_of("name").unwrap().to_string(),
+        matches.value_of("bots").unwrap().parse().expect("Invalid number of bots."),
+        bot_config,
+        matches.value_of("record").map(|path| path.to_string()),
+        conditions,
+        missing_input_policy
 
     ).join().ok();
 
@@ -1,4 +1,8 @@
 // STD Dependencies -----------------------------------------------------------
+use std::cmp;
+use std::fs::File;
+use std::io::BufWriter;
+use std::sync::{Arc, Mutex};
 use std::collections::{HashMap, VecDeque};
 
 
@@ -9,27 +13,43 @@ use clock_ticks;
 use hexahydrate;
 use cobalt;
 use cobalt::ConnectionID;
-use netsync::ServerState;
+use netsync::{NetworkConditioner, NetworkConditions, MissingInputPolicy};
 
 
 // Internal Dependencies ------------------------------------------------------
 use ::Timer;
 use ::laser_beam;
-use ::entity::Entity;
+use ::bot::{Bot, BotConfig};
+use ::entity::{Entity, ServerPlayerEntity, ServerProjectileEntity};
 use shared::color::ColorName;
 use shared::level::{
-    Level, LevelVisibility, LevelSpawn,
+    Level, LevelCollision, LevelVisibility, LevelSpawn,
     LEVEL_MAX_BEAM_VISIBILITY_DISTANCE
 };
-use shared::collision::aabb_intersect_circle;
-use shared::entity::{PLAYER_MAX_HP, PLAYER_RESPAWN_INTERVAL, ENTITY_STATE_DELAY};
+use shared::util;
+use shared::weapon::Weapon;
+use shared::collision::{aabb_intersect_circle, line_segment_distance_to_point};
+use shared::entity::{
+    PLAYER_MAX_HP, PLAYER_RADIUS, PLAYER_RESPAWN_INTERVAL, rewind_ticks,
+    PLAYER_BUFF_DAMAGE, PLAYER_BUFF_DAMAGE_FACTOR, PROJECTILE_RADIUS
+};
 use shared::action::{Action, ActionVisibility};
-use shared::entity::{PlayerInput, PlayerData, PlayerEntity};
+use shared::entity::{PlayerData, PickupEntity, ProjectileData};
+use shared::replay::ReplayRecorder;
+use shared::logging;
+use shared::stats::NetworkStats;
+use shared::metrics::ServerMetrics;
+
+
+// Statics --------------------------------------------------------------------
+const NETWORK_STATS_PRINT_INTERVAL: u64 = 10000;
 
 
 // Server Implementation ------------------------------------------------------
 pub struct Server {
     dt: f32,
+    tick: u16,
+    tick_time: u64,
     addr: String,
     connections: HashMap<ConnectionID, (
         hexahydrate::ConnectionSlot<ConnectionID>,
@@ -37,7 +57,19 @@ pub struct Server {
         ColorName,
         VecDeque<Action>
     )>,
-    available_colors: Vec<ColorName>
+    pickups: Vec<hexahydrate::ServerEntitySlot>,
+    projectiles: Vec<(hexahydrate::ServerEntitySlot, ConnectionID, u8)>,
+    bots: Vec<Bot>,
+    bot_config: BotConfig,
+    bot_fill: usize,
+    missing_input_policy: MissingInputPolicy,
+    recorder: Option<ReplayRecorder<BufWriter<File>>>,
+    available_colors: Vec<ColorName>,
+    conditioner: NetworkConditioner<(ConnectionID, bool, Vec<u8>)>,
+    network_stats: HashMap<ConnectionID, NetworkStats>,
+    network_stats_printed: u64,
+    metrics: ServerMetrics,
+    metrics_output: Option<Arc<Mutex<ServerMetrics>>>
 }
 
 impl Server {
@@ -47,10 +79,88 @@ impl Server {
         rand::thread_rng().shuffle(&mut colors);
         Server {
             dt: 1.0 / updates_per_second as f32,
+            tick: 0,
+            tick_time: 0,
             addr: addr,
             connections: HashMap::new(),
-            available_colors: colors
+            pickups: Vec::new(),
+            projectiles: Vec::new(),
+            bots: Vec::new(),
+            bot_config: BotConfig::default(),
+            bot_fill: 0,
+            missing_input_policy: MissingInputPolicy::default(),
+            recorder: None,
+            available_colors: colors,
+            conditioner: NetworkConditioner::new(NetworkConditions::default(), rand::random()),
+            network_stats: HashMap::new(),
+            network_stats_printed: 0,
+            metrics: ServerMetrics::new(clock_ticks::precise_time_ms()),
+            metrics_output: None
+        }
+    }
+
+    /// Traffic, round trip time and packet loss of all connections, including
+    /// the ones which have not yet joined the game.
+    pub fn network_stats(&self) -> &HashMap<ConnectionID, NetworkStats> {
+        &self.network_stats
+    }
+
+    /// Simulates bad network conditions for all outgoing packets.
+    pub fn set_network_conditions(&mut self, conditions: NetworkConditions) {
+        if !conditions.is_ideal() {
+            warn!("Simulating network conditions: {:?}", conditions);
+        }
+        self.conditioner.set_conditions(conditions);
+    }
+
+    /// Publishes the metrics into `output` at the end of every tick.
+    pub fn set_metrics_output(&mut self, output: Arc<Mutex<ServerMetrics>>) {
+        self.metrics_output = Some(output);
+    }
+
+    /// Records every following tick of the match into a replay file.
+    pub fn set_recorder(&mut self, recorder: ReplayRecorder<BufWriter<File>>) {
+        self.recorder = Some(recorder);
+    }
+
+    /// Fills up empty slots with bots until humans and bots add up to
+    /// `players`, joining humans replace bots. The fill can be changed while
+    /// the server is running and is applied via `fill_bots`.
+    pub fn set_bot_fill(&mut self, players: usize, config: BotConfig) {
+        self.bot_fill = players;
+        self.bot_config = config;
+    }
+
+    /// Specifies how client players are advanced on ticks for which none of
+    /// their inputs arrived in time.
+    pub fn set_missing_input_policy(&mut self, policy: MissingInputPolicy) {
+        self.missing_input_policy = policy;
+    }
+
+    pub fn init(
+        &mut self,
+        entity_server: &mut hexahydrate::Server<Entity, ConnectionID>,
+        level: &Level
+    ) {
+
+        // Create pickup entities for all spawners defined by the level
+        for spawner in &level.pickups {
+            if let Ok(slot) = entity_server.entity_create_with(|| {
+                Box::new(PickupEntity::new(
+                    spawner.kind,
+                    spawner.x,
+                    spawner.y,
+                    spawner.respawn_interval
+                ))
+
+            }) {
+                self.pickups.push(slot);
+
+            } else {
+                error!("No more entity slots for pickups.");
+            }
         }
+
     }
 
     pub fn update(
@@ -61,12 +171,43 @@ impl Server {
         level: &Level
     ) {
 
+        let tick_start = clock_ticks::precise_time_ns();
+        self.tick = self.tick.wrapping_add(1);
+        self.tick_time = clock_ticks::precise_time_ms();
+        logging::set_tick(self.tick);
+
         self.receive(entity_server, server, level);
+        self.fill_bots(entity_server, level);
+        self.update_bots(entity_server, level);
         self.update_entities_before(entity_server, level);
 
-        let actions = self.apply_actions(timer, entity_server, server, level);
+        let mut actions = Vec::new();
+        let mut hits = Vec::new();
+        self.apply_actions(entity_server, server, level, &mut hits, &mut actions);
+        self.update_projectiles(entity_server, level, &mut hits, &mut actions);
+        self.apply_hits(timer, entity_server, hits, &mut actions);
+        actions.append(&mut self.update_pickups(entity_server));
         self.update_entities_after(entity_server, level);
-        self.send(entity_server, server, level, &actions);
+        self.record(entity_server, &actions);
+        self.send(entity_server, level, &actions);
+
+        // Packets are held back by the conditioner while simulating bad
+        // network conditions and passed through right away otherwise
+        let t = clock_ticks::precise_time_ms();
+        while let Some((id, reliable, packet)) = self.conditioner.receive(t) {
+            self.network_stats.entry(id).or_insert_with(|| NetworkStats::new(t)).sent.record_server_packet(&packet);
+            self.metrics.record_sent(packet.len());
+            server.send(&id, if reliable {
+                cobalt::MessageKind::Reliable
+
+            } else {
+                cobalt::MessageKind::Instant
+
+            }, packet).ok();
+        }
+
+        self.update_network_stats(server);
+        self.update_metrics(entity_server, tick_start);
 
         // This sleeps to achieve the desired server tick rate
         server.flush().ok();
@@ -84,39 +225,68 @@ impl Server {
 
             match event {
                 cobalt::ServerEvent::Bind => {
-                    println!("[Server] Now accepting connections on {}", self.addr);
+                    info!("Now accepting connections on {}", self.addr);
                 },
-                cobalt::ServerEvent::Connection(_) => {
-                    println!("[Server] Client {} connected", self.addr);
+                cobalt::ServerEvent::Connection(id) => {
+                    let _log = logging::connection(id);
+                    info!("Client connected");
                 },
                 cobalt::ServerEvent::Message(id, packet) => {
 
+                    let _log = logging::connection(id);
+
+                    let tick_time = self.tick_time;
+                    self.network_stats.entry(id).or_insert_with(|| {
+                        NetworkStats::new(tick_time)
+
+                    }).received.record_client_packet(&packet);
+                    self.metrics.record_received(packet.len());
+
                     if self.connections.contains_key(&id) {
                         if let Some(&mut (ref slot, _, _, ref mut incoming_actions)) = self.connections.get_mut(&id) {
                             match entity_server.connection_receive(slot, packet) {
                                 Err(hexahydrate::ServerError::InvalidPacketData(bytes)) => {
-                                    if let Ok(action) = Action::from_bytes(&bytes) {
-                                        // TODO limit number of maximum actions per second?
-                                        incoming_actions.push_back(action);
+                                    match Action::from_bytes(&bytes) {
+                                        // Clock requests are answered right away to keep
+                                        // the measured round trip time accurate
+                                        Ok(Action::ClockRequest(client_time)) => {
+                                            send_clock_response(&mut self.conditioner, id, client_time, self.tick, self.tick_time);
+                                        },
+                                        Ok(action) => {
+                                            // TODO limit number of maximum actions per second?
+                                            incoming_actions.push_back(action);
+                                        },
+                                        Err(_) => {}
                                     }
                                 },
                                 _ => {}
                             }
                         }
 
-                    } else if let Ok(Action::JoinGame) = Action::from_bytes(&packet) {
-                        if let Some(conn) = server.connection_mut(&id) {
-                            self.connect(entity_server, level, conn);
+                    } else {
+                        match Action::from_bytes(&packet) {
+                            Ok(Action::JoinGame) => {
+                                if let Some(conn) = server.connection_mut(&id) {
+                                    self.connect(entity_server, level, conn);
+                                }
+                            },
+                            // Clients synchronize their clock before joining
+                            Ok(Action::ClockRequest(client_time)) => {
+                                send_clock_response(&mut self.conditioner, id, client_time, self.tick, self.tick_time);
+                            },
+                            _ => {}
                         }
                     }
 
                 },
                 cobalt::ServerEvent::ConnectionLost(id) => {
-                    println!("[Server] Lost connection to client!");
+                    let _log = logging::connection(id);
+                    warn!("Lost connection to client!");
                     self.disconnect(entity_server, &id);
                 },
                 cobalt::ServerEvent::ConnectionClosed(id, _) => {
-                    println!("[Server] Closed connection to client.");
+                    let _log = logging::connection(id);
+                    info!("Closed connection to client.");
                     self.disconnect(entity_server, &id);
                 },
                 _ => {}
@@ -126,6 +296,54 @@ impl Server {
 
     }
 
+    fn update_network_stats(&mut self, server: &mut cobalt::ServerStream) {
+
+        let t = clock_ticks::precise_time_ms();
+        for (id, stats) in &mut self.network_stats {
+            if stats.update(t) {
+                if let Some(conn) = server.connection_mut(id) {
+                    stats.record_rtt(conn.rtt());
+                    stats.set_packet_loss(conn.packet_loss());
+                }
+            }
+        }
+
+        if t >= self.network_stats_printed + NETWORK_STATS_PRINT_INTERVAL {
+            for (id, stats) in &self.network_stats {
+                let _log = logging::connection(*id);
+                info!("{}", stats);
+            }
+            self.network_stats_printed = t;
+        }
+
+    }
+
+    fn update_metrics(
+        &mut self,
+        entity_server: &mut hexahydrate::Server<Entity, ConnectionID>,
+        tick_start: u64
+    ) {
+
+        let mut entities = 0;
+        entity_server.with_entities(|_, _| entities += 1);
+
+        self.metrics.players = self.connections.len();
+        self.metrics.bots = self.bots.len();
+        self.metrics.entities = entities;
+        self.metrics.update(self.tick_time);
+
+        // Excludes the time spent sleeping until the next tick
+        let duration = clock_ticks::precise_time_ns().saturating_sub(tick_start);
+        self.metrics.tick_duration.observe(duration as f64 / 1_000_000_000.0);
+
+        if let Some(ref output) = self.metrics_output {
+            if let Ok(mut output) = output.lock() {
+                *output = self.metrics.clone();
+            }
+        }
+
+    }
+
     fn update_entities_before(
         &mut self,
         entity_server: &mut hexahydrate::Server<Entity, ConnectionID>,
@@ -138,78 +356,150 @@ impl Server {
 
     fn apply_actions(
         &mut self,
-        timer: &mut Timer,
         entity_server: &mut hexahydrate::Server<Entity, ConnectionID>,
         server: &mut cobalt::ServerStream,
-        level: &Level
-
-    ) -> Vec<(ActionVisibility, Action)> {
+        level: &Level,
+        hits: &mut Vec<(ConnectionID, ColorName, ConnectionID, u8)>,
+        outgoing_actions: &mut Vec<(ActionVisibility, Action)>
+    ) {
 
         let t = clock_ticks::precise_time_ms();
-        let mut outgoing_actions: Vec<(ActionVisibility, Action)> = Vec::new();
-        let mut beam_hits: Vec<(ConnectionID, ColorName, ConnectionID)> = Vec::new();
+        let server_tick = self.tick;
+
+        // Collect actions from both clients and bots
+        let mut incoming_actions = Vec::new();
+        for (conn_id, &mut (_, _, _, ref mut actions)) in &mut self.connections {
+            incoming_actions.extend(actions.drain(..).map(|action| (*conn_id, action)));
+        }
+
+        for bot in &mut self.bots {
+            let id = bot.id;
+            incoming_actions.extend(bot.actions.drain(..).map(|action| (id, action)));
+        }
+
+        self.metrics.record_actions(incoming_actions.len());
 
-        for (conn_id, &mut (_, ref entity_slot, _, ref mut incoming_actions)) in &mut self.connections {
+        for (conn_id, action) in incoming_actions {
 
-            while let Some(action) = incoming_actions.pop_front() {
+            let _log = logging::connection(conn_id);
 
-                match action {
+            let entity_slot = if let Some(slot) = player_slot(&self.connections, &self.bots, &conn_id) {
+                slot
 
-                    // TODO should we perform a persistent check for the duration
-                    // of the laser beam?
-                    Action::FiredLaserBeam(tick, client_r) => {
+            } else {
+                continue;
+            };
+
+            match action {
+
+                // TODO should we perform a persistent check for the duration
+                // of the laser beam?
+                Action::FiredWeapon(weapon, tick, view_tick, client_r) => {
+
+                    // Ignore unknown weapons
+                    let weapon = if let Some(weapon) = Weapon::from_u8(weapon) {
+                        weapon
+
+                    } else {
+                        continue;
+                    };
 
-                        // Correct firing angle to be somewhere between server
-                        // and client side value
-                        let entity = if let Some(entity) = entity_server.entity_get_mut(entity_slot) {
+                    // Bots see the current server state instead of the
+                    // delayed one rendered by clients
+                    let is_bot = self.bots.iter().any(|b| b.id == conn_id);
 
-                            // Difference for firing angle interpolation
-                            let tick_diff = entity.tick_diff(tick, 0);
+                    // Correct firing angle to be somewhere between server
+                    // and client side value
+                    let entity = if let Some(entity) = entity_server.entity_get_mut(entity_slot).and_then(|e| e.as_player_mut()) {
 
-                            // Tick difference for state rollback
-                            let state_diff = entity.tick_diff(tick, ENTITY_STATE_DELAY);
+                        // Difference for firing angle interpolation
+                        let tick_diff = entity.tick_diff(tick, 0);
 
-                            let mut data = entity.relative_data(tick_diff);
-                            data.merge_client_angle(client_r);
+                        // Tick difference for state rollback, based on the
+                        // server tick of the states the client was displaying
+                        // when firing
+                        let state_diff = rewind_ticks(server_tick, view_tick, !is_bot);
 
-                            println!(
-                                "[Server] {} Beam RTT {} Input Delay: {} State Delay: {}",
-                                server.connection_mut(&conn_id).unwrap().peer_addr(),
-                                server.connection_mut(&conn_id).unwrap().rtt(),
+                        let mut data = entity.relative_data(tick_diff);
+                        data.merge_client_angle(client_r);
+
+                        if let Some(conn) = server.connection_mut(&conn_id) {
+                            debug!(
+                                "{} {:?} RTT {} Input Delay: {} State Delay: {}",
+                                conn.peer_addr(),
+                                weapon,
+                                conn.rtt(),
                                 tick_diff,
                                 state_diff
                             );
+                        }
 
-                            // Ignore action from dead client entities
-                            if data.hp > 0 && entity.fire_beam(t) {
-                                Some((data, entity.color_name(), state_diff))
-
-                            } else {
-                                None
-                            }
+                        // Ignore action from dead client entities
+                        if data.hp > 0 && entity.fire_weapon(weapon, t) {
+                            Some((data, entity.color_name(), entity.buffs(), state_diff))
 
                         } else {
                             None
+                        }
+
+                    } else {
+                        None
+                    };
+
+                    if let Some((data, color_name, buffs, ticks_ago)) = entity {
+
+                        let stats = weapon.stats();
+                        let damage = if buffs & PLAYER_BUFF_DAMAGE == PLAYER_BUFF_DAMAGE {
+                            (stats.damage as f32 * PLAYER_BUFF_DAMAGE_FACTOR) as u8
+
+                        } else {
+                            stats.damage
                         };
 
-                        if let Some((data, color_name, ticks_ago)) = entity {
+                        // Projectiles are simulated by the server and
+                        // synchronized via their own entities
+                        if stats.projectile_speed.is_some() {
 
-                            // Create initial laser beam
-                            let (beam_line, mut l, r, _) = laser_beam::create(&level, &data);
+                            if let Ok(slot) = entity_server.entity_create_with(|| {
+                                Box::new(ServerProjectileEntity::new(
+                                    Some(conn_id),
+                                    false,
+                                    tick,
+                                    ProjectileData::new(weapon, color_name, data.x, data.y, data.r)
+                                ))
+
+                            }) {
+                                self.projectiles.push((slot, conn_id, damage));
+
+                            } else {
+                                error!("No more entity slots for projectiles.");
+                            }
+
+                            continue;
+
+                        }
 
-                            // Get entity data for both the current server state and as it was seen on the client when they fired
-                            let client_side_entities = entity_server.map_entities::<(Option<ConnectionID>, PlayerData, PlayerData), _>(|_, entity| {
-                                (entity.owner(), entity.current_data(), entity.relative_data(ticks_ago))
-                            });
+                        // Get entity data for both the current server state and as it was seen on the client when they fired
+                        let client_side_entities: Vec<(Option<ConnectionID>, PlayerData, PlayerData)> = entity_server.map_entities::<Option<_>, _>(|_, entity| {
+                            entity.as_player().map(|player| {
+                                (player.owner(), player.current_data(), player.relative_data(ticks_ago))
+                            })
+
+                        }).into_iter().filter_map(|p| p).collect();
+
+                        for pellet_r in weapon.pellet_angles(data.r) {
+
+                            // Create initial laser beam
+                            let (beam_line, mut l, r, _) = laser_beam::create(&level, &data, pellet_r, stats.range);
 
                             // TODO handle mirror walls and bounced off beams which hit the player
                             if let Some((hit_conn_id, hit_l)) = laser_beam::get_player_hits(
-                                conn_id,
+                                &conn_id,
                                 &beam_line,
                                 l,
                                 &client_side_entities
                             ) {
-                                beam_hits.push((*conn_id, color_name, hit_conn_id));
+                                hits.push((conn_id, color_name, hit_conn_id, damage));
                                 l = hit_l;
                             }
 
@@ -235,20 +525,143 @@ impl Server {
 
                         }
 
-                    },
-                    _ => {}
+                    }
+
+                },
+                _ => {}
+            }
+
+        }
+
+    }
+
+    fn update_projectiles(
+        &mut self,
+        entity_server: &mut hexahydrate::Server<Entity, ConnectionID>,
+        level: &Level,
+        hits: &mut Vec<(ConnectionID, ColorName, ConnectionID, u8)>,
+        outgoing_actions: &mut Vec<(ActionVisibility, Action)>
+    ) {
+
+        let players: Vec<(Option<ConnectionID>, PlayerData)> = entity_server.map_entities::<Option<_>, _>(|_, entity| {
+            entity.as_player().map(|player| (player.owner(), player.current_data()))
+
+        }).into_iter().filter_map(|p| p).collect();
+
+        // Move all projectiles and collect the ones which impacted
+        let mut impacts = Vec::new();
+        for (index, &(ref slot, owner, damage)) in self.projectiles.iter().enumerate() {
+            if let Some(projectile) = entity_server.entity_get_mut(slot).and_then(|e| e.as_projectile_mut()) {
+
+                // Advance the projectile, clients run the same simulation
+                // for their own projectiles in order to predict them
+                let last = projectile.state.get_relative(0);
+                let dt = self.dt;
+                projectile.state.apply(|data| {
+                    ProjectileData::update(dt, data, level);
+                });
+
+                let data = projectile.state.get_relative(0);
+                let splash_radius = data.weapon.stats().splash_radius;
+
+                // Find the nearest player which was hit directly, shooters
+                // cannot hit themselves
+                let line = [last.x, last.y, data.x, data.y];
+                let direct = players.iter().filter(|&&(conn_id, ref p)| {
+                    conn_id.is_some() && conn_id != Some(owner) && p.hp > 0
+                        && line_segment_distance_to_point(&line, p.x, p.y) < PLAYER_RADIUS + PROJECTILE_RADIUS
+
+                }).fold(None, |nearest: Option<(f32, ConnectionID, f32, f32)>, &(conn_id, ref p)| {
+                    let d = util::distance(last.x, last.y, p.x, p.y);
+                    match nearest {
+                        Some(n) if n.0 <= d => Some(n),
+                        _ => Some((d, conn_id.unwrap(), p.x, p.y))
+                    }
+                });
+
+                if let Some((_, hit_conn_id, hx, hy)) = direct {
+                    impacts.push((
+                        index, owner, data.color, damage, splash_radius,
+                        hx - data.r.cos() * PLAYER_RADIUS, hy - data.r.sin() * PLAYER_RADIUS,
+                        Some(hit_conn_id)
+                    ));
+
+                } else if data.impacted {
+                    impacts.push((
+                        index, owner, data.color, damage, splash_radius,
+                        data.x, data.y,
+                        None
+                    ));
                 }
+
             }
+        }
+
+        // Apply direct and splash damage, remove in reverse order to keep the
+        // remaining indices valid
+        for (index, owner, color_name, damage, splash_radius, x, y, direct) in impacts.into_iter().rev() {
+
+            if let Some(hit_conn_id) = direct {
+                hits.push((owner, color_name, hit_conn_id, damage));
+            }
+
+            // Splash damage requires line of sight to the impact point
+            for &(conn_id, ref data) in &players {
+                if let Some(conn_id) = conn_id {
+                    if conn_id != owner && Some(conn_id) != direct && data.hp > 0
+                        && util::distance(x, y, data.x, data.y) < splash_radius + PLAYER_RADIUS
+                        && level.collide_line(&[x, y, data.x, data.y]).is_none() {
+                        hits.push((owner, color_name, conn_id, damage / 2));
+                    }
+                }
+            }
+
+            outgoing_actions.push((
+                ActionVisibility::WithinRange {
+                    aabb: [
+                        x - splash_radius,
+                        y - splash_radius,
+                        x + splash_radius,
+                        y + splash_radius
+                    ],
+                    r: LEVEL_MAX_BEAM_VISIBILITY_DISTANCE
+                },
+                Action::ProjectileImpact(color_name.to_u8(), x, y, splash_radius)
+            ));
+
+            let (slot, _, _) = self.projectiles.remove(index);
+            entity_server.entity_destroy(slot).ok();
 
         }
 
-        // Handle laser beam hits
-        for (shooter_conn_id, shooter_color, hit_conn_id) in beam_hits {
+    }
+
+    fn apply_hits(
+        &mut self,
+        timer: &mut Timer,
+        entity_server: &mut hexahydrate::Server<Entity, ConnectionID>,
+        hits: Vec<(ConnectionID, ColorName, ConnectionID, u8)>,
+        outgoing_actions: &mut Vec<(ActionVisibility, Action)>
+    ) {
+
+        for (shooter_conn_id, shooter_color, hit_conn_id, damage) in hits {
+
+            let entity_slot = if let Some(slot) = player_slot(&self.connections, &self.bots, &hit_conn_id) {
+                slot
+
+            } else {
+                continue;
+            };
+
+            if let Some(entity) = entity_server.entity_get_mut(entity_slot).and_then(|e| e.as_player_mut()) {
 
-            if let Some(entity) = entity_server.entity_get_mut(&self.connections.get(&hit_conn_id).unwrap().1) {
+                // Multiple hits within the same tick should only kill once
+                if !entity.is_alive() {
+                    continue;
+                }
 
                 // Apply laser damage to entity, this saturates at 0 hp
-                entity.damage(64);
+                entity.damage(damage);
 
                 // TODO we need a simple timer system
                 // VecDeque, sort to be next first when inserting (insert
@@ -261,10 +674,11 @@ impl Server {
 
                 if entity.is_alive() {
 
-                    println!("[Server] Beam Hit: {:?} -> {:?}", shooter_conn_id, hit_conn_id);
+                    debug!("Player Hit: {:?} -> {:?}", shooter_conn_id, hit_conn_id);
+                    self.metrics.hits += 1;
 
                     let data = entity.current_data();
-                    let action = Action::LaserBeamHit(entity.color_name().to_u8(), shooter_color.to_u8(), data.x, data.y);
+                    let action = Action::PlayerHit(entity.color_name().to_u8(), shooter_color.to_u8(), data.x, data.y);
 
                     // Send action to all other players, except for the shooter
                     outgoing_actions.push((ActionVisibility::Entity(data, Some(shooter_conn_id)), action.clone()));
@@ -275,10 +689,12 @@ impl Server {
 
                 } else {
 
-                    println!("[Server] Beam Kill: {:?} -> {:?}", shooter_conn_id, hit_conn_id);
+                    info!("Player Kill: {:?} -> {:?}", shooter_conn_id, hit_conn_id);
+                    self.metrics.hits += 1;
+                    self.metrics.kills += 1;
 
                     let data = entity.current_data();
-                    let action = Action::LaserBeamKill(entity.color_name().to_u8(), shooter_color.to_u8(), data.x, data.y);
+                    let action = Action::PlayerKill(entity.color_name().to_u8(), shooter_color.to_u8(), data.x, data.y);
 
                     // Send action to all other players, except for the shooter
                     outgoing_actions.push((ActionVisibility::Entity(data.clone(), Some(shooter_conn_id)), action.clone()));
@@ -296,9 +712,10 @@ impl Server {
 
                         let spawn = server.find_player_spawn(entity_server, level);
 
-                        if let Some(conn) = server.connections.get(&hit_conn_id) {
-                            if let Some(entity) = entity_server.entity_get_mut(&conn.1) {
-                                println!("[Server] Respawning player...");
+                        if let Some(entity_slot) = player_slot(&server.connections, &server.bots, &hit_conn_id) {
+                            if let Some(entity) = entity_server.entity_get_mut(entity_slot).and_then(|e| e.as_player_mut()) {
+                                let _log = logging::connection(hit_conn_id);
+                                debug!("Respawning player...");
                                 entity.respawn(spawn);
                             }
                         }
@@ -311,6 +728,104 @@ impl Server {
 
         }
 
+    }
+
+    fn update_pickups(
+        &mut self,
+        entity_server: &mut hexahydrate::Server<Entity, ConnectionID>
+
+    ) -> Vec<(ActionVisibility, Action)> {
+
+        let t = clock_ticks::precise_time_ms();
+        let mut outgoing_actions: Vec<(ActionVisibility, Action)> = Vec::new();
+
+        // Expire player buffs
+        let entity_slots = self.connections.values().map(|c| &c.1).chain(self.bots.iter().map(|b| &b.entity_slot));
+        for entity_slot in entity_slots {
+            if let Some(player) = entity_server.entity_get_mut(entity_slot).and_then(|e| e.as_player_mut()) {
+                player.update_buffs(t);
+            }
+        }
+
+        let players: Vec<(Option<ConnectionID>, PlayerData)> = entity_server.map_entities::<Option<_>, _>(|_, entity| {
+            entity.as_player().map(|player| (player.owner(), player.current_data()))
+
+        }).into_iter().filter_map(|p| p).collect();
+
+        for pickup_slot in &self.pickups {
+
+            // Respawn collected pickups and find the first player touching
+            // an active one
+            let collision = if let Some(pickup) = entity_server.entity_get_mut(pickup_slot).and_then(|e| e.as_pickup_mut()) {
+
+                pickup.update(t);
+
+                if pickup.data.active {
+                    let aabb = pickup.data.aabb();
+                    players.iter().filter(|&&(_, ref data)| {
+                        data.hp > 0 && aabb_intersect_circle(&aabb, data.x, data.y, PLAYER_RADIUS)
+
+                    }).filter_map(|&(conn_id, _)| conn_id).next().map(|conn_id| {
+                        (conn_id, pickup.data.clone())
+                    })
+
+                } else {
+                    None
+                }
+
+            } else {
+                None
+            };
+
+            if let Some((conn_id, pickup_data)) = collision {
+
+                // Apply the pickup to the player, pickups which have no effect
+                // (e.g. health at full hp) are not consumed
+                let collected = if let Some(entity_slot) = player_slot(&self.connections, &self.bots, &conn_id) {
+                    if let Some(player) = entity_server.entity_get_mut(entity_slot).and_then(|e| e.as_player_mut()) {
+                        if player.apply_pickup(pickup_data.kind, t) {
+                            Some((player.color_name(), player.current_data()))
+
+                        } else {
+                            None
+                        }
+
+                    } else {
+                        None
+                    }
+
+                } else {
+                    None
+                };
+
+                if let Some((color_name, data)) = collected {
+
+                    let _log = logging::connection(conn_id);
+                    debug!("Pickup collected: {:?}", pickup_data.kind);
+
+                    if let Some(pickup) = entity_server.entity_get_mut(pickup_slot).and_then(|e| e.as_pickup_mut()) {
+                        pickup.collect(t);
+                    }
+
+                    let action = Action::PickupCollected(
+                        color_name.to_u8(),
+                        pickup_data.kind.to_u8(),
+                        pickup_data.x,
+                        pickup_data.y
+                    );
+
+                    // Send action to all other players which can see the collector
+                    outgoing_actions.push((ActionVisibility::Entity(data, Some(conn_id)), action.clone()));
+
+                    // Always send the action to the collector
+                    outgoing_actions.push((ActionVisibility::Connection(conn_id), action));
+
+                }
+
+            }
+
+        }
+
         outgoing_actions
 
     }
@@ -321,15 +836,21 @@ impl Server {
         level: &Level
     ) {
 
-        let entity_data = entity_server.map_entities::<(Option<ConnectionID>, PlayerData), _>(|_, entity| {
-            (entity.owner(), entity.current_data())
-        });
+        let entity_data: Vec<(Option<ConnectionID>, PlayerData)> = entity_server.map_entities::<Option<_>, _>(|_, entity| {
+            entity.as_player().map(|player| (player.owner(), player.current_data()))
+
+        }).into_iter().filter_map(|p| p).collect();
+
+        // Update visibility and send entitiy data to clients, bot entities
+        // are synchronized to clients just like any other player
+        let player_slots = self.connections.iter().map(|(conn_id, c)| (conn_id, &c.1)).chain(
+            self.bots.iter().map(|b| (&b.id, &b.entity_slot))
+        );
 
-        // Update visibility and send entitiy data to clients
-        for (conn_id, &mut (_, ref entity_slot, _, _)) in &mut self.connections {
+        for (conn_id, entity_slot) in player_slots {
 
             // Check to which other entities this player's entity is visible
-            if let Some(player_entity) = entity_server.entity_get_mut(entity_slot) {
+            if let Some(player_entity) = entity_server.entity_get_mut(entity_slot).and_then(|e| e.as_player_mut()) {
 
                 let player_data = player_entity.current_data();
 
@@ -357,23 +878,65 @@ impl Server {
 
     }
 
+    fn record(
+        &mut self,
+        entity_server: &mut hexahydrate::Server<Entity, ConnectionID>,
+        actions: &[(ActionVisibility, Action)]
+    ) {
+
+        let failed = if let Some(ref mut recorder) = self.recorder {
+
+            let players = entity_server.map_entities::<Option<_>, _>(|_, entity| {
+                entity.as_player().and_then(|player| {
+                    player.owner().map(|id| (id, player.color_name(), player.current_data()))
+                })
+
+            }).into_iter().filter_map(|p| p);
+
+            for (id, color, data) in players {
+                recorder.player(id, color, data);
+            }
+
+            for &(ref visibility, ref action) in actions {
+                recorder.action(visibility, action);
+            }
+
+            if let Err(err) = recorder.end_frame(clock_ticks::precise_time_ms()) {
+                error!("Failed to record replay: {}", err);
+                true
+
+            } else {
+                false
+            }
+
+        } else {
+            false
+        };
+
+        // Stop recording instead of failing on every following tick
+        if failed {
+            self.recorder = None;
+        }
+
+    }
+
     fn send(
         &mut self,
         entity_server: &mut hexahydrate::Server<Entity, ConnectionID>,
-        server: &mut cobalt::ServerStream,
         level: &Level,
         actions: &[(ActionVisibility, Action)]
     ) {
 
+        let t = clock_ticks::precise_time_ms();
         for (conn_id, &mut (ref slot, ref entity_slot, _, _)) in &mut self.connections {
 
             // Send out entity state updates
             for packet in entity_server.connection_send(slot, 512).unwrap() {
-                server.send(conn_id, cobalt::MessageKind::Instant, packet).ok();
+                self.conditioner.send(t, (*conn_id, false, packet), false);
             }
 
             // Send out actions
-            let entity = entity_server.entity_get(entity_slot);
+            let entity = entity_server.entity_get(entity_slot).and_then(|e| e.as_player());
             for &(ref visibility, ref action) in actions {
 
                 let send_to_connection = match *visibility {
@@ -405,12 +968,7 @@ impl Server {
                 };
 
                 if send_to_connection {
-                    server.send(
-                        conn_id,
-                        cobalt::MessageKind::Reliable,
-                        action.to_bytes()
-
-                    ).ok();
+                    self.conditioner.send(t, (*conn_id, true, action.to_bytes()), true);
                 }
 
             }
@@ -432,6 +990,132 @@ impl Server {
     }
 
 
+    // Bot Handling -----------------------------------------------------------
+    pub fn bots(&self) -> Vec<ConnectionID> {
+        self.bots.iter().map(|b| b.id).collect()
+    }
+
+    pub fn add_bot(
+        &mut self,
+        entity_server: &mut hexahydrate::Server<Entity, ConnectionID>,
+        level: &Level
+
+    ) -> Option<ConnectionID> {
+
+        let color = if let Some(color) = self.available_colors.pop() {
+            color
+
+        } else {
+            warn!("No more available colors for bots.");
+            return None;
+        };
+
+        let spawn = self.find_player_spawn(entity_server, level);
+
+        // Bots have no network connection, their IDs are counted down from
+        // the top of the range so they never collide with the ones handed
+        // out by the network layer
+        let id = (0..).map(|i| ConnectionID(u32::max_value() - i)).find(|id| {
+            !self.bots.iter().any(|b| b.id == *id)
+
+        }).unwrap();
+
+        if let Ok(entity_slot) = entity_server.entity_create_with(|| {
+            Box::new(ServerPlayerEntity::new(
+                Some(id),
+                false,
+                color,
+                PlayerData::new(spawn.x, spawn.y, 0.0, PLAYER_MAX_HP)
+            ))
+
+        }) {
+            info!("Bot {:?} added.", id);
+            if let Some(ref mut recorder) = self.recorder {
+                recorder.join(id, color);
+            }
+            self.bots.push(Bot::new(id, entity_slot, color, self.bot_config));
+            Some(id)
+
+        } else {
+            error!("No more entity slots for bots.");
+            self.available_colors.push(color);
+            None
+        }
+
+    }
+
+    pub fn remove_bot(
+        &mut self,
+        entity_server: &mut hexahydrate::Server<Entity, ConnectionID>,
+        id: &ConnectionID
+
+    ) -> bool {
+        if let Some(index) = self.bots.iter().position(|b| b.id == *id) {
+            let bot = self.bots.remove(index);
+            info!("Bot {:?} removed.", bot.id);
+            if let Some(ref mut recorder) = self.recorder {
+                recorder.leave(bot.id);
+            }
+            entity_server.entity_destroy(bot.entity_slot).ok();
+            self.available_colors.push(bot.color);
+            true
+
+        } else {
+            false
+        }
+    }
+
+    /// Adds or removes bots until the configured fill is reached, a fill of
+    /// zero leaves all bots to `add_bot` and `remove_bot`.
+    pub fn fill_bots(
+        &mut self,
+        entity_server: &mut hexahydrate::Server<Entity, ConnectionID>,
+        level: &Level
+    ) {
+
+        if self.bot_fill == 0 {
+            return;
+        }
+
+        let players = self.connections.len() + self.bots.len();
+        for _ in players..self.bot_fill {
+            if self.add_bot(entity_server, level).is_none() {
+                break;
+            }
+        }
+
+        // Remove the most recently added bots in case the fill was lowered
+        for _ in self.bot_fill..players {
+            let bot_id = self.bots.last().map(|b| b.id);
+            if let Some(bot_id) = bot_id {
+                self.remove_bot(entity_server, &bot_id);
+            }
+        }
+
+    }
+
+    fn update_bots(
+        &mut self,
+        entity_server: &mut hexahydrate::Server<Entity, ConnectionID>,
+        level: &Level
+    ) {
+
+        let t = clock_ticks::precise_time_ms();
+        let players: Vec<(Option<ConnectionID>, PlayerData)> = entity_server.map_entities::<Option<_>, _>(|_, entity| {
+            entity.as_player().map(|player| (player.owner(), player.current_data()))
+
+        }).into_iter().filter_map(|p| p).collect();
+
+        for bot in &mut self.bots {
+            if let Some(entity) = entity_server.entity_get_mut(&bot.entity_slot).and_then(|e| e.as_player_mut()) {
+                let data = entity.current_data();
+                entity.input(bot.update(t, self.tick, level, &data, &players));
+            }
+        }
+
+    }
+
+
     // Connection Handling ----------------------------------------------------
     fn connect(
         &mut self,
@@ -440,6 +1124,17 @@ impl Server {
         conn: &mut cobalt::Connection
     ) {
 
+        let _log = logging::connection(conn.id());
+
+        // Human players replace bots
+        let players = self.connections.len() + self.bots.len();
+        if self.available_colors.is_empty() || (self.bot_fill > 0 && players >= self.bot_fill) {
+            let bot_id = self.bots.last().map(|b| b.id);
+            if let Some(bot_id) = bot_id {
+                self.remove_bot(entity_server, &bot_id);
+            }
+        }
+
         // Find a potential spawn point
         let spawn = self.find_player_spawn(entity_server, level);
 
@@ -450,33 +1145,39 @@ impl Server {
             if let Some(color) = self.available_colors.pop() {
 
                 // Create a new player entity for the connected client
+                let policy = self.missing_input_policy;
                 if let Ok(entity_slot) = entity_server.entity_create_with(|| {
 
-                    Box::new(PlayerEntity::<ServerState<PlayerData, PlayerInput>>::new(
+                    let mut entity = ServerPlayerEntity::new(
                         Some(conn.id()),
                         false,
                         color,
                         PlayerData::new(spawn.x, spawn.y, 0.0, PLAYER_MAX_HP)
-                    ))
+                    );
+                    entity.state.set_missing_input_policy(policy);
+                    Box::new(entity)
 
                 }) {
-                    println!("[Server] New client connection.");
+                    info!("New client connection.");
+                    if let Some(ref mut recorder) = self.recorder {
+                        recorder.join(conn.id(), color);
+                    }
                     self.connections.insert(
                         conn.id(), (slot, entity_slot, color, VecDeque::new())
                     );
 
                 } else {
-                    println!("[Server] No more entity slots.");
+                    error!("No more entity slots.");
                     conn.close()
                 }
 
             } else {
-                println!("[Server] No more available colors.");
+                warn!("No more available colors.");
                 conn.close();
             }
 
         } else {
-            println!("[Server] No more connection slots.");
+            warn!("No more connection slots.");
             conn.close();
         }
 
@@ -487,8 +1188,14 @@ impl Server {
         entity_server: &mut hexahydrate::Server<Entity, ConnectionID>,
         id: &ConnectionID
     ) {
+        let _log = logging::connection(*id);
+        self.network_stats.remove(id);
         if let Some((slot, entity_slot, color, _)) = self.connections.remove(id) {
-            println!("[Server] Client disconnected.");
+            info!("Client disconnected.");
+            self.conditioner.retain(|&(conn_id, _, _)| conn_id != *id);
+            if let Some(ref mut recorder) = self.recorder {
+                recorder.leave(*id);
+            }
             entity_server.entity_destroy(entity_slot).ok();
             entity_server.connection_remove(slot).expect("Connection does not exist.");
             self.available_colors.push(color);
@@ -497,3 +1204,34 @@ impl Server {
 
 }
 
+
+// Helpers --------------------------------------------------------------------
+fn send_clock_response(
+    conditioner: &mut NetworkConditioner<(ConnectionID, bool, Vec<u8>)>,
+    id: ConnectionID,
+    client_time: u64,
+    tick: u16,
+    tick_time: u64
+) {
+    let t = clock_ticks::precise_time_ms();
+    let elapsed = cmp::min(t.saturating_sub(tick_time), u16::max_value() as u64) as u16;
+    let response = Action::ClockResponse(client_time, t, tick, elapsed).to_bytes();
+    conditioner.send(t, (id, false, response), false);
+}
+
+fn player_slot<'a>(
+    connections: &'a HashMap<ConnectionID, (
+        hexahydrate::ConnectionSlot<ConnectionID>,
+        hexahydrate::ServerEntitySlot,
+        ColorName,
+        VecDeque<Action>
+    )>,
+    bots: &'a [Bot],
+    id: &ConnectionID
+
+) -> Option<&'a hexahydrate::ServerEntitySlot> {
+    connections.get(id).map(|c| &c.1).or_else(|| {
+        bots.iter().find(|b| b.id == *id).map(|b| &b.entity_slot)
+    })
+}
+
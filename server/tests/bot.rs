@@ -0,0 +1,70 @@
+extern crate cobalt;
+extern crate hexahydrate;
+extern crate server;
+extern crate shared;
+
+use cobalt::ConnectionID;
+use server::{Entity, Server, BotConfig, DEFAULT_LEVEL_DATA};
+use shared::level::Level;
+
+
+// Helpers --------------------------------------------------------------------
+fn setup() -> (Server, hexahydrate::Server<Entity, ConnectionID>, Level) {
+    (
+        Server::new("127.0.0.1:7156".to_string(), 30),
+        hexahydrate::Server::new(60),
+        Level::from_toml_string(DEFAULT_LEVEL_DATA)
+    )
+}
+
+
+// Runtime Bot Management -----------------------------------------------------
+#[test]
+fn test_add_and_remove_bots() {
+
+    let (mut server, mut entities, level) = setup();
+    let a = server.add_bot(&mut entities, &level).unwrap();
+    let b = server.add_bot(&mut entities, &level).unwrap();
+    assert!(a != b);
+    assert_eq!(server.bots(), vec![a, b]);
+
+    assert!(server.remove_bot(&mut entities, &a));
+    assert_eq!(server.bots(), vec![b]);
+
+    // Unknown bots are ignored
+    assert!(!server.remove_bot(&mut entities, &a));
+    assert_eq!(server.bots(), vec![b]);
+
+}
+
+#[test]
+fn test_change_bot_fill() {
+
+    let (mut server, mut entities, level) = setup();
+    server.set_bot_fill(3, BotConfig::default());
+    server.fill_bots(&mut entities, &level);
+    assert_eq!(server.bots().len(), 3);
+
+    // Lowering the fill removes the surplus bots
+    server.set_bot_fill(1, BotConfig::default());
+    server.fill_bots(&mut entities, &level);
+    assert_eq!(server.bots().len(), 1);
+
+    server.set_bot_fill(2, BotConfig::default());
+    server.fill_bots(&mut entities, &level);
+    assert_eq!(server.bots().len(), 2);
+
+}
+
+#[test]
+fn test_manual_bots_without_fill() {
+
+    let (mut server, mut entities, level) = setup();
+    let id = server.add_bot(&mut entities, &level).unwrap();
+
+    // Without a fill bots are only managed manually
+    server.fill_bots(&mut entities, &level);
+    assert_eq!(server.bots(), vec![id]);
+
+}
+
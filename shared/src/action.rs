@@ -17,10 +17,14 @@ pub const LASER_BEAM_DURATION: u64 = 150;
 #[derive(Debug, RustcEncodable, RustcDecodable, Clone)]
 pub enum Action {
     JoinGame,
-    FiredLaserBeam(u8, f32),
+    FiredWeapon(u8, u16, u16, f32),
     CreateLaserBeam(u8, f32, f32, f32, f32),
-    LaserBeamHit(u8, u8, f32, f32),
-    LaserBeamKill(u8, u8, f32, f32)
+    ProjectileImpact(u8, f32, f32, f32),
+    PlayerHit(u8, u8, f32, f32),
+    PlayerKill(u8, u8, f32, f32),
+    PickupCollected(u8, u8, f32, f32),
+    ClockRequest(u64),
+    ClockResponse(u64, u64, u16, u16)
 }
 
 impl Action {
@@ -0,0 +1,59 @@
+// External Dependencies ------------------------------------------------------
+use clap::{Arg, ArgMatches};
+use netsync::NetworkConditions;
+
+
+// Network Conditioner Arguments ----------------------------------------------
+
+/// Command line arguments for simulating bad network conditions, the
+/// conditions apply to all packets sent by the process which parses them.
+pub fn conditioner_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("latency")
+            .long("latency")
+            .value_name("MS")
+            .help("Simulates the given one way latency in milliseconds for all outgoing packets.")
+            .takes_value(true)
+            .default_value("0"),
+
+        Arg::with_name("jitter")
+            .long("jitter")
+            .value_name("MS")
+            .help("Simulates up to the given random delay in milliseconds on top of the latency.")
+            .takes_value(true)
+            .default_value("0"),
+
+        Arg::with_name("loss")
+            .long("loss")
+            .value_name("PROBABILITY")
+            .help("Simulates the loss of unreliable packets with a probability from 0.0 to 1.0.")
+            .takes_value(true)
+            .default_value("0.0"),
+
+        Arg::with_name("duplication")
+            .long("duplication")
+            .value_name("PROBABILITY")
+            .help("Simulates duplicated unreliable packets with a probability from 0.0 to 1.0.")
+            .takes_value(true)
+            .default_value("0.0"),
+
+        Arg::with_name("reordering")
+            .long("reordering")
+            .value_name("PROBABILITY")
+            .help("Simulates reordered unreliable packets with a probability from 0.0 to 1.0.")
+            .takes_value(true)
+            .default_value("0.0")
+    ]
+}
+
+/// Parses the arguments added via `conditioner_args`.
+pub fn conditions_from_matches(matches: &ArgMatches) -> NetworkConditions {
+    NetworkConditions {
+        latency: matches.value_of("latency").unwrap().parse().expect("Invalid latency."),
+        jitter: matches.value_of("jitter").unwrap().parse().expect("Invalid jitter."),
+        loss: matches.value_of("loss").unwrap().parse().expect("Invalid loss probability."),
+        duplication: matches.value_of("duplication").unwrap().parse().expect("Invalid duplication probability."),
+        reordering: matches.value_of("reordering").unwrap().parse().expect("Invalid reordering probability.")
+    }
+}
+
@@ -0,0 +1,289 @@
+// STD Dependencies -----------------------------------------------------------
+use std::collections::VecDeque;
+
+
+// External Dependencies ------------------------------------------------------
+use netsync::{tick_diff, tick_offset};
+
+
+// Internal Dependencies ------------------------------------------------------
+use ::action::Action;
+
+
+// Statics --------------------------------------------------------------------
+pub const CLOCK_SYNC_INTERVAL: u64 = 1000;
+pub const CLOCK_SYNC_MIN_SAMPLES: usize = 4;
+const CLOCK_SYNC_BURST_INTERVAL: u64 = 100;
+const CLOCK_SYNC_MAX_SAMPLES: usize = 16;
+
+/// Time after which an unanswered clock request is considered lost
+const CLOCK_SYNC_TIMEOUT: u64 = 2000;
+
+/// Additional ticks the client runs ahead of the server to absorb jitter
+const CLOCK_TICK_MARGIN: f64 = 1.0;
+
+/// Maximum amount by which the local tick rate is sped up or slowed down
+const CLOCK_MAX_DILATION: f64 = 0.05;
+
+/// Dilation applied per tick of drift
+const CLOCK_DILATION_FACTOR: f64 = 0.01;
+
+/// Drift in ticks after which the local tick is reset instead of dilated
+const CLOCK_SNAP_TICKS: f64 = 8.0;
+
+
+// Clock Synchronization ------------------------------------------------------
+#[derive(Debug, Copy, Clone)]
+struct ClockSample {
+    rtt: f64,
+    offset: f64
+}
+
+/// Client side estimation of the server's tick timeline.
+///
+/// Clients periodically send `Action::ClockRequest` which the server answers
+/// with its current time and tick. From these samples the round trip time and
+/// the offset between both clocks are estimated, the offset is taken from the
+/// sample with the lowest round trip time since it is the least affected by
+/// queuing delays.
+///
+/// The local tick is then kept ahead of the server by half the round trip time
+/// plus some margin, so inputs arrive right before the server simulates their
+/// tick. Small amounts of drift are corrected by running slightly more or
+/// fewer simulation steps (time dilation), larger ones reset the local tick.
+#[derive(Debug)]
+pub struct ClockSync {
+    tick_duration: f64,
+    samples: VecDeque<ClockSample>,
+    pending: VecDeque<u64>,
+    outcomes: VecDeque<bool>,
+    rtt: f64,
+    jitter: f64,
+    offset: f64,
+    base_tick: u16,
+    base_time: f64,
+    next_request: u64,
+    dilation: f64,
+    steps: f64
+}
+
+impl ClockSync {
+
+    pub fn new(updates_per_second: u64) -> ClockSync {
+        ClockSync {
+            tick_duration: 1000.0 / updates_per_second as f64,
+            samples: VecDeque::new(),
+            pending: VecDeque::new(),
+            outcomes: VecDeque::new(),
+            rtt: 0.0,
+            jitter: 0.0,
+            offset: 0.0,
+            base_tick: 0,
+            base_time: 0.0,
+            next_request: 0,
+            dilation: 1.0,
+            steps: 0.0
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.samples.clear();
+        self.pending.clear();
+        self.outcomes.clear();
+        self.rtt = 0.0;
+        self.jitter = 0.0;
+        self.offset = 0.0;
+        self.next_request = 0;
+        self.dilation = 1.0;
+        self.steps = 0.0;
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.samples.len() >= CLOCK_SYNC_MIN_SAMPLES
+    }
+
+    /// Average round trip time in milliseconds.
+    pub fn rtt(&self) -> f64 {
+        self.rtt
+    }
+
+    /// Mean deviation of the round trip time in milliseconds.
+    pub fn jitter(&self) -> f64 {
+        self.jitter
+    }
+
+    /// Offset of the server clock relative to the local one in milliseconds.
+    pub fn offset(&self) -> f64 {
+        self.offset
+    }
+
+    /// Percentage of recent clock requests which went unanswered.
+    pub fn packet_loss(&self) -> f32 {
+        if self.outcomes.is_empty() {
+            0.0
+
+        } else {
+            let lost = self.outcomes.iter().filter(|answered| !**answered).count();
+            lost as f32 * 100.0 / self.outcomes.len() as f32
+        }
+    }
+
+    /// Current rate of the local tick relative to the server's.
+    pub fn dilation(&self) -> f64 {
+        self.dilation
+    }
+
+    /// Returns a clock request whenever the next one is due, requests are
+    /// sent in quick succession until enough samples have been collected.
+    pub fn request(&mut self, t: u64) -> Option<Action> {
+        self.expire(t);
+        if t >= self.next_request {
+            self.next_request = t + if self.is_synced() {
+                CLOCK_SYNC_INTERVAL
+
+            } else {
+                CLOCK_SYNC_BURST_INTERVAL
+            };
+            self.pending.push_back(t);
+            Some(Action::ClockRequest(t))
+
+        } else {
+            None
+        }
+    }
+
+    /// Takes in the server's answer to a previous clock request.
+    ///
+    /// `tick_elapsed` is the time which has passed on the server since it
+    /// started simulating `server_tick`. Duplicated responses and those to
+    /// requests which already timed out are ignored, returns whether the
+    /// response was taken in.
+    pub fn receive(
+        &mut self,
+        t: u64,
+        client_time: u64,
+        server_time: u64,
+        server_tick: u16,
+        tick_elapsed: u16
+
+    ) -> bool {
+
+        if client_time > t {
+            return false;
+        }
+
+        self.expire(t);
+        if let Some(index) = self.pending.iter().position(|time| *time == client_time) {
+            self.pending.remove(index);
+            self.add_outcome(true);
+
+        } else {
+            return false;
+        }
+
+        let rtt = (t - client_time) as f64;
+        self.samples.push_back(ClockSample {
+            rtt: rtt,
+            offset: server_time as f64 + rtt * 0.5 - t as f64
+        });
+
+        if self.samples.len() > CLOCK_SYNC_MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+
+        let count = self.samples.len() as f64;
+        self.rtt = self.samples.iter().fold(0.0, |sum, s| sum + s.rtt) / count;
+        self.jitter = self.samples.iter().fold(0.0, |sum, s| sum + (s.rtt - self.rtt).abs()) / count;
+        self.offset = self.samples.iter().fold(None, |best: Option<ClockSample>, s| {
+            match best {
+                Some(b) if b.rtt <= s.rtt => Some(b),
+                _ => Some(*s)
+            }
+
+        }).map_or(0.0, |s| s.offset);
+
+        // Local time at which the server started simulating the tick
+        self.base_tick = server_tick;
+        self.base_time = server_time.saturating_sub(tick_elapsed as u64) as f64 - self.offset;
+        true
+
+    }
+
+    /// Number of ticks the local tick should be ahead of the server's.
+    pub fn lead(&self) -> f64 {
+        (self.rtt * 0.5 + self.jitter) / self.tick_duration + CLOCK_TICK_MARGIN
+    }
+
+    /// Estimated server tick at the local time `t`.
+    pub fn server_tick(&self, t: u64) -> u16 {
+        self.tick_at(t as f64)
+    }
+
+    /// Estimated server tick of the remote states which are displayed at the
+    /// local time `t`, i.e. the tick the server sent them at.
+    pub fn view_tick(&self, t: u64) -> u16 {
+        self.tick_at(t as f64 - self.rtt * 0.5)
+    }
+
+    /// Number of ticks the local tick is behind its target, negative when
+    /// running ahead.
+    pub fn drift(&self, tick: u16, t: u64) -> f64 {
+        let target = (t as f64 - self.base_time) / self.tick_duration + self.lead();
+        target - tick_diff(tick, self.base_tick) as f64
+    }
+
+    /// Returns a new local tick in case the drift has become too large to be
+    /// corrected via dilation, e.g. right after the initial synchronization.
+    pub fn align(&mut self, tick: u16, t: u64) -> Option<u16> {
+        let drift = self.drift(tick, t);
+        if self.is_synced() && drift.abs() > CLOCK_SNAP_TICKS {
+            self.steps = 0.0;
+            Some(tick_offset(tick, drift.round() as i64))
+
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of simulation steps to run for the current update.
+    ///
+    /// This is usually one but becomes zero or two every now and then in order
+    /// to slowly catch up with or fall back to the target tick.
+    pub fn steps(&mut self, tick: u16, t: u64) -> usize {
+
+        self.dilation = if self.is_synced() {
+            let correction = self.drift(tick, t) * CLOCK_DILATION_FACTOR;
+            1.0 + correction.max(-CLOCK_MAX_DILATION).min(CLOCK_MAX_DILATION)
+
+        } else {
+            1.0
+        };
+
+        self.steps += self.dilation;
+        let steps = self.steps.floor();
+        self.steps -= steps;
+        steps as usize
+
+    }
+
+    fn expire(&mut self, t: u64) {
+        while self.pending.front().map_or(false, |time| time + CLOCK_SYNC_TIMEOUT < t) {
+            self.pending.pop_front();
+            self.add_outcome(false);
+        }
+    }
+
+    fn add_outcome(&mut self, answered: bool) {
+        self.outcomes.push_back(answered);
+        if self.outcomes.len() > CLOCK_SYNC_MAX_SAMPLES {
+            self.outcomes.pop_front();
+        }
+    }
+
+    fn tick_at(&self, t: f64) -> u16 {
+        let ticks = ((t - self.base_time) / self.tick_duration).floor();
+        tick_offset(self.base_tick, ticks as i64)
+    }
+
+}
+
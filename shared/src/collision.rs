@@ -131,6 +131,26 @@ pub fn line_segment_intersect_circle_test(line: &[f32; 4], cx: f32, cy: f32, r:
 
 }
 
+pub fn line_segment_distance_to_point(line: &[f32; 4], x: f32, y: f32) -> f32 {
+
+    let (ax, ay) = (line[0], line[1]);
+    let (dx, dy) = (line[2] - ax, line[3] - ay);
+
+    // Project the point onto the segment and clamp to its end points
+    let l = dx * dx + dy * dy;
+    let t = if l > 0.0 {
+        ((x - ax) * dx + (y - ay) * dy) / l
+
+    } else {
+        0.0
+    };
+
+    let t = t.max(0.0).min(1.0);
+    let (ex, ey) = (ax + t * dx - x, ay + t * dy - y);
+    (ex * ex + ey * ey).sqrt()
+
+}
+
 pub fn line_intersect_line(line: &[f32; 4], other: &[f32; 4]) -> Option<[f32; 3]> {
 
     let (ax, ay) = ( line[2] -  line[0],  line[3] -  line[1]);
@@ -1,7 +1,22 @@
 // Modules --------------------------------------------------------------------
 mod player;
+mod pickup;
+mod projectile;
 
 
 // Re-Exports -----------------------------------------------------------------
 pub use self::player::*;
+pub use self::pickup::*;
+pub use self::projectile::*;
 
+
+// Entity Kinds ---------------------------------------------------------------
+pub const ENTITY_KIND_PLAYER: u8 = 1;
+pub const ENTITY_KIND_PICKUP: u8 = 2;
+pub const ENTITY_KIND_PROJECTILE: u8 = 3;
+
+
+// Traits ---------------------------------------------------------------------
+pub trait EntityKind {
+    fn kind_id() -> u8;
+}
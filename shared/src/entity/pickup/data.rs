@@ -0,0 +1,68 @@
+// External Dependencies ------------------------------------------------------
+use bincode::SizeLimit;
+use bincode::rustc_serialize::{encode, decode};
+
+
+// Internal Dependencies ------------------------------------------------------
+use super::{PickupKind, PICKUP_RADIUS};
+
+
+// Pickup Network Data --------------------------------------------------------
+#[derive(Debug, Clone)]
+pub struct PickupData {
+    pub kind: PickupKind,
+    pub x: f32,
+    pub y: f32,
+    pub active: bool
+}
+
+impl PickupData {
+
+    pub fn new(kind: PickupKind, x: f32, y: f32) -> PickupData {
+        PickupData {
+            kind: kind,
+            x: x,
+            y: y,
+            active: true
+        }
+    }
+
+    pub fn aabb(&self) -> [f32; 4] {
+        [
+            self.x - PICKUP_RADIUS,
+            self.y - PICKUP_RADIUS,
+            self.x + PICKUP_RADIUS,
+            self.y + PICKUP_RADIUS
+        ]
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode(&PickupNetworkData(
+            self.kind.to_u8(),
+            self.active,
+            self.x,
+            self.y
+
+        ), SizeLimit::Bounded(10)).unwrap()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<PickupData> {
+        if let Ok(data) = decode::<PickupNetworkData>(bytes) {
+            PickupKind::from_u8(data.0).map(|kind| {
+                PickupData {
+                    kind: kind,
+                    x: data.2,
+                    y: data.3,
+                    active: data.1
+                }
+            })
+
+        } else {
+            None
+        }
+    }
+
+}
+
+#[derive(RustcEncodable, RustcDecodable)]
+struct PickupNetworkData(u8, bool, f32, f32);
@@ -0,0 +1,94 @@
+// External Dependencies ------------------------------------------------------
+use hexahydrate;
+use cobalt::ConnectionID;
+
+
+// Internal Dependencies ------------------------------------------------------
+use super::{PickupData, PickupKind};
+use ::entity::{EntityKind, ENTITY_KIND_PICKUP};
+
+
+// Entities -------------------------------------------------------------------
+#[derive(Debug)]
+pub struct PickupEntity {
+    pub data: PickupData,
+    pub respawn_interval: u64,
+    pub respawn_at: u64,
+    pub is_new: bool,
+    authoritative: bool
+}
+
+impl PickupEntity {
+
+    pub fn new(kind: PickupKind, x: f32, y: f32, respawn_interval: u64) -> PickupEntity {
+        PickupEntity {
+            data: PickupData::new(kind, x, y),
+            respawn_interval: respawn_interval,
+            respawn_at: 0,
+            is_new: true,
+            authoritative: true
+        }
+    }
+
+    pub fn collect(&mut self, t: u64) {
+        self.data.active = false;
+        self.respawn_at = t + self.respawn_interval;
+    }
+
+    pub fn update(&mut self, t: u64) {
+        if !self.data.active && t >= self.respawn_at {
+            self.data.active = true;
+        }
+    }
+
+}
+
+impl EntityKind for PickupEntity {
+    fn kind_id() -> u8 {
+        ENTITY_KIND_PICKUP
+    }
+}
+
+
+// Shared Entity --------------------------------------------------------------
+impl hexahydrate::Entity<ConnectionID> for PickupEntity {
+
+    fn part_bytes(&mut self, _: Option<&hexahydrate::ConnectionSlot<ConnectionID>>) -> Option<Vec<u8>> {
+
+        // Only the server side entity sends out its state
+        if self.authoritative {
+            Some(vec![if self.data.active { 1 } else { 0 }])
+
+        } else {
+            None
+        }
+
+    }
+
+    fn merge_bytes(&mut self, _: Option<&hexahydrate::ConnectionSlot<ConnectionID>>, bytes: &[u8]) {
+        if !self.authoritative && !bytes.is_empty() {
+            self.data.active = bytes[0] == 1;
+        }
+    }
+
+    fn kind(&self) -> u8 {
+        Self::kind_id()
+    }
+
+    fn to_bytes(&self, _: &hexahydrate::ConnectionSlot<ConnectionID>) -> Vec<u8> {
+        self.data.to_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<PickupEntity> {
+        PickupData::from_bytes(bytes).map(|data| {
+            PickupEntity {
+                data: data,
+                respawn_interval: 0,
+                respawn_at: 0,
+                is_new: true,
+                authoritative: false
+            }
+        })
+    }
+
+}
@@ -0,0 +1,83 @@
+// Modules --------------------------------------------------------------------
+mod entity;
+mod data;
+
+
+// Internal Dependencies ------------------------------------------------------
+use ::color::ColorName;
+use ::entity::{PLAYER_BUFF_DAMAGE, PLAYER_BUFF_SPEED, PLAYER_BUFF_SHIELD};
+
+
+// Statics --------------------------------------------------------------------
+pub const PICKUP_RADIUS: f32 = 4.0;
+pub const PICKUP_RESPAWN_INTERVAL: u64 = 15000;
+pub const PICKUP_HEALTH_AMOUNT: u8 = 96;
+pub const PICKUP_BUFF_DURATION: u64 = 10000;
+
+
+// Re-Exports -----------------------------------------------------------------
+pub use self::entity::PickupEntity;
+pub use self::data::PickupData;
+
+
+// Pickup Kinds ---------------------------------------------------------------
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PickupKind {
+    Health,
+    DamageBoost,
+    Speed,
+    Shield
+}
+
+impl PickupKind {
+
+    pub fn to_u8(&self) -> u8 {
+        match *self {
+            PickupKind::Health => 0,
+            PickupKind::DamageBoost => 1,
+            PickupKind::Speed => 2,
+            PickupKind::Shield => 3
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Option<PickupKind> {
+        match value {
+            0 => Some(PickupKind::Health),
+            1 => Some(PickupKind::DamageBoost),
+            2 => Some(PickupKind::Speed),
+            3 => Some(PickupKind::Shield),
+            _ => None
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<PickupKind> {
+        match name {
+            "health" => Some(PickupKind::Health),
+            "damage" => Some(PickupKind::DamageBoost),
+            "speed" => Some(PickupKind::Speed),
+            "shield" => Some(PickupKind::Shield),
+            _ => None
+        }
+    }
+
+    pub fn color_name(&self) -> ColorName {
+        match *self {
+            PickupKind::Health => ColorName::Green,
+            PickupKind::DamageBoost => ColorName::Red,
+            PickupKind::Speed => ColorName::Yellow,
+            PickupKind::Shield => ColorName::Blue
+        }
+    }
+
+    /// Returns the player buff flag which is granted by the pickup, health
+    /// pickups are applied instantly and do not grant a buff.
+    pub fn buff(&self) -> Option<u8> {
+        match *self {
+            PickupKind::Health => None,
+            PickupKind::DamageBoost => Some(PLAYER_BUFF_DAMAGE),
+            PickupKind::Speed => Some(PLAYER_BUFF_SPEED),
+            PickupKind::Shield => Some(PLAYER_BUFF_SHIELD)
+        }
+    }
+
+}
@@ -9,7 +9,11 @@ use bincode::rustc_serialize::{encode, decode};
 
 
 // Internal Dependencies ------------------------------------------------------
-use super::{PlayerInput, PLAYER_SPEED, PLAYER_RADIUS};
+use super::{
+    PlayerInput,
+    PLAYER_SPEED, PLAYER_RADIUS,
+    PLAYER_BUFF_SPEED, PLAYER_BUFF_SPEED_FACTOR
+};
 use ::util::{rad_to_u16, u16_to_rad};
 use ::level::LevelCollision;
 
@@ -25,6 +29,7 @@ pub struct PlayerData {
     pub y: f32,
     pub r: f32,
     pub hp: u8,
+    pub buffs: u8,
     pub visible: bool,
     pub vx: f32,
     pub vy: f32
@@ -38,6 +43,7 @@ impl PlayerData {
             y: y,
             r: r,
             hp: hp,
+            buffs: 0,
             visible: false,
             vx: 0.0,
             vy: 0.0
@@ -50,14 +56,17 @@ impl NetworkProperty for PlayerData {
 
     fn interpolate_from(&self, last: &Self, u: f32) -> Self {
 
+        let dx = self.x - last.x;
+        let dy = self.y - last.y;
+
         // Prevent interpolation glitches when a player entity becomes visible
-        // again
-        if !last.visible {
+        // again or moved further than it could walk, e.g. after respawning.
+        // States might be several ticks apart, so the distance is not limited
+        // otherwise.
+        if !last.visible || dx.abs() > PLAYER_SPEED * 3.0 || dy.abs() > PLAYER_SPEED * 3.0 {
             self.clone()
 
         } else {
-            let dx = (self.x - last.x).max(-PLAYER_SPEED * 3.0).min(PLAYER_SPEED * 3.0);
-            let dy = (self.y - last.y).max(-PLAYER_SPEED * 3.0).min(PLAYER_SPEED * 3.0);
             let r = self.r - last.r;
             let dr = r.sin().atan2(r.cos());
 
@@ -66,6 +75,7 @@ impl NetworkProperty for PlayerData {
                 y: last.y + dy * u,
                 r: last.r + dr * u,
                 hp: self.hp,
+                buffs: self.buffs,
                 visible: self.visible,
                 vx: self.vx,
                 vy: self.vy
@@ -80,9 +90,10 @@ impl NetworkProperty for PlayerData {
             self.x,
             self.y,
             rad_to_u16(self.r),
-            self.hp
+            self.hp,
+            self.buffs
 
-        ), SizeLimit::Bounded(12)).unwrap()
+        ), SizeLimit::Bounded(13)).unwrap()
     }
 
     fn from_bytes(bytes: &[u8]) -> Self where Self: Sized {
@@ -93,11 +104,27 @@ impl NetworkProperty for PlayerData {
             r: u16_to_rad(position.3),
             visible: position.0,
             hp: position.4,
+            buffs: position.5,
             vx: 0.0,
             vy: 0.0
         }
     }
 
+    fn error_distance(&self, other: &Self) -> f32 {
+        // Rotation is driven by local input only and never mispredicted
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+
+    fn apply_error(&self, from: &Self, to: &Self, u: f32) -> Self {
+        // Dead players are hidden and respawn at a new position
+        let mut data = self.clone();
+        if self.hp > 0 {
+            data.x += (from.x - to.x) * u;
+            data.y += (from.y - to.y) * u;
+        }
+        data
+    }
+
 }
 
 impl PlayerData {
@@ -106,6 +133,10 @@ impl PlayerData {
 
         let (mut dx, mut dy) = (0.0, 0.0);
         let mut speed = PLAYER_SPEED;
+        if state.buffs & PLAYER_BUFF_SPEED == PLAYER_BUFF_SPEED {
+            speed *= PLAYER_BUFF_SPEED_FACTOR;
+        }
+
         if input.buttons & 16 == 16 {
             speed *= 0.5;
         }
@@ -167,5 +198,5 @@ impl PlayerData {
 }
 
 #[derive(RustcEncodable, RustcDecodable)]
-struct PlayerNetworkPosition(bool, f32, f32, u16, u8);
+struct PlayerNetworkPosition(bool, f32, f32, u16, u8, u8);
 
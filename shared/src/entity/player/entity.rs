@@ -4,17 +4,25 @@ use std::collections::HashMap;
 
 // External Dependencies ------------------------------------------------------
 use hexahydrate;
+use clock_ticks;
 use cobalt::ConnectionID;
-use netsync::{NetworkState, ClientState, ServerState, NetworkProperty};
+use netsync::{tick_diff, NetworkState, ClientState, ServerState, NetworkProperty, SnapshotBuffer, Tick};
 
 
 // Internal Dependencies ------------------------------------------------------
-use super::{PlayerData, PlayerInput};
+use super::{
+    PlayerData, PlayerInput,
+    PLAYER_INPUT_REDUNDANCY, PLAYER_ERROR_SMOOTHING_TICKS, PLAYER_ERROR_SNAP_DISTANCE
+};
+use ::entity::{EntityKind, ENTITY_KIND_PLAYER};
 use ::color::{Color, ColorName};
+use ::UPDATES_PER_SECOND;
 
 
 // Statics --------------------------------------------------------------------
 pub const ENTITY_STATE_DELAY: u8 = 4;
+pub const PLAYER_INTERPOLATION_DELAY: u64 = 75;
+pub const PLAYER_MAX_EXTRAPOLATION: u64 = 100;
 
 
 // Entities -------------------------------------------------------------------
@@ -26,11 +34,13 @@ pub struct PlayerEntity<S: NetworkState<PlayerData, PlayerInput>> {
     pub local: bool,
     pub owner: Option<ConnectionID>,
     pub state: S,
+    pub snapshots: SnapshotBuffer<PlayerData>,
     pub is_new: bool,
     pub visibility_state: HashMap<ConnectionID, bool>,
     pub last_visible: u64,
     pub last_hidden: u64,
-    pub last_beam_fire: u64
+    pub last_weapon_fire: u64,
+    pub buff_timers: Vec<(u8, u64)>
 }
 
 impl<S: NetworkState<PlayerData, PlayerInput>> PlayerEntity<S> {
@@ -43,7 +53,7 @@ impl<S: NetworkState<PlayerData, PlayerInput>> PlayerEntity<S> {
 
     ) -> PlayerEntity<S> {
 
-        println!("Player entity created: {:?}", color);
+        debug!("Player entity created: {:?}", color);
 
         let mut entity = PlayerEntity {
             color: color,
@@ -52,11 +62,17 @@ impl<S: NetworkState<PlayerData, PlayerInput>> PlayerEntity<S> {
             local: local,
             owner: owner,
             state: S::new(30),
+            snapshots: SnapshotBuffer::new(
+                1000 / UPDATES_PER_SECOND,
+                PLAYER_INTERPOLATION_DELAY,
+                PLAYER_MAX_EXTRAPOLATION
+            ),
             is_new: true,
             visibility_state: HashMap::new(),
             last_hidden: 0,
             last_visible: 0,
-            last_beam_fire: 0
+            last_weapon_fire: 0,
+            buff_timers: Vec::new()
         };
 
         entity.state.set(data);
@@ -92,9 +108,9 @@ impl<S: NetworkState<PlayerData, PlayerInput>> PlayerEntity<S> {
         }
     }
 
-    pub fn fire_beam(&mut self, interval: u64, t: u64) -> bool {
-        if t >= self.last_beam_fire + interval {
-            self.last_beam_fire = t;
+    pub fn fire_weapon(&mut self, interval: u64, t: u64) -> bool {
+        if t >= self.last_weapon_fire + interval {
+            self.last_weapon_fire = t;
             true
 
         } else {
@@ -104,9 +120,15 @@ impl<S: NetworkState<PlayerData, PlayerInput>> PlayerEntity<S> {
 
 }
 
+impl<S: NetworkState<PlayerData, PlayerInput>> EntityKind for PlayerEntity<S> {
+    fn kind_id() -> u8 {
+        ENTITY_KIND_PLAYER
+    }
+}
+
 impl<S: NetworkState<PlayerData, PlayerInput>> Drop for PlayerEntity<S> {
     fn drop(&mut self) {
-        println!("Player entity dropped: {:?}", self.color);
+        debug!("Player entity dropped: {:?}", self.color);
     }
 }
 
@@ -119,7 +141,7 @@ impl hexahydrate::Entity<ConnectionID> for PlayerEntity<ServerState<PlayerData,
             Some(self.state.send(None))
 
         } else {
-            let bytes = self.state.send_with(Some(ENTITY_STATE_DELAY), |state| {
+            let bytes = self.state.send_snapshot_with(ENTITY_STATE_DELAY, |state| {
 
                 // Hide dead entities
                 let dead = state.hp == 0;
@@ -134,6 +156,7 @@ impl hexahydrate::Entity<ConnectionID> for PlayerEntity<ServerState<PlayerData,
                     state.x = 0.0;
                     state.y = 0.0;
                     state.r = 0.0;
+                    state.buffs = 0;
                     state.visible = false;
                 }
 
@@ -152,7 +175,7 @@ impl hexahydrate::Entity<ConnectionID> for PlayerEntity<ServerState<PlayerData,
     }
 
     fn kind(&self) -> u8 {
-        1
+        Self::kind_id()
     }
 
     fn to_bytes(&self, connection_slot: &hexahydrate::ConnectionSlot<ConnectionID>) -> Vec<u8> {
@@ -180,33 +203,71 @@ impl hexahydrate::Entity<ConnectionID> for PlayerEntity<ClientState<PlayerData,
 
     fn merge_bytes(&mut self, _: Option<&hexahydrate::ConnectionSlot<ConnectionID>>, bytes: &[u8]) {
         if self.local {
-            self.state.receive(&bytes[1..], Some(bytes[0]));
+            self.state.receive_confirmed(bytes);
 
         } else {
-            self.state.receive_with(bytes, None, |current, state| {
-                if !state.visible {
-                    state.x = current.x;
-                    state.y = current.y;
-                    state.r = current.r;
-                    state.vx = current.vx;
-                    state.vy = current.vy;
+            if let Some((size, sequence)) = u16::from_bytes(bytes) {
+
+                let mut snapshot = None;
+                self.state.receive_with(&bytes[size..], None, |current, state| {
+                    if !state.visible {
+                        state.x = current.x;
+                        state.y = current.y;
+                        state.r = current.r;
+                        state.vx = current.vx;
+                        state.vy = current.vy;
+                    }
+                    snapshot = Some(state.clone());
+                });
+
+                if let Some(snapshot) = snapshot {
+                    self.snapshots.push(clock_ticks::precise_time_ms(), sequence, snapshot);
                 }
-            });
+
+            }
         }
     }
 
     fn kind(&self) -> u8 {
-        1
+        Self::kind_id()
     }
 
     fn from_bytes(bytes: &[u8]) -> Option<PlayerEntity<ClientState<PlayerData, PlayerInput>>> {
-        Some(PlayerEntity::new(
+
+        let mut entity = PlayerEntity::new(
             None,
             bytes[0] == 1,
             ColorName::from_u8(bytes[1]),
             PlayerData::from_bytes(&bytes[2..])
-        ))
+        );
+
+        // Limit the number of unacknowledged inputs re-sent with each packet
+        entity.state.set_input_redundancy(PLAYER_INPUT_REDUNDANCY);
+
+        // Hide small prediction errors instead of popping to the server state
+        entity.state.set_error_smoothing(PLAYER_ERROR_SMOOTHING_TICKS, PLAYER_ERROR_SNAP_DISTANCE);
+        Some(entity)
+
     }
 
 }
 
+
+// Lag Compensation -----------------------------------------------------------
+
+/// Number of ticks by which other players are rewound when checking a shot
+/// fired while `view_tick` was displayed.
+///
+/// Clients render remote players `ENTITY_STATE_DELAY` ticks late on top of
+/// their latency, `delayed` should be `false` for shooters which see the
+/// current server state, like bots.
+pub fn rewind_ticks(server_tick: u16, view_tick: u16, delayed: bool) -> u8 {
+    let ticks_ago = tick_diff(server_tick, view_tick).clamp(0, 255) as u8;
+    if delayed {
+        ticks_ago.saturating_add(ENTITY_STATE_DELAY)
+
+    } else {
+        ticks_ago
+    }
+}
+
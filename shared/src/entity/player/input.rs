@@ -11,14 +11,14 @@ use ::util::{rad_to_u16, u16_to_rad};
 // Player Network Input -------------------------------------------------------
 #[derive(Debug, Default, Clone)]
 pub struct PlayerInput {
-    pub tick: u8,
+    pub tick: u16,
     pub buttons: u8,
     pub r: f32,
     pub dt: f32
 }
 
 impl PlayerInput {
-    pub fn new(tick: u8, buttons: u8, r: f32, dt: f32) -> PlayerInput {
+    pub fn new(tick: u16, buttons: u8, r: f32, dt: f32) -> PlayerInput {
         PlayerInput {
             tick: tick,
             buttons: buttons,
@@ -30,7 +30,9 @@ impl PlayerInput {
 
 impl NetworkInput for PlayerInput {
 
-    fn tick(&self) -> u8 {
+    type Tick = u16;
+
+    fn tick(&self) -> u16 {
         self.tick
     }
 
@@ -40,13 +42,13 @@ impl NetworkInput for PlayerInput {
             self.buttons,
             rad_to_u16(self.r)
 
-        ), SizeLimit::Bounded(4)).unwrap()
+        ), SizeLimit::Bounded(5)).unwrap()
     }
 
     fn from_bytes(bytes: &[u8]) -> Option<(usize, Self)> where Self: Sized {
-        if bytes.len() >= 4 {
+        if bytes.len() >= 5 {
             let input = decode::<PlayerNetworkInput>(bytes).unwrap();
-            Some((4, PlayerInput {
+            Some((5, PlayerInput {
                 tick: input.0,
                 buttons: input.1,
                 r: u16_to_rad(input.2),
@@ -58,8 +60,14 @@ impl NetworkInput for PlayerInput {
         }
     }
 
+    fn repeat(&self, tick: u16) -> Self where Self: Sized {
+        let mut input = self.clone();
+        input.tick = tick;
+        input
+    }
+
 }
 
 #[derive(RustcEncodable, RustcDecodable)]
-struct PlayerNetworkInput(u8, u8, u16);
+struct PlayerNetworkInput(u16, u8, u16);
 
@@ -15,11 +15,20 @@ pub const PLAYER_MAX_HP: u8 = 255;
 pub const PLAYER_RESPAWN_INTERVAL: u64 = 2000;
 pub const PLAYER_VISBILITY_CONE: f32 = consts::PI * 0.20;
 pub const PLAYER_VISBILITY_CONE_OFFSET: f32 = PLAYER_RADIUS * 3.0;
-pub const PLAYER_BEAM_FIRE_INTERVAL: u64 = 300;
+pub const PLAYER_INPUT_REDUNDANCY: usize = 8;
+pub const PLAYER_ERROR_SMOOTHING_TICKS: usize = 6;
+pub const PLAYER_ERROR_SNAP_DISTANCE: f32 = PLAYER_RADIUS * 4.0;
+
+pub const PLAYER_BUFF_DAMAGE: u8 = 1;
+pub const PLAYER_BUFF_SPEED: u8 = 2;
+pub const PLAYER_BUFF_SHIELD: u8 = 4;
+pub const PLAYER_BUFF_DAMAGE_FACTOR: f32 = 1.5;
+pub const PLAYER_BUFF_SPEED_FACTOR: f32 = 1.35;
+pub const PLAYER_BUFF_SHIELD_FACTOR: f32 = 0.5;
 
 
 // Re-Exports -----------------------------------------------------------------
-pub use self::entity::{PlayerEntity, ENTITY_STATE_DELAY};
+pub use self::entity::{PlayerEntity, rewind_ticks, ENTITY_STATE_DELAY, PLAYER_INTERPOLATION_DELAY};
 pub use self::input::PlayerInput;
 pub use self::data::PlayerData;
 
@@ -0,0 +1,150 @@
+// External Dependencies ------------------------------------------------------
+use netsync::NetworkProperty;
+use bincode::SizeLimit;
+use bincode::rustc_serialize::{encode, decode};
+
+
+// Internal Dependencies ------------------------------------------------------
+use ::weapon::Weapon;
+use ::color::ColorName;
+use ::level::LevelCollision;
+use ::util::{rad_to_u16, u16_to_rad};
+
+
+// Projectile Network Data ----------------------------------------------------
+#[derive(Debug, Clone)]
+pub struct ProjectileData {
+    pub weapon: Weapon,
+    pub color: ColorName,
+    pub x: f32,
+    pub y: f32,
+    pub r: f32,
+    pub travelled: f32,
+    pub ticks: u8,
+    pub impacted: bool
+}
+
+impl ProjectileData {
+
+    pub fn new(weapon: Weapon, color: ColorName, x: f32, y: f32, r: f32) -> ProjectileData {
+        ProjectileData {
+            weapon: weapon,
+            color: color,
+            x: x,
+            y: y,
+            r: r,
+            travelled: 0.0,
+            ticks: 0,
+            impacted: false
+        }
+    }
+
+    /// Advances the projectile by a single tick.
+    ///
+    /// Projectiles stop and are marked as impacted once they either hit a
+    /// level wall or reach the maximum range of their weapon.
+    pub fn update<L: LevelCollision>(dt: f32, state: &mut ProjectileData, level: &L) {
+
+        if state.impacted {
+            return;
+        }
+
+        let stats = state.weapon.stats();
+        let distance = (stats.projectile_speed.unwrap_or(0.0) * dt).min(stats.range - state.travelled);
+        let line = [
+            state.x,
+            state.y,
+            state.x + state.r.cos() * distance,
+            state.y + state.r.sin() * distance
+        ];
+
+        if let Some((_, intersection)) = level.collide_line(&line) {
+
+            // Stop slightly in front of the wall so that the impact point
+            // itself is not considered to be behind it
+            let l = (intersection[2] - 0.5).max(0.0);
+            state.x += state.r.cos() * l;
+            state.y += state.r.sin() * l;
+            state.impacted = true;
+
+        } else {
+            state.x = line[2];
+            state.y = line[3];
+        }
+
+        state.travelled += distance;
+        state.ticks = state.ticks.saturating_add(1);
+
+        if state.travelled >= stats.range {
+            state.impacted = true;
+        }
+
+    }
+
+    /// Decodes a serialized projectile state, returning `None` for payloads
+    /// which are malformed or refer to an unknown weapon.
+    pub fn decode(bytes: &[u8]) -> Option<ProjectileData> {
+        decode::<ProjectileNetworkData>(bytes).ok().and_then(|data| {
+            Weapon::from_u8(data.0).map(|weapon| ProjectileData {
+                weapon: weapon,
+                color: ColorName::from_u8(data.1),
+                x: data.2,
+                y: data.3,
+                r: u16_to_rad(data.4),
+                travelled: data.5,
+                ticks: data.6,
+                impacted: data.7
+            })
+        })
+    }
+
+}
+
+impl Default for ProjectileData {
+    fn default() -> ProjectileData {
+        ProjectileData::new(Weapon::LaserBeam, ColorName::Black, 0.0, 0.0, 0.0)
+    }
+}
+
+impl NetworkProperty for ProjectileData {
+
+    fn interpolate_from(&self, last: &Self, u: f32) -> Self {
+        ProjectileData {
+            weapon: self.weapon,
+            color: self.color,
+            x: last.x + (self.x - last.x) * u,
+            y: last.y + (self.y - last.y) * u,
+            r: self.r,
+            travelled: self.travelled,
+            ticks: self.ticks,
+            impacted: self.impacted
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        encode(&ProjectileNetworkData(
+            self.weapon.to_u8(),
+            self.color.to_u8(),
+            self.x,
+            self.y,
+            rad_to_u16(self.r),
+            self.travelled,
+            self.ticks,
+            self.impacted
+
+        ), SizeLimit::Bounded(18)).unwrap()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self where Self: Sized {
+        // Entities validate their payloads via decode() before they reach the
+        // network state, so this only guards against misuse
+        ProjectileData::decode(bytes).unwrap_or_else(|| {
+            error!("Invalid projectile state of {} bytes", bytes.len());
+            ProjectileData::default()
+        })
+    }
+
+}
+
+#[derive(RustcEncodable, RustcDecodable)]
+struct ProjectileNetworkData(u8, u8, f32, f32, u16, f32, u8, bool);
@@ -0,0 +1,143 @@
+// External Dependencies ------------------------------------------------------
+use hexahydrate;
+use cobalt::ConnectionID;
+use netsync::{NetworkState, ClientState, ServerState, Tick};
+
+
+// Internal Dependencies ------------------------------------------------------
+use super::{ProjectileData, ProjectileInput, PROJECTILE_STATE_BUFFER};
+use ::entity::{EntityKind, ENTITY_KIND_PROJECTILE};
+
+
+// Entities -------------------------------------------------------------------
+#[derive(Debug)]
+pub struct ProjectileEntity<S: NetworkState<ProjectileData, ProjectileInput>> {
+    pub local: bool,
+    pub owner: Option<ConnectionID>,
+    pub fired_tick: u16,
+    pub state: S,
+    pub is_new: bool
+}
+
+impl<S: NetworkState<ProjectileData, ProjectileInput>> ProjectileEntity<S> {
+
+    pub fn new(
+        owner: Option<ConnectionID>,
+        local: bool,
+        fired_tick: u16,
+        data: ProjectileData
+
+    ) -> ProjectileEntity<S> {
+
+        let mut entity = ProjectileEntity {
+            local: local,
+            owner: owner,
+            fired_tick: fired_tick,
+            state: S::new(PROJECTILE_STATE_BUFFER),
+            is_new: true
+        };
+
+        entity.state.set(data);
+        entity
+
+    }
+
+    pub fn is_owned_by(&self, connection_slot: Option<&hexahydrate::ConnectionSlot<ConnectionID>>) -> bool {
+        if let Some(slot) = connection_slot.as_ref() {
+            if let Some(owner) = self.owner.as_ref() {
+                slot.user_data == *owner
+
+            } else {
+                false
+            }
+
+        } else {
+            false
+        }
+    }
+
+}
+
+impl<S: NetworkState<ProjectileData, ProjectileInput>> EntityKind for ProjectileEntity<S> {
+    fn kind_id() -> u8 {
+        ENTITY_KIND_PROJECTILE
+    }
+}
+
+
+// Server Side Entity ---------------------------------------------------------
+impl hexahydrate::Entity<ConnectionID> for ProjectileEntity<ServerState<ProjectileData, ProjectileInput>> {
+
+    fn part_bytes(&mut self, _: Option<&hexahydrate::ConnectionSlot<ConnectionID>>) -> Option<Vec<u8>> {
+        Some(self.state.send(Some(0)))
+    }
+
+    fn merge_bytes(&mut self, _: Option<&hexahydrate::ConnectionSlot<ConnectionID>>, _: &[u8]) {
+        // Projectiles are fully simulated by the server
+    }
+
+    fn kind(&self) -> u8 {
+        Self::kind_id()
+    }
+
+    fn to_bytes(&self, connection_slot: &hexahydrate::ConnectionSlot<ConnectionID>) -> Vec<u8> {
+        let mut bytes = vec![if self.is_owned_by(Some(connection_slot)) { 1 } else { 0 }];
+        bytes.append(&mut self.fired_tick.to_bytes());
+        bytes.append(&mut self.state.send(Some(0)));
+        bytes
+    }
+
+}
+
+
+// Client Side Entity ---------------------------------------------------------
+impl hexahydrate::Entity<ConnectionID> for ProjectileEntity<ClientState<ProjectileData, ProjectileInput>> {
+
+    fn part_bytes(&mut self, _: Option<&hexahydrate::ConnectionSlot<ConnectionID>>) -> Option<Vec<u8>> {
+        None
+    }
+
+    fn merge_bytes(&mut self, _: Option<&hexahydrate::ConnectionSlot<ConnectionID>>, bytes: &[u8]) {
+
+        let ticks = match ProjectileData::decode(bytes) {
+            Some(data) => data.ticks,
+            None => {
+                warn!("Ignoring invalid projectile state of {} bytes", bytes.len());
+                return;
+            }
+        };
+
+        // The server steps projectiles once per tick starting with the tick
+        // they were fired on, so locally predicted steps up to the matching
+        // client tick are confirmed by this state
+        if self.local {
+            let confirmed_tick = self.fired_tick.wrapping_add(ticks as u16).wrapping_sub(1);
+            self.state.receive(bytes, Some(confirmed_tick));
+
+        } else {
+            self.state.receive_with(bytes, None, |_, _| {});
+        }
+
+    }
+
+    fn kind(&self) -> u8 {
+        Self::kind_id()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<ProjectileEntity<ClientState<ProjectileData, ProjectileInput>>> {
+        if bytes.len() > 3 {
+            if let Some(data) = ProjectileData::decode(&bytes[3..]) {
+                return Some(ProjectileEntity::new(
+                    None,
+                    bytes[0] == 1,
+                    bytes[1] as u16 | (bytes[2] as u16) << 8,
+                    data
+                ));
+            }
+        }
+
+        warn!("Ignoring invalid projectile entity of {} bytes", bytes.len());
+        None
+    }
+
+}
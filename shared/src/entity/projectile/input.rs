@@ -0,0 +1,52 @@
+// External Dependencies ------------------------------------------------------
+use netsync::{NetworkInput, Tick};
+
+
+// Projectile Simulation Input ------------------------------------------------
+//
+// Projectiles are not controlled by players, their inputs only represent the
+// simulation steps which the owning client predicted locally. They are never
+// sent over the network.
+#[derive(Debug, Default, Clone)]
+pub struct ProjectileInput {
+    pub tick: u16,
+    pub dt: f32
+}
+
+impl ProjectileInput {
+    pub fn new(tick: u16, dt: f32) -> ProjectileInput {
+        ProjectileInput {
+            tick: tick,
+            dt: dt
+        }
+    }
+}
+
+impl NetworkInput for ProjectileInput {
+
+    type Tick = u16;
+
+    fn tick(&self) -> u16 {
+        self.tick
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.tick.to_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<(usize, Self)> where Self: Sized {
+        u16::from_bytes(bytes).map(|(size, tick)| {
+            (size, ProjectileInput {
+                tick: tick,
+                dt: 0.0
+            })
+        })
+    }
+
+    fn repeat(&self, tick: u16) -> Self where Self: Sized {
+        let mut input = self.clone();
+        input.tick = tick;
+        input
+    }
+
+}
@@ -0,0 +1,15 @@
+// Modules --------------------------------------------------------------------
+mod entity;
+mod input;
+mod data;
+
+
+// Statics --------------------------------------------------------------------
+pub const PROJECTILE_RADIUS: f32 = 1.5;
+pub const PROJECTILE_STATE_BUFFER: usize = 64;
+
+
+// Re-Exports -----------------------------------------------------------------
+pub use self::entity::ProjectileEntity;
+pub use self::input::ProjectileInput;
+pub use self::data::ProjectileData;
@@ -4,6 +4,10 @@ use rand;
 use rand::Rng;
 
 
+// Internal Dependencies ------------------------------------------------------
+use ::entity::{PickupKind, PICKUP_RESPAWN_INTERVAL, PLAYER_RADIUS};
+
+
 // Modules --------------------------------------------------------------------
 mod collision;
 pub use self::collision::*;
@@ -20,6 +24,15 @@ pub use self::wall::*;
 mod spawn;
 pub use self::spawn::*;
 
+mod pickup_spawner;
+pub use self::pickup_spawner::LevelPickupSpawner;
+
+mod solid;
+pub use self::solid::*;
+
+mod navigation;
+pub use self::navigation::*;
+
 
 // Statics --------------------------------------------------------------------
 pub const MAX_LEVEL_SIZE: f32 = 512.0;
@@ -30,9 +43,12 @@ pub struct Level {
     pub walls: Vec<LevelWall>,
     pub lights: Vec<LightSource>,
     pub spawns: Vec<LevelSpawn>,
+    pub pickups: Vec<LevelPickupSpawner>,
     pub bounds: [f32; 4],
     pub solids: Vec<Vec<[f32; 2]>>,
-    wall_indicies: Vec<usize>
+    invalid_solids: Vec<(usize, SolidError)>,
+    wall_indicies: Vec<usize>,
+    navigation: LevelNavigationGrid
 }
 
 impl Level {
@@ -42,9 +58,12 @@ impl Level {
             walls: Vec::new(),
             lights: Vec::new(),
             spawns: vec![LevelSpawn::new(0.0, 0.0)],
+            pickups: Vec::new(),
             solids: Vec::new(),
+            invalid_solids: Vec::new(),
             bounds: [1000000.0, 1000000.0, -100000.0, -1000000.0],
-            wall_indicies: Vec::new()
+            wall_indicies: Vec::new(),
+            navigation: LevelNavigationGrid::new()
         }
     }
 
@@ -106,22 +125,54 @@ impl Level {
 
             }
 
+            // Load Pickup Spawners
+            if let Some(&toml::Value::Array(ref pickups)) = value.get("pickups") {
+                for pickup in pickups {
+                    if let toml::Value::Table(ref properties) = *pickup {
+
+                        let kind = properties.get("kind").and_then(|k| k.as_str()).and_then(PickupKind::from_name);
+                        let respawn_interval = properties.get("respawn").and_then(|r| r.as_integer()).map(|r| r as u64);
+
+                        if let Some(kind) = kind {
+                            level.pickups.push(LevelPickupSpawner::new(
+                                properties.get("x").unwrap().as_float().unwrap() as f32,
+                                properties.get("y").unwrap().as_float().unwrap() as f32,
+                                kind,
+                                respawn_interval.unwrap_or(PICKUP_RESPAWN_INTERVAL)
+                            ));
+                        }
+
+                    }
+                }
+            }
+
             // Load solids
             if let Some(&toml::Value::Array(ref solids)) = value.get("solids") {
-                for solid in solids {
+                for (index, solid) in solids.iter().enumerate() {
                     if let toml::Value::Array(ref points) = *solid {
                         let points: Vec<f32> = points.into_iter().map(|p| p.as_float().unwrap() as f32).collect();
                         let mut pairs = Vec::with_capacity(points.len() / 2);
                         for i in 0..points.len() / 2 {
                             pairs.push([points[i * 2], points[i * 2 + 1]]);
                         }
-                        level.solids.push(pairs);
+
+                        // Invalid outlines can neither be rendered nor navigated
+                        // around correctly
+                        match validate_solid(&pairs) {
+                            Ok(()) => level.solids.push(clean_solid(&pairs)),
+                            Err(err) => {
+                                warn!("Skipping invalid solid #{}: {:?}", index, err);
+                                level.invalid_solids.push((index, err));
+                            }
+                        }
                     }
                 }
             }
 
         }
 
+        // Generate navigation data once all walls and solids are in place
+        level.navigation = LevelNavigationGrid::from_level(&level, PLAYER_RADIUS);
         level
 
     }
@@ -150,5 +201,15 @@ impl Level {
         &self.wall_indicies[..]
     }
 
+    pub fn navigation_grid(&self) -> &LevelNavigationGrid {
+        &self.navigation
+    }
+
+    /// Solids which were skipped while loading, together with their index in
+    /// the level data.
+    pub fn invalid_solids(&self) -> &[(usize, SolidError)] {
+        &self.invalid_solids[..]
+    }
+
 }
 
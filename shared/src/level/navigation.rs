@@ -0,0 +1,362 @@
+// STD Dependencies -----------------------------------------------------------
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+
+
+// Internal Dependencies ------------------------------------------------------
+use ::level::{Level, solid_contains_point, solid_distance_from_point};
+
+
+// Statics --------------------------------------------------------------------
+pub const NAVIGATION_GRID_SPACING: f32 = 3.0;
+const NAVIGATION_MAX_SNAP_DISTANCE: isize = 4;
+const NAVIGATION_STRAIGHT_COST: u32 = 10;
+const NAVIGATION_DIAGONAL_COST: u32 = 14;
+
+
+// Traits ---------------------------------------------------------------------
+pub trait LevelNavigation {
+    fn is_walkable(&self, x: f32, y: f32) -> bool;
+    fn walkable_path(&self, ax: f32, ay: f32, bx: f32, by: f32) -> bool;
+    fn find_path(&self, ax: f32, ay: f32, bx: f32, by: f32) -> Option<Vec<[f32; 2]>>;
+}
+
+impl LevelNavigation for Level {
+
+    fn is_walkable(&self, x: f32, y: f32) -> bool {
+        self.navigation.cell_at(x, y).map_or(false, |index| self.navigation.cells[index])
+    }
+
+    fn walkable_path(&self, ax: f32, ay: f32, bx: f32, by: f32) -> bool {
+
+        let (dx, dy) = (bx - ax, by - ay);
+        let steps = ((dx * dx + dy * dy).sqrt() / (NAVIGATION_GRID_SPACING * 0.5)).ceil().max(1.0) as usize;
+
+        (0..steps + 1).all(|i| {
+            let f = i as f32 / steps as f32;
+            self.is_walkable(ax + dx * f, ay + dy * f)
+        })
+
+    }
+
+    fn find_path(&self, ax: f32, ay: f32, bx: f32, by: f32) -> Option<Vec<[f32; 2]>> {
+
+        let nav = &self.navigation;
+        let start = match nav.nearest_walkable(ax, ay) {
+            Some(index) => index,
+            None => return None
+        };
+
+        let goal = match nav.nearest_walkable(bx, by) {
+            Some(index) => index,
+            None => return None
+        };
+
+        nav.search(start, goal).map(|cells| {
+
+            let mut points: Vec<[f32; 2]> = cells.into_iter().map(|index| nav.position(index)).collect();
+
+            // Start and end exactly at the requested positions where possible
+            points[0] = [ax, ay];
+            let last = points.len() - 1;
+            if last > 0 {
+                points[last] = [bx, by];
+
+            } else {
+                points.push([bx, by]);
+            }
+
+            self.smooth_path(points)
+
+        })
+
+    }
+
+}
+
+
+// Navigation Grid ------------------------------------------------------------
+
+/// A clearance aware grid which marks every cell whose center can be occupied
+/// by a circle of the given radius without touching any walls or solids.
+#[derive(Debug)]
+pub struct LevelNavigationGrid {
+    width: usize,
+    height: usize,
+    origin: [f32; 2],
+    cells: Vec<bool>
+}
+
+impl LevelNavigationGrid {
+
+    pub fn new() -> LevelNavigationGrid {
+        LevelNavigationGrid {
+            width: 0,
+            height: 0,
+            origin: [0.0, 0.0],
+            cells: Vec::new()
+        }
+    }
+
+    pub fn from_level(level: &Level, radius: f32) -> LevelNavigationGrid {
+
+        let b = &level.bounds;
+        if b[0] > b[2] || b[1] > b[3] {
+            return LevelNavigationGrid::new();
+        }
+
+        let width = ((b[2] - b[0]) / NAVIGATION_GRID_SPACING).ceil() as usize;
+        let height = ((b[3] - b[1]) / NAVIGATION_GRID_SPACING).ceil() as usize;
+
+        let mut grid = LevelNavigationGrid {
+            width: width,
+            height: height,
+            origin: [b[0], b[1]],
+            cells: vec![false; width * height]
+        };
+
+        // Players collide with walls at one unit in addition to their radius,
+        // solids keep the same clearance
+        let clearance = radius + 1.0;
+        for index in 0..grid.cells.len() {
+            let p = grid.position(index);
+            let (x, y) = (p[0], p[1]);
+
+            let clear_of_walls = level.walls.iter().all(|wall| {
+                x < wall.aabb[0] - clearance || x > wall.aabb[2] + clearance
+                    || y < wall.aabb[1] - clearance || y > wall.aabb[3] + clearance
+                    || wall.distance_from_point(x, y) >= clearance
+            });
+
+            let clear_of_solids = level.solids.iter().all(|s| {
+                !solid_contains_point(s, x, y) && solid_distance_from_point(s, x, y) >= clearance
+            });
+
+            grid.cells[index] = clear_of_walls && clear_of_solids;
+        }
+
+        grid
+
+    }
+
+    pub fn walkable_cells(&self) -> usize {
+        self.cells.iter().filter(|c| **c).count()
+    }
+
+    pub fn cells(&self) -> &[bool] {
+        &self.cells
+    }
+
+    /// Returns all walkable cells which can be reached from `start`.
+    pub fn flood_fill(&self, start: usize) -> Vec<usize> {
+
+        let mut visited = vec![false; self.cells.len()];
+        let mut filled = Vec::new();
+        let mut queue = VecDeque::new();
+
+        if self.cells.get(start) == Some(&true) {
+            visited[start] = true;
+            queue.push_back(start);
+        }
+
+        while let Some(index) = queue.pop_front() {
+            filled.push(index);
+            for (next, _) in self.neighbours(index) {
+                if !visited[next] {
+                    visited[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        filled
+
+    }
+
+    fn cell_at(&self, x: f32, y: f32) -> Option<usize> {
+        let gx = ((x - self.origin[0]) / NAVIGATION_GRID_SPACING).floor() as isize;
+        let gy = ((y - self.origin[1]) / NAVIGATION_GRID_SPACING).floor() as isize;
+        self.index(gx, gy)
+    }
+
+    fn index(&self, gx: isize, gy: isize) -> Option<usize> {
+        if gx >= 0 && gy >= 0 && (gx as usize) < self.width && (gy as usize) < self.height {
+            Some(gy as usize * self.width + gx as usize)
+
+        } else {
+            None
+        }
+    }
+
+    pub fn position(&self, index: usize) -> [f32; 2] {
+        [
+            self.origin[0] + ((index % self.width) as f32 + 0.5) * NAVIGATION_GRID_SPACING,
+            self.origin[1] + ((index / self.width) as f32 + 0.5) * NAVIGATION_GRID_SPACING
+        ]
+    }
+
+    pub fn nearest_walkable(&self, x: f32, y: f32) -> Option<usize> {
+
+        let gx = ((x - self.origin[0]) / NAVIGATION_GRID_SPACING).floor() as isize;
+        let gy = ((y - self.origin[1]) / NAVIGATION_GRID_SPACING).floor() as isize;
+
+        let mut nearest = None;
+        let mut min_distance = ::std::f32::MAX;
+        for oy in -NAVIGATION_MAX_SNAP_DISTANCE..NAVIGATION_MAX_SNAP_DISTANCE + 1 {
+            for ox in -NAVIGATION_MAX_SNAP_DISTANCE..NAVIGATION_MAX_SNAP_DISTANCE + 1 {
+                if let Some(index) = self.index(gx + ox, gy + oy) {
+                    if self.cells[index] {
+                        let p = self.position(index);
+                        let (dx, dy) = (p[0] - x, p[1] - y);
+                        let distance = dx * dx + dy * dy;
+                        if distance < min_distance {
+                            nearest = Some(index);
+                            min_distance = distance;
+                        }
+                    }
+                }
+            }
+        }
+
+        nearest
+
+    }
+
+    fn search(&self, start: usize, goal: usize) -> Option<Vec<usize>> {
+
+        let mut costs = vec![u32::max_value(); self.cells.len()];
+        let mut parents = vec![usize::max_value(); self.cells.len()];
+        let mut open = BinaryHeap::new();
+
+        costs[start] = 0;
+        open.push(Node {
+            index: start,
+            estimate: self.heuristic(start, goal)
+        });
+
+        while let Some(node) = open.pop() {
+
+            if node.index == goal {
+                let mut path = vec![goal];
+                let mut index = goal;
+                while index != start {
+                    index = parents[index];
+                    path.push(index);
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for (next, cost) in self.neighbours(node.index) {
+                let cost = costs[node.index] + cost;
+                if cost < costs[next] {
+                    costs[next] = cost;
+                    parents[next] = node.index;
+                    open.push(Node {
+                        index: next,
+                        estimate: cost + self.heuristic(next, goal)
+                    });
+                }
+            }
+
+        }
+
+        None
+
+    }
+
+    fn neighbours(&self, index: usize) -> Vec<(usize, u32)> {
+
+        let (x, y) = ((index % self.width) as isize, (index / self.width) as isize);
+        let mut neighbours = Vec::with_capacity(8);
+        for oy in -1..2 {
+            for ox in -1..2 {
+
+                if ox == 0 && oy == 0 {
+                    continue;
+                }
+
+                let next = match self.index(x + ox, y + oy) {
+                    Some(index) if self.cells[index] => index,
+                    _ => continue
+                };
+
+                // Avoid cutting corners when moving diagonally
+                if ox != 0 && oy != 0 {
+                    if self.walkable(x + ox, y) && self.walkable(x, y + oy) {
+                        neighbours.push((next, NAVIGATION_DIAGONAL_COST));
+                    }
+
+                } else {
+                    neighbours.push((next, NAVIGATION_STRAIGHT_COST));
+                }
+
+            }
+        }
+
+        neighbours
+
+    }
+
+    fn walkable(&self, gx: isize, gy: isize) -> bool {
+        self.index(gx, gy).map_or(false, |index| self.cells[index])
+    }
+
+    fn heuristic(&self, from: usize, to: usize) -> u32 {
+        let dx = ((from % self.width) as isize - (to % self.width) as isize).abs() as u32;
+        let dy = ((from / self.width) as isize - (to / self.width) as isize).abs() as u32;
+        NAVIGATION_STRAIGHT_COST * (dx + dy) - (2 * NAVIGATION_STRAIGHT_COST - NAVIGATION_DIAGONAL_COST) * dx.min(dy)
+    }
+
+}
+
+
+// Internal Helpers -----------------------------------------------------------
+impl Level {
+
+    fn smooth_path(&self, points: Vec<[f32; 2]>) -> Vec<[f32; 2]> {
+
+        // Skip ahead as long as there is a direct walkable connection
+        let mut path = vec![points[0]];
+        let mut i = 0;
+        while i < points.len() - 1 {
+            let mut next = i + 1;
+            for j in (i + 2)..points.len() {
+                if self.walkable_path(points[i][0], points[i][1], points[j][0], points[j][1]) {
+                    next = j;
+
+                } else {
+                    break;
+                }
+            }
+            path.push(points[next]);
+            i = next;
+        }
+
+        path
+
+    }
+
+}
+
+#[derive(Eq, PartialEq)]
+struct Node {
+    index: usize,
+    estimate: u32
+}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Node) -> Ordering {
+        match other.estimate.cmp(&self.estimate) {
+            Ordering::Equal => self.index.cmp(&other.index),
+            ordering => ordering
+        }
+    }
+}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Node) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
@@ -0,0 +1,25 @@
+// Internal Dependencies ------------------------------------------------------
+use ::entity::PickupKind;
+
+
+// Pickup Spawner -------------------------------------------------------------
+#[derive(Debug, Clone)]
+pub struct LevelPickupSpawner {
+    pub x: f32,
+    pub y: f32,
+    pub kind: PickupKind,
+    pub respawn_interval: u64
+}
+
+impl LevelPickupSpawner {
+
+    pub fn new(x: f32, y: f32, kind: PickupKind, respawn_interval: u64) -> LevelPickupSpawner {
+        LevelPickupSpawner {
+            x: x,
+            y: y,
+            kind: kind,
+            respawn_interval: respawn_interval
+        }
+    }
+
+}
@@ -0,0 +1,262 @@
+// Statics --------------------------------------------------------------------
+const SOLID_EPSILON: f32 = 0.001;
+
+
+// Solid Validation -----------------------------------------------------------
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum SolidError {
+    TooFewPoints,
+    ZeroArea,
+    SelfIntersection(usize, usize)
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum SolidWinding {
+    Clockwise,
+    CounterClockwise
+}
+
+/// Removes duplicate and collinear points from a solid's outline.
+pub fn clean_solid(points: &[[f32; 2]]) -> Vec<[f32; 2]> {
+
+    let mut cleaned: Vec<[f32; 2]> = Vec::with_capacity(points.len());
+    for p in points {
+        let duplicate = match cleaned.last() {
+            Some(last) => same_point(last, p),
+            None => false
+        };
+        if !duplicate {
+            cleaned.push(*p);
+        }
+    }
+
+    while cleaned.len() > 1 && same_point(&cleaned[0], &cleaned[cleaned.len() - 1]) {
+        cleaned.pop();
+    }
+
+    // Drop points which lie on the line between their neighbours
+    let mut i = 0;
+    while cleaned.len() >= 3 && i < cleaned.len() {
+        let size = cleaned.len();
+        let (a, b, c) = (
+            cleaned[(i + size - 1) % size],
+            cleaned[i],
+            cleaned[(i + 1) % size]
+        );
+        if cross(&a, &b, &c).abs() <= SOLID_EPSILON {
+            cleaned.remove(i);
+            i = i.saturating_sub(1);
+
+        } else {
+            i += 1;
+        }
+    }
+
+    cleaned
+
+}
+
+/// Checks that a solid's outline describes a simple polygon.
+///
+/// Duplicate and collinear points are ignored, the returned edge indices
+/// refer to the cleaned outline.
+pub fn validate_solid(points: &[[f32; 2]]) -> Result<(), SolidError> {
+
+    let points = clean_solid(points);
+    if points.len() < 3 {
+        return Err(SolidError::TooFewPoints);
+    }
+
+    let size = points.len();
+    for i in 0..size {
+        for j in (i + 2)..size {
+
+            // Skip the edge pair sharing the start / end point
+            if i == 0 && j == size - 1 {
+                continue;
+            }
+
+            if segments_intersect(
+                &points[i], &points[(i + 1) % size],
+                &points[j], &points[(j + 1) % size]
+            ) {
+                return Err(SolidError::SelfIntersection(i, j));
+            }
+
+        }
+    }
+
+    if solid_area(&points).abs() <= SOLID_EPSILON {
+        return Err(SolidError::ZeroArea);
+    }
+
+    Ok(())
+
+}
+
+/// Returns the signed area of a solid, positive for counter clockwise
+/// outlines.
+pub fn solid_area(points: &[[f32; 2]]) -> f32 {
+    let size = points.len();
+    (0..size).fold(0.0, |area, i| {
+        let (a, b) = (&points[i], &points[(i + 1) % size]);
+        area + a[0] * b[1] - b[0] * a[1]
+
+    }) * 0.5
+}
+
+/// Even-odd test whether a point lies within a solid's outline.
+pub fn solid_contains_point(points: &[[f32; 2]], x: f32, y: f32) -> bool {
+    let mut inside = false;
+    let mut j = points.len().saturating_sub(1);
+    for i in 0..points.len() {
+        let (a, b) = (points[i], points[j]);
+        if (a[1] > y) != (b[1] > y) && x < (b[0] - a[0]) * (y - a[1]) / (b[1] - a[1]) + a[0] {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Distance of a point from the closest edge of a solid's outline.
+pub fn solid_distance_from_point(points: &[[f32; 2]], x: f32, y: f32) -> f32 {
+    let size = points.len();
+    (0..size).fold(f32::MAX, |distance, i| {
+        let (a, b) = (&points[i], &points[(i + 1) % size]);
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let l = dx * dx + dy * dy;
+        let t = if l > 0.0 {
+            (((x - a[0]) * dx + (y - a[1]) * dy) / l).clamp(0.0, 1.0)
+
+        } else {
+            0.0
+        };
+        let (ex, ey) = (x - a[0] - t * dx, y - a[1] - t * dy);
+        distance.min((ex * ex + ey * ey).sqrt())
+    })
+}
+
+pub fn solid_winding(points: &[[f32; 2]]) -> SolidWinding {
+    if solid_area(points) < 0.0 {
+        SolidWinding::Clockwise
+
+    } else {
+        SolidWinding::CounterClockwise
+    }
+}
+
+
+// Solid Triangulation --------------------------------------------------------
+
+/// Triangulates a solid via ear clipping and returns a flat triangle list
+/// of `[ax, ay, bx, by, cx, cy, ...]`.
+///
+/// Outlines with either winding order are supported, invalid outlines still
+/// produce triangles but are not guaranteed to cover the solid correctly.
+pub fn triangulate_solid(points: &[[f32; 2]]) -> Vec<f32> {
+
+    let mut points = clean_solid(points);
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    // Ear clipping requires the points to be in counter clockwise order
+    if solid_winding(&points) == SolidWinding::Clockwise {
+        points.reverse();
+    }
+
+    let mut triangles = Vec::with_capacity((points.len() - 2) * 6);
+    let mut indices: Vec<usize> = (0..points.len()).collect();
+    while indices.len() > 3 {
+
+        let size = indices.len();
+        let ear = (0..size).find(|&i| is_ear(&points, &indices, i));
+
+        // Invalid outlines may run out of ears, in which case we fall back to
+        // the first convex corner in order to always terminate
+        let i = match ear {
+            Some(i) => i,
+            None => (0..size).find(|&i| {
+                let (a, b, c) = corner(&points, &indices, i);
+                cross(a, b, c) > 0.0
+
+            }).unwrap_or(0)
+        };
+
+        {
+            let (a, b, c) = corner(&points, &indices, i);
+            triangles.extend_from_slice(&[a[0], a[1], b[0], b[1], c[0], c[1]]);
+        }
+
+        indices.remove(i);
+
+    }
+
+    let (a, b, c) = corner(&points, &indices, 1);
+    triangles.extend_from_slice(&[a[0], a[1], b[0], b[1], c[0], c[1]]);
+    triangles
+
+}
+
+
+// Helpers --------------------------------------------------------------------
+fn corner<'a>(points: &'a [[f32; 2]], indices: &[usize], i: usize) -> (&'a [f32; 2], &'a [f32; 2], &'a [f32; 2]) {
+    let size = indices.len();
+    (
+        &points[indices[(i + size - 1) % size]],
+        &points[indices[i]],
+        &points[indices[(i + 1) % size]]
+    )
+}
+
+fn is_ear(points: &[[f32; 2]], indices: &[usize], i: usize) -> bool {
+
+    let (a, b, c) = corner(points, indices, i);
+    if cross(a, b, c) <= 0.0 {
+        return false;
+    }
+
+    // No other remaining point may lie within the ear
+    !indices.iter().map(|&index| &points[index]).any(|p| {
+        !same_point(p, a) && !same_point(p, b) && !same_point(p, c) && in_triangle(a, b, c, p)
+    })
+
+}
+
+fn cross(a: &[f32; 2], b: &[f32; 2], c: &[f32; 2]) -> f32 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+fn same_point(a: &[f32; 2], b: &[f32; 2]) -> bool {
+    (a[0] - b[0]).abs() <= SOLID_EPSILON && (a[1] - b[1]).abs() <= SOLID_EPSILON
+}
+
+fn in_triangle(a: &[f32; 2], b: &[f32; 2], c: &[f32; 2], p: &[f32; 2]) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
+
+fn on_segment(a: &[f32; 2], b: &[f32; 2], p: &[f32; 2]) -> bool {
+    p[0] >= a[0].min(b[0]) - SOLID_EPSILON && p[0] <= a[0].max(b[0]) + SOLID_EPSILON
+        && p[1] >= a[1].min(b[1]) - SOLID_EPSILON && p[1] <= a[1].max(b[1]) + SOLID_EPSILON
+}
+
+fn segments_intersect(a: &[f32; 2], b: &[f32; 2], c: &[f32; 2], d: &[f32; 2]) -> bool {
+
+    let (d1, d2) = (cross(c, d, a), cross(c, d, b));
+    let (d3, d4) = (cross(a, b, c), cross(a, b, d));
+
+    if ((d1 > SOLID_EPSILON && d2 < -SOLID_EPSILON) || (d1 < -SOLID_EPSILON && d2 > SOLID_EPSILON))
+        && ((d3 > SOLID_EPSILON && d4 < -SOLID_EPSILON) || (d3 < -SOLID_EPSILON && d4 > SOLID_EPSILON)) {
+        true
+
+    // Touching or overlapping edges
+    } else {
+        (d1.abs() <= SOLID_EPSILON && on_segment(c, d, a))
+            || (d2.abs() <= SOLID_EPSILON && on_segment(c, d, b))
+            || (d3.abs() <= SOLID_EPSILON && on_segment(a, b, c))
+            || (d4.abs() <= SOLID_EPSILON && on_segment(a, b, d))
+    }
+
+}
+
@@ -33,6 +33,12 @@ impl LevelWall {
     }
 
     pub fn distance_from_point(&self, x: f32, y: f32) -> f32 {
+        let (ox, oy) = self.closest_point(x, y);
+        let (dx, dy) = (x - ox, y - oy);
+        (dx * dx + dy * dy).sqrt()
+    }
+
+    pub fn closest_point(&self, x: f32, y: f32) -> (f32, f32) {
 
         let (vx, vy) = (self.points[0], self.points[1]);
         let (wx, wy) = (self.points[2], self.points[3]);
@@ -40,9 +46,7 @@ impl LevelWall {
         let t = ((x - vx) * (wx - vx) + (y - vy) * (wy - vy)) / self.length;
         let t = t.max(0.0).min(1.0);
 
-        let (ox, oy) = (vx + t * (wx - vx), vy + t * (wy - vy));
-        let (dx, dy) = (x - ox, y - oy);
-        (dx * dx + dy * dy).sqrt()
+        (vx + t * (wx - vx), vy + t * (wy - vy))
 
     }
 
@@ -10,28 +10,73 @@
 
 // Crates ---------------------------------------------------------------------
 extern crate rustc_serialize;
+#[macro_use]
+extern crate log;
 extern crate clock_ticks;
 extern crate bincode;
 extern crate toml;
 extern crate rand;
+extern crate clap;
 
 extern crate hexahydrate;
 extern crate netsync;
 extern crate cobalt;
 
 
+// Macros ---------------------------------------------------------------------
+#[macro_export]
+macro_rules! entity_registry {
+    (
+        $name:ident,
+        $entity:ty,
+        [ $($kind:ty),* ]
+    ) => (
+        #[derive(Debug)]
+        pub struct $name;
+
+        impl $name {
+            pub fn kinds() -> Vec<u8> {
+                vec![$(<$kind as $crate::entity::EntityKind>::kind_id()),*]
+            }
+        }
+
+        impl ::hexahydrate::EntityRegistry<$entity, ::cobalt::ConnectionID> for $name {
+            fn entity_from_bytes(&self, kind: u8, bytes: &[u8]) -> Option<Box<$entity>> {
+                $(
+                    if kind == <$kind as $crate::entity::EntityKind>::kind_id() {
+                        return <$kind as ::hexahydrate::Entity<::cobalt::ConnectionID>>::from_bytes(
+                            bytes
+
+                        ).map(|e| Box::new(e) as Box<$entity>);
+                    }
+                )*
+                None
+            }
+        }
+    );
+}
+
+
 // Statics --------------------------------------------------------------------
 pub static UPDATES_PER_SECOND: u64 = 30;
 
 
 // Modules --------------------------------------------------------------------
 pub mod util;
+pub mod clock;
 pub mod level;
 pub mod color;
 mod timer;
 pub mod action;
+pub mod weapon;
 pub mod entity;
 pub mod collision;
+pub mod replay;
+pub mod stats;
+pub mod logging;
+pub mod metrics;
+pub mod status;
+pub mod cli;
 
 
 // Re-Exports ------------------------------------------------------------------
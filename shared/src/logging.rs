@@ -0,0 +1,266 @@
+// STD Dependencies -----------------------------------------------------------
+use std::env;
+use std::fmt;
+use std::io;
+use std::io::{Write, LineWriter};
+use std::fs::File;
+use std::cell::Cell;
+use std::sync::Mutex;
+
+
+// External Dependencies ------------------------------------------------------
+use log;
+use log::{LogLevel, LogLevelFilter, LogMetadata, LogRecord, MaxLogLevelFilter};
+use clock_ticks;
+use cobalt::ConnectionID;
+use rustc_serialize::json;
+
+
+// Statics --------------------------------------------------------------------
+pub const LOG_FILTER_ENV: &'static str = "SHOOTER_LOG";
+pub const DEFAULT_LOG_FILTER: &'static str = "info";
+
+thread_local! {
+    static LOG_TICK: Cell<Option<u16>> = Cell::new(None);
+    static LOG_CONNECTION: Cell<Option<u32>> = Cell::new(None);
+}
+
+
+// Logging Errors -------------------------------------------------------------
+#[derive(Debug)]
+pub enum LogError {
+    Io(io::Error),
+    InvalidFilter(String),
+    AlreadyInitialized
+}
+
+impl fmt::Display for LogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LogError::Io(ref err) => write!(f, "Failed to open log file: {}", err),
+            LogError::InvalidFilter(ref filter) => write!(f, "Invalid log filter \"{}\"", filter),
+            LogError::AlreadyInitialized => write!(f, "Logging was already initialized")
+        }
+    }
+}
+
+impl From<io::Error> for LogError {
+    fn from(err: io::Error) -> LogError {
+        LogError::Io(err)
+    }
+}
+
+
+// Log Filter -----------------------------------------------------------------
+
+/// Per module log levels, parsed from a comma separated list of either plain
+/// levels or `module=level` pairs, e.g. `info,server::bot=debug,shared=warn`.
+///
+/// The most specific module wins, plain levels apply to everything else.
+#[derive(Debug, Clone)]
+pub struct LogFilter {
+    default: LogLevelFilter,
+    modules: Vec<(String, LogLevelFilter)>
+}
+
+impl LogFilter {
+
+    pub fn parse(spec: &str) -> Result<LogFilter, LogError> {
+
+        let mut filter = LogFilter {
+            default: LogLevelFilter::Info,
+            modules: Vec::new()
+        };
+
+        for part in spec.split(',').map(|part| part.trim()).filter(|part| !part.is_empty()) {
+            let mut pair = part.splitn(2, '=');
+            let (module, level) = match (pair.next(), pair.next()) {
+                (Some(module), Some(level)) => (Some(module.trim()), level.trim()),
+                (Some(level), None) => (None, level),
+                _ => return Err(LogError::InvalidFilter(part.to_string()))
+            };
+
+            let level = try!(level.parse::<LogLevelFilter>().map_err(|_| {
+                LogError::InvalidFilter(part.to_string())
+            }));
+
+            match module {
+                Some(module) if !module.is_empty() => filter.modules.push((module.to_string(), level)),
+                Some(_) => return Err(LogError::InvalidFilter(part.to_string())),
+                None => filter.default = level
+            }
+        }
+
+        Ok(filter)
+
+    }
+
+    pub fn level(&self, target: &str) -> LogLevelFilter {
+        self.modules.iter().filter(|&&(ref module, _)| {
+            target == module || target.starts_with(module.as_str()) && target[module.len()..].starts_with("::")
+
+        }).max_by_key(|&&(ref module, _)| module.len()).map_or(self.default, |&(_, level)| level)
+    }
+
+    pub fn max_level(&self) -> LogLevelFilter {
+        self.modules.iter().fold(self.default, |max, &(_, level)| {
+            if level > max { level } else { max }
+        })
+    }
+
+}
+
+
+// Log Context ----------------------------------------------------------------
+
+/// Sets the tick included in all following records of the current thread.
+pub fn set_tick(tick: u16) {
+    LOG_TICK.with(|t| t.set(Some(tick)));
+}
+
+/// Includes the connection in all records of the current thread until the
+/// returned guard is dropped.
+pub fn connection(id: ConnectionID) -> ConnectionGuard {
+    let previous = LOG_CONNECTION.with(|c| {
+        let previous = c.get();
+        c.set(Some(id.0));
+        previous
+    });
+    ConnectionGuard {
+        previous: previous
+    }
+}
+
+#[derive(Debug)]
+pub struct ConnectionGuard {
+    previous: Option<u32>
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        let previous = self.previous;
+        LOG_CONNECTION.with(|c| c.set(previous));
+    }
+}
+
+
+// Log Entries ----------------------------------------------------------------
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub time: u64,
+    pub level: LogLevel,
+    pub target: String,
+    pub tick: Option<u16>,
+    pub connection: Option<u32>,
+    pub message: String
+}
+
+impl LogEntry {
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{:<5} [{}]", self.level, self.target);
+        if let Some(tick) = self.tick {
+            text.push_str(&format!(" tick={}", tick));
+        }
+        if let Some(connection) = self.connection {
+            text.push_str(&format!(" conn={}", connection));
+        }
+        text.push(' ');
+        text.push_str(&self.message);
+        text
+    }
+
+    pub fn to_json(&self) -> String {
+        json::encode(&JsonLogEntry {
+            time: self.time,
+            level: self.level.to_string(),
+            target: &self.target,
+            tick: self.tick,
+            connection: self.connection,
+            message: &self.message
+
+        }).expect("Failed to encode log entry.")
+    }
+
+}
+
+#[derive(RustcEncodable)]
+struct JsonLogEntry<'a> {
+    time: u64,
+    level: String,
+    target: &'a str,
+    tick: Option<u16>,
+    connection: Option<u32>,
+    message: &'a str
+}
+
+
+// Logger ---------------------------------------------------------------------
+struct Logger {
+    filter: LogFilter,
+    file: Option<Mutex<LineWriter<File>>>
+}
+
+impl log::Log for Logger {
+
+    fn enabled(&self, metadata: &LogMetadata) -> bool {
+        metadata.level() <= self.filter.level(metadata.target())
+    }
+
+    fn log(&self, record: &LogRecord) {
+
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let entry = LogEntry {
+            time: clock_ticks::precise_time_ms(),
+            level: record.level(),
+            target: record.target().to_string(),
+            tick: LOG_TICK.with(|t| t.get()),
+            connection: LOG_CONNECTION.with(|c| c.get()),
+            message: format!("{}", record.args())
+        };
+
+        println!("{}", entry.to_text());
+
+        if let Some(ref file) = self.file {
+            if let Ok(mut file) = file.lock() {
+                writeln!(file, "{}", entry.to_json()).ok();
+            }
+        }
+
+    }
+
+}
+
+/// Installs the global logger.
+///
+/// The filter is taken from `filter` or the `SHOOTER_LOG` environment
+/// variable and defaults to `info`. Records are written to stdout and, if
+/// `path` is given, as JSON lines to the file.
+pub fn init(filter: Option<&str>, path: Option<&str>) -> Result<(), LogError> {
+
+    let filter = match filter {
+        Some(filter) => try!(LogFilter::parse(filter)),
+        None => try!(LogFilter::parse(
+            env::var(LOG_FILTER_ENV).unwrap_or_else(|_| DEFAULT_LOG_FILTER.to_string()).as_str()
+        ))
+    };
+
+    let file = match path {
+        Some(path) => Some(Mutex::new(LineWriter::new(try!(File::create(path))))),
+        None => None
+    };
+
+    log::set_logger(|max_level: MaxLogLevelFilter| {
+        max_level.set(filter.max_level());
+        Box::new(Logger {
+            filter: filter,
+            file: file
+        })
+
+    }).map_err(|_| LogError::AlreadyInitialized)
+
+}
+
@@ -0,0 +1,203 @@
+// STD Dependencies -----------------------------------------------------------
+use std::fmt::Write;
+
+
+// Statics --------------------------------------------------------------------
+pub const METRICS_RATE_WINDOW: u64 = 1000;
+
+/// Upper bounds in seconds, the last ones cover ticks which exceed the tick
+/// duration at 30 updates per second
+pub const TICK_DURATION_BUCKETS: [f64; 10] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.02, 0.0333, 0.05, 0.1, 0.25
+];
+
+
+// Histogram ------------------------------------------------------------------
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    bounds: Vec<f64>,
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64
+}
+
+impl Histogram {
+
+    pub fn new(bounds: &[f64]) -> Histogram {
+        Histogram {
+            bounds: bounds.to_vec(),
+            counts: vec![0; bounds.len()],
+            sum: 0.0,
+            count: 0
+        }
+    }
+
+    pub fn observe(&mut self, value: f64) {
+        if let Some(index) = self.bounds.iter().position(|bound| value <= *bound) {
+            self.counts[index] += 1;
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Upper bounds with the cumulative number of observations less than or
+    /// equal to them.
+    pub fn buckets(&self) -> Vec<(f64, u64)> {
+        let mut total = 0;
+        self.bounds.iter().zip(self.counts.iter()).map(|(bound, count)| {
+            total += *count;
+            (*bound, total)
+
+        }).collect()
+    }
+
+}
+
+
+// Server Metrics -------------------------------------------------------------
+#[derive(Debug, Clone)]
+pub struct ServerMetrics {
+    pub tick_duration: Histogram,
+    pub players: usize,
+    pub bots: usize,
+    pub entities: usize,
+
+    /// Totals since the server was started
+    pub actions: u64,
+    pub hits: u64,
+    pub kills: u64,
+    pub packets_sent: u64,
+    pub packets_received: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+
+    /// Per second rates during the last completed window
+    pub actions_per_second: u64,
+    pub bytes_sent_per_second: u64,
+    pub bytes_received_per_second: u64,
+    window: [u64; 3],
+    window_start: u64
+}
+
+impl ServerMetrics {
+
+    pub fn new(t: u64) -> ServerMetrics {
+        ServerMetrics {
+            tick_duration: Histogram::new(&TICK_DURATION_BUCKETS),
+            players: 0,
+            bots: 0,
+            entities: 0,
+            actions: 0,
+            hits: 0,
+            kills: 0,
+            packets_sent: 0,
+            packets_received: 0,
+            bytes_sent: 0,
+            bytes_received: 0,
+            actions_per_second: 0,
+            bytes_sent_per_second: 0,
+            bytes_received_per_second: 0,
+            window: [0; 3],
+            window_start: t
+        }
+    }
+
+    pub fn record_actions(&mut self, count: usize) {
+        self.actions += count as u64;
+        self.window[0] += count as u64;
+    }
+
+    pub fn record_sent(&mut self, bytes: usize) {
+        self.packets_sent += 1;
+        self.bytes_sent += bytes as u64;
+        self.window[1] += bytes as u64;
+    }
+
+    pub fn record_received(&mut self, bytes: usize) {
+        self.packets_received += 1;
+        self.bytes_received += bytes as u64;
+        self.window[2] += bytes as u64;
+    }
+
+    /// Completes the current window once it has lasted long enough, returns
+    /// `true` when the rates have been updated.
+    pub fn update(&mut self, t: u64) -> bool {
+        let elapsed = t.saturating_sub(self.window_start);
+        if elapsed >= METRICS_RATE_WINDOW {
+            self.actions_per_second = self.window[0] * 1000 / elapsed;
+            self.bytes_sent_per_second = self.window[1] * 1000 / elapsed;
+            self.bytes_received_per_second = self.window[2] * 1000 / elapsed;
+            self.window = [0; 3];
+            self.window_start = t;
+            true
+
+        } else {
+            false
+        }
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub fn to_prometheus(&self) -> String {
+
+        let mut out = String::new();
+
+        metric_header(&mut out, "shooter_tick_duration_seconds", "histogram", "Time spent simulating a server tick.");
+        for (bound, count) in self.tick_duration.buckets() {
+            writeln!(out, "shooter_tick_duration_seconds_bucket{{le=\"{}\"}} {}", bound, count).ok();
+        }
+        writeln!(out, "shooter_tick_duration_seconds_bucket{{le=\"+Inf\"}} {}", self.tick_duration.count()).ok();
+        writeln!(out, "shooter_tick_duration_seconds_sum {}", self.tick_duration.sum()).ok();
+        writeln!(out, "shooter_tick_duration_seconds_count {}", self.tick_duration.count()).ok();
+
+        metric_header(&mut out, "shooter_players", "gauge", "Players currently in the game.");
+        writeln!(out, "shooter_players{{kind=\"human\"}} {}", self.players).ok();
+        writeln!(out, "shooter_players{{kind=\"bot\"}} {}", self.bots).ok();
+
+        metric_header(&mut out, "shooter_entities", "gauge", "Entities currently synchronized by the server.");
+        writeln!(out, "shooter_entities {}", self.entities).ok();
+
+        metric_header(&mut out, "shooter_actions_total", "counter", "Actions received from players and bots.");
+        writeln!(out, "shooter_actions_total {}", self.actions).ok();
+
+        metric_header(&mut out, "shooter_actions_per_second", "gauge", "Actions received during the last second.");
+        writeln!(out, "shooter_actions_per_second {}", self.actions_per_second).ok();
+
+        metric_header(&mut out, "shooter_network_packets_total", "counter", "Packets sent to and received from clients.");
+        writeln!(out, "shooter_network_packets_total{{direction=\"sent\"}} {}", self.packets_sent).ok();
+        writeln!(out, "shooter_network_packets_total{{direction=\"received\"}} {}", self.packets_received).ok();
+
+        metric_header(&mut out, "shooter_network_bytes_total", "counter", "Bytes sent to and received from clients.");
+        writeln!(out, "shooter_network_bytes_total{{direction=\"sent\"}} {}", self.bytes_sent).ok();
+        writeln!(out, "shooter_network_bytes_total{{direction=\"received\"}} {}", self.bytes_received).ok();
+
+        metric_header(&mut out, "shooter_bandwidth_bytes_per_second", "gauge", "Bytes sent and received during the last second.");
+        writeln!(out, "shooter_bandwidth_bytes_per_second{{direction=\"sent\"}} {}", self.bytes_sent_per_second).ok();
+        writeln!(out, "shooter_bandwidth_bytes_per_second{{direction=\"received\"}} {}", self.bytes_received_per_second).ok();
+
+        metric_header(&mut out, "shooter_hits_total", "counter", "Players hit by weapons.");
+        writeln!(out, "shooter_hits_total {}", self.hits).ok();
+
+        metric_header(&mut out, "shooter_kills_total", "counter", "Players killed by weapons.");
+        writeln!(out, "shooter_kills_total {}", self.kills).ok();
+
+        out
+
+    }
+
+}
+
+
+// Helpers --------------------------------------------------------------------
+fn metric_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    writeln!(out, "# HELP {} {}", name, help).ok();
+    writeln!(out, "# TYPE {} {}", name, kind).ok();
+}
+
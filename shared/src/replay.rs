@@ -0,0 +1,339 @@
+// STD Dependencies -----------------------------------------------------------
+use std::fmt;
+use std::io;
+use std::io::{Read, Write, Seek, SeekFrom, BufReader, BufWriter};
+use std::fs::File;
+use std::path::Path;
+
+
+// External Dependencies ------------------------------------------------------
+use cobalt::ConnectionID;
+use rustc_serialize::{Encodable, Decodable};
+use bincode::SizeLimit;
+use bincode::rustc_serialize::{encode, decode};
+
+
+// Internal Dependencies ------------------------------------------------------
+use ::color::ColorName;
+use ::action::{Action, ActionVisibility};
+use ::entity::PlayerData;
+
+
+// Statics --------------------------------------------------------------------
+pub const REPLAY_VERSION: u16 = 2;
+pub const REPLAY_MAX_CHUNK_SIZE: u32 = 16 * 1024 * 1024;
+const REPLAY_MAGIC: &'static [u8; 4] = b"TDSR";
+const REPLAY_FLUSH_INTERVAL: u32 = 64;
+
+
+// Replay Errors --------------------------------------------------------------
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    InvalidMagic,
+    UnsupportedVersion(u16),
+    ChunkTooLarge(u32),
+    Encoding(String),
+    Decoding(String)
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ReplayError::Io(ref err) => write!(f, "I/O error: {}", err),
+            ReplayError::InvalidMagic => write!(f, "Not a replay file"),
+            ReplayError::UnsupportedVersion(version) => write!(
+                f, "Unsupported replay version {} (expected {})", version, REPLAY_VERSION
+            ),
+            ReplayError::ChunkTooLarge(size) => write!(f, "Replay chunk of {} bytes is too large", size),
+            ReplayError::Encoding(ref err) => write!(f, "Failed to encode replay data: {}", err),
+            ReplayError::Decoding(ref err) => write!(f, "Failed to decode replay data: {}", err)
+        }
+    }
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(err: io::Error) -> ReplayError {
+        ReplayError::Io(err)
+    }
+}
+
+
+// Replay Data ----------------------------------------------------------------
+#[derive(Debug, Clone, RustcEncodable, RustcDecodable)]
+pub struct ReplayHeader {
+    pub updates_per_second: u64,
+
+    /// TOML source of the level the match was played on
+    pub level: String
+}
+
+#[derive(Debug, Clone, Default, RustcEncodable, RustcDecodable)]
+pub struct ReplayFrame {
+    pub tick: u32,
+    pub time: u64,
+    pub players: Vec<ReplayPlayer>,
+    pub events: Vec<ReplayEvent>
+}
+
+#[derive(Debug, Clone, RustcEncodable, RustcDecodable)]
+pub struct ReplayPlayer {
+    pub id: u32,
+    pub color: u8,
+    pub data: PlayerData
+}
+
+#[derive(Debug, Clone, RustcEncodable, RustcDecodable)]
+pub enum ReplayEvent {
+    Join(u32, u8),
+    Leave(u32),
+    Action(ReplayVisibility, Action)
+}
+
+/// Serializable version of `ActionVisibility`.
+#[derive(Debug, Clone, RustcEncodable, RustcDecodable)]
+pub enum ReplayVisibility {
+    Any,
+    Connection(u32),
+    Entity(PlayerData, Option<u32>),
+    WithinRange([f32; 4], f32)
+}
+
+impl<'a> From<&'a ActionVisibility> for ReplayVisibility {
+    fn from(visibility: &'a ActionVisibility) -> ReplayVisibility {
+        match *visibility {
+            ActionVisibility::Any => ReplayVisibility::Any,
+            ActionVisibility::Connection(id) => ReplayVisibility::Connection(id.0),
+            ActionVisibility::Entity(ref data, id) => {
+                ReplayVisibility::Entity(data.clone(), id.map(|id| id.0))
+            },
+            ActionVisibility::WithinRange { aabb, r } => ReplayVisibility::WithinRange(aabb, r)
+        }
+    }
+}
+
+
+// Replay Recording -----------------------------------------------------------
+
+/// Streams a match to a writer, one frame per server tick.
+///
+/// Only the frame of the current tick is kept in memory, everything else is
+/// written out as soon as the tick ends.
+pub struct ReplayRecorder<W: Write> {
+    writer: W,
+    frame: ReplayFrame
+}
+
+impl ReplayRecorder<BufWriter<File>> {
+
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        level: &str,
+        updates_per_second: u64
+
+    ) -> Result<ReplayRecorder<BufWriter<File>>, ReplayError> {
+        let file = try!(File::create(path));
+        ReplayRecorder::new(BufWriter::new(file), level, updates_per_second)
+    }
+
+}
+
+impl<W: Write> ReplayRecorder<W> {
+
+    pub fn new(
+        mut writer: W,
+        level: &str,
+        updates_per_second: u64
+
+    ) -> Result<ReplayRecorder<W>, ReplayError> {
+
+        try!(writer.write_all(REPLAY_MAGIC));
+        try!(writer.write_all(&[(REPLAY_VERSION & 0xff) as u8, (REPLAY_VERSION >> 8) as u8]));
+        try!(write_chunk(&mut writer, &ReplayHeader {
+            updates_per_second: updates_per_second,
+            level: level.to_string()
+        }));
+
+        Ok(ReplayRecorder {
+            writer: writer,
+            frame: ReplayFrame::default()
+        })
+
+    }
+
+    pub fn join(&mut self, id: ConnectionID, color: ColorName) {
+        self.frame.events.push(ReplayEvent::Join(id.0, color.to_u8()));
+    }
+
+    pub fn leave(&mut self, id: ConnectionID) {
+        self.frame.events.push(ReplayEvent::Leave(id.0));
+    }
+
+    pub fn action(&mut self, visibility: &ActionVisibility, action: &Action) {
+        self.frame.events.push(ReplayEvent::Action(visibility.into(), action.clone()));
+    }
+
+    pub fn player(&mut self, id: ConnectionID, color: ColorName, data: PlayerData) {
+        self.frame.players.push(ReplayPlayer {
+            id: id.0,
+            color: color.to_u8(),
+            data: data
+        });
+    }
+
+    /// Writes out the current frame and starts the next one.
+    pub fn end_frame(&mut self, time: u64) -> Result<(), ReplayError> {
+        self.frame.time = time;
+        try!(write_chunk(&mut self.writer, &self.frame));
+
+        // Keep the file usable in case the server gets killed
+        if self.frame.tick % REPLAY_FLUSH_INTERVAL == 0 {
+            try!(self.writer.flush());
+        }
+
+        self.frame.tick = self.frame.tick.wrapping_add(1);
+        self.frame.players.clear();
+        self.frame.events.clear();
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<W, ReplayError> {
+        try!(self.writer.flush());
+        Ok(self.writer)
+    }
+
+}
+
+
+// Replay Reading -------------------------------------------------------------
+
+/// Reads a recorded match, frames are decoded one at a time while iterating.
+pub struct ReplayReader<R: Read> {
+    reader: R,
+    header: ReplayHeader,
+    offset: u64
+}
+
+impl ReplayReader<BufReader<File>> {
+
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<ReplayReader<BufReader<File>>, ReplayError> {
+        let file = try!(File::open(path));
+        ReplayReader::new(BufReader::new(file))
+    }
+
+}
+
+impl<R: Read> ReplayReader<R> {
+
+    pub fn new(mut reader: R) -> Result<ReplayReader<R>, ReplayError> {
+
+        let mut magic = [0u8; 4];
+        try!(reader.read_exact(&mut magic));
+        if &magic != REPLAY_MAGIC {
+            return Err(ReplayError::InvalidMagic);
+        }
+
+        let mut version = [0u8; 2];
+        try!(reader.read_exact(&mut version));
+        let version = version[0] as u16 | (version[1] as u16) << 8;
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+
+        match try!(read_chunk::<R, ReplayHeader>(&mut reader)) {
+            Some((header, size)) => Ok(ReplayReader {
+                reader: reader,
+                header: header,
+                offset: 6 + size
+            }),
+            None => Err(ReplayError::Io(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Missing replay header"
+            )))
+        }
+
+    }
+
+    pub fn header(&self) -> &ReplayHeader {
+        &self.header
+    }
+
+    /// Byte offset of the next frame within the replay.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+}
+
+impl<R: Read + Seek> ReplayReader<R> {
+
+    /// Continues reading at a frame offset previously returned by `offset()`.
+    pub fn seek(&mut self, offset: u64) -> Result<(), ReplayError> {
+        try!(self.reader.seek(SeekFrom::Start(offset)));
+        self.offset = offset;
+        Ok(())
+    }
+
+}
+
+impl<R: Read> Iterator for ReplayReader<R> {
+
+    type Item = Result<ReplayFrame, ReplayError>;
+
+    fn next(&mut self) -> Option<Result<ReplayFrame, ReplayError>> {
+        match read_chunk(&mut self.reader) {
+            Ok(Some((frame, size))) => {
+                self.offset += size;
+                Some(Ok(frame))
+            },
+            Ok(None) => None,
+            Err(err) => Some(Err(err))
+        }
+    }
+
+}
+
+
+// Helpers --------------------------------------------------------------------
+fn write_chunk<W: Write, T: Encodable>(writer: &mut W, value: &T) -> Result<(), ReplayError> {
+
+    let bytes = try!(encode(value, SizeLimit::Bounded(REPLAY_MAX_CHUNK_SIZE as u64)).map_err(|err| {
+        ReplayError::Encoding(format!("{:?}", err))
+    }));
+
+    let size = bytes.len() as u32;
+    try!(writer.write_all(&[
+        (size & 0xff) as u8,
+        (size >> 8 & 0xff) as u8,
+        (size >> 16 & 0xff) as u8,
+        (size >> 24) as u8
+    ]));
+    try!(writer.write_all(&bytes));
+    Ok(())
+
+}
+
+/// Returns the decoded chunk together with its size in bytes.
+fn read_chunk<R: Read, T: Decodable>(reader: &mut R) -> Result<Option<(T, u64)>, ReplayError> {
+
+    // A clean end of the stream is only allowed in between chunks
+    let mut size = [0u8; 4];
+    let read = try!(reader.read(&mut size));
+    if read == 0 {
+        return Ok(None);
+    }
+    try!(reader.read_exact(&mut size[read..]));
+
+    let size = size[0] as u32 | (size[1] as u32) << 8 | (size[2] as u32) << 16 | (size[3] as u32) << 24;
+    if size > REPLAY_MAX_CHUNK_SIZE {
+        return Err(ReplayError::ChunkTooLarge(size));
+    }
+
+    let mut bytes = vec![0u8; size as usize];
+    try!(reader.read_exact(&mut bytes));
+    decode::<T>(&bytes).map(|value| Some((value, 4 + size as u64))).map_err(|err| {
+        ReplayError::Decoding(format!("{:?}", err))
+    })
+
+}
+
@@ -0,0 +1,251 @@
+// STD Dependencies -----------------------------------------------------------
+use std::fmt;
+use std::collections::VecDeque;
+
+
+// Statics --------------------------------------------------------------------
+pub const NETWORK_STATS_WINDOW: u64 = 1000;
+pub const NETWORK_STATS_RTT_HISTORY: usize = 60;
+
+
+// Traffic Kinds --------------------------------------------------------------
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TrafficKind {
+    /// Entity creation, destruction and their confirmations
+    Create = 0,
+
+    /// Entity state updates and their confirmations
+    Update = 1,
+
+    /// Actions including clock synchronization
+    Action = 2,
+
+    /// Player inputs sent as client side entity updates
+    Input = 3
+}
+
+impl TrafficKind {
+
+    pub fn all() -> [TrafficKind; 4] {
+        [TrafficKind::Create, TrafficKind::Update, TrafficKind::Action, TrafficKind::Input]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            TrafficKind::Create => "creates",
+            TrafficKind::Update => "updates",
+            TrafficKind::Action => "actions",
+            TrafficKind::Input => "inputs"
+        }
+    }
+
+}
+
+
+// Traffic Statistics ---------------------------------------------------------
+#[derive(Debug, Default, Copy, Clone)]
+pub struct TrafficCounter {
+    pub packets: u64,
+    pub bytes: u64
+}
+
+/// Traffic in one direction of a connection.
+///
+/// Entity packets can contain records of several kinds, their bytes are split
+/// up accordingly and the packet is counted once for every kind it contains.
+#[derive(Debug, Default, Clone)]
+pub struct TrafficStats {
+    pub total: TrafficCounter,
+    pub kinds: [TrafficCounter; 4],
+
+    /// Bytes per second and kind during the last completed window
+    pub rates: [u64; 4],
+    window: [u64; 4]
+}
+
+impl TrafficStats {
+
+    pub fn kind(&self, kind: TrafficKind) -> TrafficCounter {
+        self.kinds[kind as usize]
+    }
+
+    pub fn rate(&self) -> u64 {
+        self.rates.iter().fold(0, |sum, rate| sum + rate)
+    }
+
+    pub fn record(&mut self, kind: TrafficKind, bytes: usize) {
+        self.total.packets += 1;
+        self.total.bytes += bytes as u64;
+        self.kinds[kind as usize].packets += 1;
+        self.add(kind, bytes);
+    }
+
+    /// Records a packet sent by the server, either hexahydrate entity records
+    /// or a serialized action.
+    pub fn record_server_packet(&mut self, packet: &[u8]) {
+        // See hexahydrate's server side network states
+        if packet.first().map_or(false, |b| *b <= 5) {
+            self.record_entities(packet, |state, bytes| match state {
+                0 => Some((TrafficKind::Create, 4 + entity_length(bytes))),
+                3 => Some((TrafficKind::Update, 3 + entity_length(bytes))),
+                1 | 4 | 5 => Some((TrafficKind::Create, 2)),
+                _ => None
+            });
+
+        } else {
+            self.record(TrafficKind::Action, packet.len());
+        }
+    }
+
+    /// Records a packet sent by a client, either hexahydrate entity records
+    /// or a serialized action.
+    pub fn record_client_packet(&mut self, packet: &[u8]) {
+        // See hexahydrate's client side network states
+        if packet.first().map_or(false, |b| *b >= 1 && *b <= 4) {
+            self.record_entities(packet, |state, bytes| match state {
+                3 => Some((TrafficKind::Input, 3 + entity_length(bytes))),
+                2 => Some((TrafficKind::Update, 2)),
+                1 | 4 => Some((TrafficKind::Create, 2)),
+                _ => None
+            });
+
+        } else {
+            self.record(TrafficKind::Action, packet.len());
+        }
+    }
+
+    fn record_entities<F: Fn(u8, &[u8]) -> Option<(TrafficKind, usize)>>(&mut self, packet: &[u8], record: F) {
+
+        let mut contained = [false; 4];
+        let mut i = 0;
+        while i < packet.len() {
+            if let Some((kind, size)) = record(packet[i], &packet[i..]) {
+                let size = if i + size > packet.len() {
+                    packet.len() - i
+
+                } else {
+                    size
+                };
+                self.add(kind, size);
+                contained[kind as usize] = true;
+                i += size;
+
+            // Attribute malformed trailing data to updates
+            } else {
+                self.add(TrafficKind::Update, packet.len() - i);
+                contained[TrafficKind::Update as usize] = true;
+                break;
+            }
+        }
+
+        self.total.packets += 1;
+        self.total.bytes += packet.len() as u64;
+        for (index, contained) in contained.iter().enumerate() {
+            if *contained {
+                self.kinds[index].packets += 1;
+            }
+        }
+
+    }
+
+    fn add(&mut self, kind: TrafficKind, bytes: usize) {
+        let index = kind as usize;
+        self.kinds[index].bytes += bytes as u64;
+        self.window[index] += bytes as u64;
+    }
+
+    fn roll(&mut self, elapsed: u64) {
+        for (rate, bytes) in self.rates.iter_mut().zip(self.window.iter_mut()) {
+            *rate = *bytes * 1000 / elapsed;
+            *bytes = 0;
+        }
+    }
+
+}
+
+
+// Connection Statistics ------------------------------------------------------
+#[derive(Debug, Clone)]
+pub struct NetworkStats {
+    pub sent: TrafficStats,
+    pub received: TrafficStats,
+
+    /// Estimated packet loss in percent
+    pub packet_loss: f32,
+    rtt_history: VecDeque<u32>,
+    window_start: u64
+}
+
+impl NetworkStats {
+
+    pub fn new(t: u64) -> NetworkStats {
+        NetworkStats {
+            sent: TrafficStats::default(),
+            received: TrafficStats::default(),
+            packet_loss: 0.0,
+            rtt_history: VecDeque::new(),
+            window_start: t
+        }
+    }
+
+    /// Most recent round trip time in milliseconds.
+    pub fn rtt(&self) -> Option<u32> {
+        self.rtt_history.back().cloned()
+    }
+
+    /// Round trip times in milliseconds, oldest first.
+    pub fn rtt_history(&self) -> &VecDeque<u32> {
+        &self.rtt_history
+    }
+
+    pub fn record_rtt(&mut self, rtt: u32) {
+        self.rtt_history.push_back(rtt);
+        if self.rtt_history.len() > NETWORK_STATS_RTT_HISTORY {
+            self.rtt_history.pop_front();
+        }
+    }
+
+    pub fn set_packet_loss(&mut self, packet_loss: f32) {
+        self.packet_loss = packet_loss;
+    }
+
+    /// Completes the current window once it has lasted long enough, returns
+    /// `true` when the rates have been updated.
+    pub fn update(&mut self, t: u64) -> bool {
+        let elapsed = t.saturating_sub(self.window_start);
+        if elapsed >= NETWORK_STATS_WINDOW {
+            self.sent.roll(elapsed);
+            self.received.roll(elapsed);
+            self.window_start = t;
+            true
+
+        } else {
+            false
+        }
+    }
+
+}
+
+impl fmt::Display for NetworkStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "RTT {}ms, loss {:.1}%", self.rtt().unwrap_or(0), self.packet_loss));
+        for &(name, stats) in &[("sent", &self.sent), ("received", &self.received)] {
+            try!(write!(
+                f, ", {} {} packets / {} bytes ({} B/s:",
+                name, stats.total.packets, stats.total.bytes, stats.rate()
+            ));
+            for kind in &TrafficKind::all() {
+                try!(write!(f, " {} {}", kind.name(), stats.rates[*kind as usize]));
+            }
+            try!(write!(f, ")"));
+        }
+        Ok(())
+    }
+}
+
+
+// Helpers --------------------------------------------------------------------
+fn entity_length(bytes: &[u8]) -> usize {
+    bytes.get(2).map_or(0, |length| *length as usize)
+}
+
@@ -0,0 +1,67 @@
+// STD Dependencies -----------------------------------------------------------
+use std::fmt;
+
+
+// External Dependencies ------------------------------------------------------
+use rustc_serialize::json;
+
+
+// Statics --------------------------------------------------------------------
+pub const STATUS_PATH: &'static str = "/status";
+
+
+// Status Errors --------------------------------------------------------------
+#[derive(Debug)]
+pub enum StatusError {
+    Request(String),
+    InvalidResponse(String)
+}
+
+impl fmt::Display for StatusError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StatusError::Request(ref err) => write!(f, "Request failed: {}", err),
+            StatusError::InvalidResponse(ref err) => write!(f, "Invalid status response: {}", err)
+        }
+    }
+}
+
+
+// Server Status --------------------------------------------------------------
+
+/// Public information about a running server, served as JSON by its HTTP
+/// listener for server browsers.
+#[derive(Debug, Clone, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct ServerStatus {
+    pub name: String,
+    pub map: String,
+    pub mode: String,
+    pub version: String,
+    pub players: usize,
+    pub bots: usize,
+    pub max_players: usize
+}
+
+impl ServerStatus {
+
+    pub fn to_json(&self) -> String {
+        json::encode(self).expect("Failed to encode server status.")
+    }
+
+    pub fn from_json(string: &str) -> Result<ServerStatus, StatusError> {
+        json::decode(string).map_err(|err| StatusError::InvalidResponse(err.to_string()))
+    }
+
+}
+
+impl fmt::Display for ServerStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "{} - {} on {} ({}/{} players, {} bots) v{}",
+            self.name, self.mode, self.map,
+            self.players + self.bots, self.max_players, self.bots,
+            self.version
+        )
+    }
+}
+
@@ -0,0 +1,110 @@
+// STD Dependencies -----------------------------------------------------------
+use std::f32::consts;
+
+
+// Weapons --------------------------------------------------------------------
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Weapon {
+    LaserBeam,
+    Rocket,
+    Shotgun
+}
+
+impl Weapon {
+
+    pub fn to_u8(&self) -> u8 {
+        match *self {
+            Weapon::LaserBeam => 0,
+            Weapon::Rocket => 1,
+            Weapon::Shotgun => 2
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Option<Weapon> {
+        match value {
+            0 => Some(Weapon::LaserBeam),
+            1 => Some(Weapon::Rocket),
+            2 => Some(Weapon::Shotgun),
+            _ => None
+        }
+    }
+
+    pub fn stats(&self) -> WeaponStats {
+        match *self {
+            Weapon::LaserBeam => WeaponStats {
+                fire_interval: 300,
+                damage: 64,
+                range: 90.0,
+                spread: 0.0,
+                pellets: 1,
+                projectile_speed: None,
+                splash_radius: 0.0
+            },
+            Weapon::Rocket => WeaponStats {
+                fire_interval: 900,
+                damage: 96,
+                range: 180.0,
+                spread: 0.0,
+                pellets: 1,
+                projectile_speed: Some(150.0),
+                splash_radius: 14.0
+            },
+            Weapon::Shotgun => WeaponStats {
+                fire_interval: 700,
+                damage: 28,
+                range: 45.0,
+                spread: consts::PI * 0.15,
+                pellets: 5,
+                projectile_speed: None,
+                splash_radius: 0.0
+            }
+        }
+    }
+
+    /// Returns the firing angles of all pellets fired in direction `r`.
+    ///
+    /// Pellets are spread out evenly across the weapon's spread so that
+    /// clients and server agree on them without any randomness involved.
+    pub fn pellet_angles(&self, r: f32) -> Vec<f32> {
+        let stats = self.stats();
+        if stats.pellets <= 1 {
+            vec![r]
+
+        } else {
+            let step = stats.spread / (stats.pellets - 1) as f32;
+            (0..stats.pellets).map(|i| {
+                r - stats.spread * 0.5 + step * i as f32
+
+            }).collect()
+        }
+    }
+
+}
+
+
+// Weapon Statistics ----------------------------------------------------------
+#[derive(Debug, Clone)]
+pub struct WeaponStats {
+
+    /// Minimum delay in milliseconds between two shots
+    pub fire_interval: u64,
+
+    /// Damage applied per hit, for spread weapons this is per pellet
+    pub damage: u8,
+
+    /// Maximum length of hitscan beams or travel distance of projectiles
+    pub range: f32,
+
+    /// Total angle across which the pellets are spread
+    pub spread: f32,
+
+    /// Number of pellets fired per shot
+    pub pellets: u8,
+
+    /// Speed in units per second for projectile weapons, `None` for hitscan
+    pub projectile_speed: Option<f32>,
+
+    /// Radius around the impact point of a projectile in which players are damaged
+    pub splash_radius: f32
+
+}
@@ -0,0 +1,266 @@
+extern crate shared;
+
+use shared::action::Action;
+use shared::clock::{ClockSync, CLOCK_SYNC_INTERVAL, CLOCK_SYNC_MIN_SAMPLES};
+
+
+// Helpers --------------------------------------------------------------------
+const TICK_DURATION: u64 = 40;
+
+/// Simulates a server whose clock is `offset` milliseconds ahead of the
+/// client's and which started at tick zero.
+struct MockServer {
+    offset: u64,
+    latency: u64
+}
+
+impl MockServer {
+
+    fn tick_at(&self, t: u64) -> u16 {
+        ((t + self.offset) / TICK_DURATION) as u16
+    }
+
+    /// Runs a full request / response exchange starting at client time `t`,
+    /// returning the client time at which the response arrived.
+    fn exchange(&self, clock: &mut ClockSync, t: u64) -> u64 {
+        assert!(clock.request(t).is_some(), "expected a clock request to be due");
+        self.respond(clock, t)
+    }
+
+    /// Answers a clock request sent at client time `t`, returning the client
+    /// time at which the response arrived.
+    fn respond(&self, clock: &mut ClockSync, t: u64) -> u64 {
+        let server_time = t + self.latency + self.offset;
+        let arrival = t + self.latency * 2;
+        clock.receive(
+            arrival,
+            t,
+            server_time,
+            (server_time / TICK_DURATION) as u16,
+            (server_time % TICK_DURATION) as u16
+        );
+        arrival
+    }
+
+}
+
+fn synced_clock(server: &MockServer) -> (ClockSync, u64) {
+    let mut clock = ClockSync::new(25);
+    let mut t = 1000;
+    for _ in 0..CLOCK_SYNC_MIN_SAMPLES {
+        server.exchange(&mut clock, t);
+        t += 200;
+    }
+    (clock, t)
+}
+
+
+// Estimation -----------------------------------------------------------------
+#[test]
+fn test_sync_after_min_samples() {
+
+    let server = MockServer { offset: 5000, latency: 40 };
+    let mut clock = ClockSync::new(25);
+    for i in 0..CLOCK_SYNC_MIN_SAMPLES {
+        assert!(!clock.is_synced());
+        server.exchange(&mut clock, 1000 + i as u64 * 100);
+    }
+
+    assert!(clock.is_synced());
+    assert_eq!(clock.rtt(), 80.0);
+    assert_eq!(clock.jitter(), 0.0);
+    assert_eq!(clock.offset(), 5000.0);
+
+}
+
+#[test]
+fn test_offset_from_lowest_rtt() {
+
+    let mut clock = ClockSync::new(30);
+
+    // A delayed response overestimates the one way latency
+    clock.request(1000);
+    clock.receive(1300, 1000, 10100, 0, 0);
+    clock.request(2000);
+    clock.receive(2050, 2000, 11025, 0, 0);
+    assert_eq!(clock.rtt(), 175.0);
+    assert_eq!(clock.offset(), 9000.0);
+
+}
+
+#[test]
+fn test_server_tick_estimate() {
+
+    let server = MockServer { offset: 123456, latency: 25 };
+    let (clock, t) = synced_clock(&server);
+    for dt in 0..100 {
+        let t = t + dt * 7;
+        assert_eq!(clock.server_tick(t), server.tick_at(t));
+        assert_eq!(clock.view_tick(t), server.tick_at(t - 25));
+    }
+
+}
+
+#[test]
+fn test_tick_wrap_around() {
+
+    // Server tick is about to wrap around
+    let server = MockServer { offset: 65530 * TICK_DURATION, latency: 10 };
+    let (clock, t) = synced_clock(&server);
+    assert_eq!(clock.server_tick(t + TICK_DURATION * 20), server.tick_at(t + TICK_DURATION * 20));
+
+}
+
+
+// Requests -------------------------------------------------------------------
+#[test]
+fn test_request_interval() {
+
+    let server = MockServer { offset: 0, latency: 10 };
+    let mut clock = ClockSync::new(30);
+    match clock.request(1000) {
+        Some(Action::ClockRequest(1000)) => {},
+        _ => panic!("Expected initial clock request")
+    }
+    assert!(clock.request(1050).is_none(), "should wait between requests");
+    assert!(clock.request(1100).is_some(), "should send requests in quick succession while not synced");
+
+    server.respond(&mut clock, 1000);
+    server.respond(&mut clock, 1100);
+    server.exchange(&mut clock, 1200);
+    server.exchange(&mut clock, 1300);
+    assert!(clock.is_synced());
+
+    assert!(clock.request(1400).is_some());
+    assert!(clock.request(1500).is_none(), "should slow down once synced");
+    assert!(clock.request(1400 + CLOCK_SYNC_INTERVAL).is_some());
+
+}
+
+#[test]
+fn test_ignore_responses_from_the_future() {
+    let mut clock = ClockSync::new(30);
+    assert!(!clock.receive(1000, 2000, 0, 0, 0));
+    assert_eq!(clock.rtt(), 0.0);
+}
+
+#[test]
+fn test_ignore_duplicate_responses() {
+
+    let server = MockServer { offset: 0, latency: 10 };
+    let (mut clock, t) = synced_clock(&server);
+    let t = t + CLOCK_SYNC_INTERVAL;
+    server.exchange(&mut clock, t);
+    let (rtt, jitter, offset) = (clock.rtt(), clock.jitter(), clock.offset());
+
+    // The same response arriving again later
+    assert!(!clock.receive(t + 100, t, t + 10, 0, 0));
+    assert_eq!(clock.rtt(), rtt);
+    assert_eq!(clock.jitter(), jitter);
+    assert_eq!(clock.offset(), offset);
+    assert_eq!(clock.packet_loss(), 0.0);
+
+}
+
+#[test]
+fn test_ignore_expired_responses() {
+
+    let server = MockServer { offset: 0, latency: 10 };
+    let (mut clock, t) = synced_clock(&server);
+    let t = t + CLOCK_SYNC_INTERVAL;
+    assert!(clock.request(t).is_some());
+    let (rtt, jitter, offset) = (clock.rtt(), clock.jitter(), clock.offset());
+
+    // The response arrives after the request timed out
+    assert!(!clock.receive(t + 5000, t, t + 2500, 0, 0));
+    assert_eq!(clock.rtt(), rtt);
+    assert_eq!(clock.jitter(), jitter);
+    assert_eq!(clock.offset(), offset);
+    assert_eq!(clock.packet_loss(), 100.0 / (CLOCK_SYNC_MIN_SAMPLES + 1) as f32);
+
+}
+
+
+// Alignment ------------------------------------------------------------------
+#[test]
+fn test_align_ahead_of_server() {
+
+    let server = MockServer { offset: 9999, latency: 50 };
+    let (mut clock, t) = synced_clock(&server);
+
+    let tick = clock.align(0, t).expect("Expected the initial tick to be aligned");
+    let ahead = (tick as i64 - server.tick_at(t) as i64) as f64;
+    assert!((ahead - clock.lead()).abs() <= 1.0, "should run ahead by the lead");
+    assert!(clock.lead() > 50.0 / TICK_DURATION as f64, "should lead by at least the one way latency");
+
+    assert_eq!(clock.align(tick, t), None, "should not realign once aligned");
+
+}
+
+#[test]
+fn test_dilation_corrects_drift() {
+
+    let server = MockServer { offset: 777, latency: 30 };
+    let (mut clock, mut t) = synced_clock(&server);
+    let mut tick = clock.align(0, t).unwrap();
+
+    // Fall behind by a few ticks
+    tick = tick.wrapping_sub(4);
+    assert_eq!(clock.align(tick, t), None, "should not snap for small amounts of drift");
+
+    let mut total = 0;
+    for _ in 0..600 {
+        let steps = clock.steps(tick, t);
+        assert!(steps <= 2);
+        assert!(clock.dilation() <= 1.05 && clock.dilation() >= 0.95);
+        tick = tick.wrapping_add(steps as u16);
+        total += steps;
+        t += TICK_DURATION;
+    }
+
+    assert!(total > 600, "should run additional steps to catch up");
+    assert!(clock.drift(tick, t).abs() < 1.5, "should converge on the target tick");
+
+}
+
+#[test]
+fn test_no_dilation_while_unsynced() {
+    let mut clock = ClockSync::new(30);
+    for i in 0..10 {
+        assert_eq!(clock.steps(i, i as u64 * 33), 1);
+    }
+    assert_eq!(clock.align(0, 5000), None);
+}
+
+#[test]
+fn test_reset() {
+    let server = MockServer { offset: 0, latency: 10 };
+    let (mut clock, _) = synced_clock(&server);
+    clock.reset();
+    assert!(!clock.is_synced());
+    assert!(clock.request(0).is_some());
+}
+
+
+#[test]
+fn test_packet_loss() {
+
+    let server = MockServer { offset: 0, latency: 10 };
+    let mut clock = ClockSync::new(25);
+    assert_eq!(clock.packet_loss(), 0.0);
+
+    // Every other request goes unanswered
+    for i in 0..8 {
+        let t = 1000 + i * CLOCK_SYNC_INTERVAL;
+        assert!(clock.request(t).is_some());
+        if i % 2 == 0 {
+            server.respond(&mut clock, t);
+        }
+    }
+
+    // The last unanswered request has not yet timed out
+    assert!((clock.packet_loss() - 100.0 / 3.0).abs() < 0.01, "only timed out requests count as lost");
+    clock.request(20000);
+    assert_eq!(clock.packet_loss(), 50.0);
+
+}
@@ -0,0 +1,89 @@
+extern crate cobalt;
+extern crate hexahydrate;
+extern crate netsync;
+#[macro_use]
+extern crate shared;
+
+use cobalt::ConnectionID;
+use hexahydrate::{Entity, EntityRegistry};
+use netsync::{ClientState, NetworkProperty};
+use shared::entity::{
+    PlayerData, PlayerInput, PlayerEntity,
+    PickupData, PickupEntity, PickupKind,
+    ProjectileData, ProjectileInput, ProjectileEntity,
+    ENTITY_KIND_PLAYER, ENTITY_KIND_PICKUP, ENTITY_KIND_PROJECTILE,
+    PLAYER_MAX_HP
+};
+use shared::weapon::Weapon;
+use shared::color::ColorName;
+
+
+// Registry -------------------------------------------------------------------
+entity_registry!(Registry, Entity<ConnectionID>, [
+    PlayerEntity<ClientState<PlayerData, PlayerInput>>,
+    PickupEntity,
+    ProjectileEntity<ClientState<ProjectileData, ProjectileInput>>
+]);
+
+
+// Helpers --------------------------------------------------------------------
+fn player_bytes() -> Vec<u8> {
+    let mut bytes = vec![1, ColorName::Red.to_u8()];
+    bytes.append(&mut PlayerData::new(10.0, 20.0, 0.0, PLAYER_MAX_HP).to_bytes());
+    bytes
+}
+
+fn projectile_bytes() -> Vec<u8> {
+    let mut bytes = vec![1, 12, 0];
+    bytes.append(&mut ProjectileData::new(Weapon::Rocket, ColorName::Red, 10.0, 20.0, 0.0).to_bytes());
+    bytes
+}
+
+
+// Kinds ----------------------------------------------------------------------
+#[test]
+fn test_kinds() {
+    assert_eq!(Registry::kinds(), vec![
+        ENTITY_KIND_PLAYER,
+        ENTITY_KIND_PICKUP,
+        ENTITY_KIND_PROJECTILE
+    ]);
+}
+
+#[test]
+fn test_kinds_are_unique() {
+    let mut kinds = Registry::kinds();
+    kinds.sort();
+    kinds.dedup();
+    assert_eq!(kinds.len(), 3);
+}
+
+
+// Entity Creation ------------------------------------------------------------
+#[test]
+fn test_entity_from_bytes() {
+
+    let player = Registry.entity_from_bytes(ENTITY_KIND_PLAYER, &player_bytes()).unwrap();
+    assert_eq!(player.kind(), ENTITY_KIND_PLAYER);
+
+    let pickup_bytes = PickupData::new(PickupKind::Health, 10.0, 20.0).to_bytes();
+    let pickup = Registry.entity_from_bytes(ENTITY_KIND_PICKUP, &pickup_bytes).unwrap();
+    assert_eq!(pickup.kind(), ENTITY_KIND_PICKUP);
+
+    let projectile = Registry.entity_from_bytes(ENTITY_KIND_PROJECTILE, &projectile_bytes()).unwrap();
+    assert_eq!(projectile.kind(), ENTITY_KIND_PROJECTILE);
+
+}
+
+#[test]
+fn test_entity_from_bytes_unknown_kind() {
+    assert!(Registry.entity_from_bytes(0, &player_bytes()).is_none());
+    assert!(Registry.entity_from_bytes(255, &player_bytes()).is_none());
+}
+
+#[test]
+fn test_entity_from_bytes_invalid_payload() {
+    assert!(Registry.entity_from_bytes(ENTITY_KIND_PICKUP, &[]).is_none());
+    assert!(Registry.entity_from_bytes(ENTITY_KIND_PROJECTILE, &[1, 12]).is_none());
+}
+
@@ -0,0 +1,76 @@
+extern crate log;
+extern crate shared;
+
+use log::{LogLevel, LogLevelFilter};
+use shared::logging::{LogEntry, LogFilter};
+
+
+// Filters --------------------------------------------------------------------
+#[test]
+fn test_filter_default_level() {
+    let filter = LogFilter::parse("").unwrap();
+    assert_eq!(filter.level("server"), LogLevelFilter::Info);
+    assert_eq!(filter.max_level(), LogLevelFilter::Info);
+
+    let filter = LogFilter::parse("warn").unwrap();
+    assert_eq!(filter.level("client::client"), LogLevelFilter::Warn);
+}
+
+#[test]
+fn test_filter_module_levels() {
+
+    let filter = LogFilter::parse("warn, server=info,server::bot=trace ,client=off").unwrap();
+    assert_eq!(filter.level("server"), LogLevelFilter::Info);
+    assert_eq!(filter.level("server::server"), LogLevelFilter::Info);
+    assert_eq!(filter.level("server::bot"), LogLevelFilter::Trace);
+    assert_eq!(filter.level("server::bot::path"), LogLevelFilter::Trace);
+    assert_eq!(filter.level("client::client"), LogLevelFilter::Off);
+    assert_eq!(filter.level("shared::entity"), LogLevelFilter::Warn);
+    assert_eq!(filter.max_level(), LogLevelFilter::Trace);
+
+    // Only whole module names match
+    assert_eq!(filter.level("serverless"), LogLevelFilter::Warn);
+
+}
+
+#[test]
+fn test_filter_invalid() {
+    assert!(LogFilter::parse("verbose").is_err());
+    assert!(LogFilter::parse("server=loud").is_err());
+    assert!(LogFilter::parse("=debug").is_err());
+}
+
+
+// Entries --------------------------------------------------------------------
+fn entry(tick: Option<u16>, connection: Option<u32>, message: &str) -> LogEntry {
+    LogEntry {
+        time: 1234,
+        level: LogLevel::Warn,
+        target: "server::server".to_string(),
+        tick: tick,
+        connection: connection,
+        message: message.to_string()
+    }
+}
+
+#[test]
+fn test_entry_text() {
+    assert_eq!(entry(None, None, "Started").to_text(), "WARN  [server::server] Started");
+    assert_eq!(
+        entry(Some(42), Some(7), "Player joined").to_text(),
+        "WARN  [server::server] tick=42 conn=7 Player joined"
+    );
+}
+
+#[test]
+fn test_entry_json() {
+    assert_eq!(
+        entry(None, None, "Started").to_json(),
+        "{\"time\":1234,\"level\":\"WARN\",\"target\":\"server::server\",\"tick\":null,\"connection\":null,\"message\":\"Started\"}"
+    );
+    assert_eq!(
+        entry(Some(42), Some(7), "Said \"hi\"\n\\").to_json(),
+        "{\"time\":1234,\"level\":\"WARN\",\"target\":\"server::server\",\"tick\":42,\"connection\":7,\"message\":\"Said \\\"hi\\\"\\n\\\\\"}"
+    );
+}
+
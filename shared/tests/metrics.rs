@@ -0,0 +1,81 @@
+extern crate shared;
+
+use shared::metrics::{Histogram, ServerMetrics, METRICS_RATE_WINDOW};
+
+
+// Histogram ------------------------------------------------------------------
+#[test]
+fn test_histogram_buckets() {
+
+    let mut histogram = Histogram::new(&[0.01, 0.1, 1.0]);
+    histogram.observe(0.005);
+    histogram.observe(0.01);
+    histogram.observe(0.5);
+    histogram.observe(2.0);
+
+    assert_eq!(histogram.buckets(), vec![(0.01, 2), (0.1, 2), (1.0, 3)]);
+    assert_eq!(histogram.count(), 4);
+    assert!((histogram.sum() - 2.515).abs() < 0.0001);
+
+}
+
+
+// Server Metrics -------------------------------------------------------------
+#[test]
+fn test_rates() {
+
+    let mut metrics = ServerMetrics::new(1000);
+    metrics.record_actions(5);
+    metrics.record_actions(5);
+    metrics.record_sent(300);
+    metrics.record_received(100);
+    metrics.record_received(100);
+
+    assert!(!metrics.update(1500));
+    assert_eq!(metrics.actions_per_second, 0);
+
+    assert!(metrics.update(1000 + METRICS_RATE_WINDOW * 2));
+    assert_eq!(metrics.actions_per_second, 5);
+    assert_eq!(metrics.bytes_sent_per_second, 150);
+    assert_eq!(metrics.bytes_received_per_second, 100);
+
+    // Totals are kept when the window is rolled over
+    assert!(metrics.update(1000 + METRICS_RATE_WINDOW * 3));
+    assert_eq!(metrics.actions_per_second, 0);
+    assert_eq!(metrics.actions, 10);
+    assert_eq!(metrics.packets_received, 2);
+    assert_eq!(metrics.bytes_received, 200);
+
+}
+
+#[test]
+fn test_prometheus_format() {
+
+    let mut metrics = ServerMetrics::new(0);
+    metrics.players = 2;
+    metrics.bots = 3;
+    metrics.kills = 7;
+    metrics.tick_duration.observe(0.004);
+    metrics.tick_duration.observe(0.04);
+
+    let text = metrics.to_prometheus();
+    let lines: Vec<&str> = text.lines().collect();
+    assert!(lines.contains(&"# TYPE shooter_tick_duration_seconds histogram"));
+    assert!(lines.contains(&"shooter_tick_duration_seconds_bucket{le=\"0.0025\"} 0"));
+    assert!(lines.contains(&"shooter_tick_duration_seconds_bucket{le=\"0.005\"} 1"));
+    assert!(lines.contains(&"shooter_tick_duration_seconds_bucket{le=\"0.05\"} 2"));
+    assert!(lines.contains(&"shooter_tick_duration_seconds_bucket{le=\"+Inf\"} 2"));
+    assert!(lines.contains(&"shooter_tick_duration_seconds_count 2"));
+    assert!(lines.contains(&"shooter_players{kind=\"human\"} 2"));
+    assert!(lines.contains(&"shooter_players{kind=\"bot\"} 3"));
+    assert!(lines.contains(&"# TYPE shooter_kills_total counter"));
+    assert!(lines.contains(&"shooter_kills_total 7"));
+
+    // Every sample belongs to a declared metric
+    for line in lines.iter().filter(|line| !line.starts_with('#')) {
+        let name = line.split(|c| c == '{' || c == ' ').next().unwrap();
+        assert!(text.contains(&format!("# TYPE {} ", name.trim_right_matches("_bucket").trim_right_matches("_sum").trim_right_matches("_count"))), "{} is not declared", name);
+    }
+
+}
+
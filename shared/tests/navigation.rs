@@ -0,0 +1,209 @@
+extern crate shared;
+
+use std::fs::File;
+use std::io::Read;
+
+use shared::level::{Level, LevelCollision, LevelNavigation};
+
+
+// Helpers --------------------------------------------------------------------
+fn map_level() -> Level {
+    let mut toml = String::new();
+    File::open("../editor/map.toml").unwrap().read_to_string(&mut toml).unwrap();
+    Level::from_toml_string(&toml)
+}
+
+fn box_level() -> Level {
+    // A 100x100 box split by a wall with a passage at its bottom
+    Level::from_toml_string(r#"
+        walls = [
+            { line = [0.0, 0.0, 100.0, 0.0] },
+            { line = [100.0, 0.0, 100.0, 100.0] },
+            { line = [0.0, 100.0, 100.0, 100.0] },
+            { line = [0.0, 0.0, 0.0, 100.0] },
+            { line = [50.0, 0.0, 50.0, 70.0] }
+        ]
+    "#)
+}
+
+fn path_length(path: &[[f32; 2]]) -> f32 {
+    path.windows(2).fold(0.0, |length, p| {
+        let (dx, dy) = (p[1][0] - p[0][0], p[1][1] - p[0][1]);
+        length + (dx * dx + dy * dy).sqrt()
+    })
+}
+
+fn assert_valid_path(level: &Level, path: &[[f32; 2]], from: [f32; 2], to: [f32; 2]) {
+
+    assert!(path.len() >= 2);
+    assert_eq!(path[0], from);
+    assert_eq!(path[path.len() - 1], to);
+
+    // Inner segments must keep their clearance, all of them must not cross walls
+    for (i, p) in path.windows(2).enumerate() {
+        assert!(level.collide_line(&[p[0][0], p[0][1], p[1][0], p[1][1]]).is_none());
+        if i > 0 && i < path.len() - 2 {
+            assert!(level.walkable_path(p[0][0], p[0][1], p[1][0], p[1][1]));
+        }
+    }
+
+}
+
+
+// Grid -----------------------------------------------------------------------
+#[test]
+fn test_clearance() {
+    let level = box_level();
+    assert!(level.is_walkable(25.0, 50.0));
+    assert!(level.is_walkable(75.0, 50.0));
+    assert!(!level.is_walkable(50.0, 50.0));
+    assert!(!level.is_walkable(53.0, 50.0));
+    assert!(!level.is_walkable(2.0, 50.0));
+    assert!(!level.is_walkable(-20.0, 50.0));
+}
+
+#[test]
+fn test_solid_clearance() {
+    let level = Level::from_toml_string(r#"
+        walls = [
+            { line = [0.0, 0.0, 100.0, 0.0] },
+            { line = [100.0, 0.0, 100.0, 100.0] },
+            { line = [0.0, 100.0, 100.0, 100.0] },
+            { line = [0.0, 0.0, 0.0, 100.0] }
+        ]
+        solids = [[60.0, 60.0, 80.0, 60.0, 80.0, 80.0, 60.0, 80.0]]
+    "#);
+    assert!(!level.is_walkable(70.0, 70.0));
+    assert!(!level.is_walkable(57.0, 70.0));
+    assert!(!level.is_walkable(70.0, 83.0));
+    assert!(level.is_walkable(50.0, 70.0));
+    assert!(level.is_walkable(70.0, 90.0));
+}
+
+#[test]
+fn test_flood_fill() {
+
+    let level = box_level();
+    let grid = level.navigation_grid();
+    let start = grid.nearest_walkable(25.0, 50.0).unwrap();
+    assert_eq!(grid.flood_fill(start).len(), grid.walkable_cells());
+
+    let level = Level::from_toml_string(r#"
+        walls = [
+            { line = [0.0, 0.0, 100.0, 0.0] },
+            { line = [100.0, 0.0, 100.0, 100.0] },
+            { line = [0.0, 100.0, 100.0, 100.0] },
+            { line = [0.0, 0.0, 0.0, 100.0] },
+            { line = [50.0, 0.0, 50.0, 100.0] }
+        ]
+    "#);
+    let grid = level.navigation_grid();
+    let (left, right) = (grid.nearest_walkable(25.0, 50.0).unwrap(), grid.nearest_walkable(75.0, 50.0).unwrap());
+    let filled = grid.flood_fill(left);
+    assert_eq!(filled.len() * 2, grid.walkable_cells());
+    assert!(!filled.contains(&right));
+
+}
+
+#[test]
+fn test_empty_level() {
+    let level = Level::new();
+    assert_eq!(level.navigation_grid().walkable_cells(), 0);
+    assert!(level.find_path(0.0, 0.0, 10.0, 10.0).is_none());
+}
+
+
+// Path Finding ---------------------------------------------------------------
+#[test]
+fn test_path_straight() {
+    let level = box_level();
+    let path = level.find_path(10.0, 20.0, 40.0, 50.0).unwrap();
+    assert_eq!(path, vec![[10.0, 20.0], [40.0, 50.0]]);
+}
+
+#[test]
+fn test_path_around_wall() {
+
+    let level = box_level();
+    let (from, to) = ([25.0, 20.0], [75.0, 20.0]);
+    let path = level.find_path(from[0], from[1], to[0], to[1]).unwrap();
+    assert_valid_path(&level, &path, from, to);
+
+    // The path needs to go through the passage below the wall
+    assert!(path.iter().any(|p| p[1] > 70.0));
+    assert!(path.len() <= 5);
+
+}
+
+#[test]
+fn test_path_blocked() {
+    let level = Level::from_toml_string(r#"
+        walls = [
+            { line = [0.0, 0.0, 100.0, 0.0] },
+            { line = [100.0, 0.0, 100.0, 100.0] },
+            { line = [0.0, 100.0, 100.0, 100.0] },
+            { line = [0.0, 0.0, 0.0, 100.0] },
+            { line = [50.0, 0.0, 50.0, 100.0] }
+        ]
+    "#);
+    assert!(level.find_path(25.0, 50.0, 75.0, 50.0).is_none());
+}
+
+#[test]
+fn test_path_through_narrow_passage() {
+    // The gap is too narrow for a player to pass
+    let level = Level::from_toml_string(r#"
+        walls = [
+            { line = [0.0, 0.0, 100.0, 0.0] },
+            { line = [100.0, 0.0, 100.0, 100.0] },
+            { line = [0.0, 100.0, 100.0, 100.0] },
+            { line = [0.0, 0.0, 0.0, 100.0] },
+            { line = [50.0, 0.0, 50.0, 45.0] },
+            { line = [50.0, 55.0, 50.0, 100.0] }
+        ]
+    "#);
+    assert!(level.find_path(25.0, 50.0, 75.0, 50.0).is_none());
+}
+
+
+// Shipped Map ----------------------------------------------------------------
+#[test]
+fn test_map_spawns_walkable() {
+    let level = map_level();
+    assert!(level.navigation_grid().walkable_cells() > 0);
+    for spawn in &level.spawns {
+        assert!(level.is_walkable(spawn.x, spawn.y), "Spawn at {}, {} is not walkable", spawn.x, spawn.y);
+    }
+}
+
+#[test]
+fn test_map_solids_not_walkable() {
+    let level = map_level();
+    for solid in &level.solids {
+        let (sx, sy) = solid.iter().fold((0.0, 0.0), |(sx, sy), p| (sx + p[0], sy + p[1]));
+        let (x, y) = (sx / solid.len() as f32, sy / solid.len() as f32);
+        if shared::level::solid_contains_point(solid, x, y) {
+            assert!(!level.is_walkable(x, y));
+        }
+    }
+}
+
+#[test]
+fn test_map_paths_between_spawns() {
+
+    let level = map_level();
+    for a in &level.spawns {
+        for b in &level.spawns {
+
+            let (from, to) = ([a.x, a.y], [b.x, b.y]);
+            let path = level.find_path(a.x, a.y, b.x, b.y).unwrap();
+            assert_valid_path(&level, &path, from, to);
+
+            let (dx, dy) = (b.x - a.x, b.y - a.y);
+            assert!(path_length(&path) >= (dx * dx + dy * dy).sqrt() - 0.01);
+
+        }
+    }
+
+}
+
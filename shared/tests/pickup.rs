@@ -0,0 +1,167 @@
+extern crate cobalt;
+extern crate hexahydrate;
+extern crate shared;
+
+use cobalt::ConnectionID;
+use hexahydrate::Entity;
+use shared::collision::aabb_intersect_circle;
+use shared::color::ColorName;
+use shared::level::Level;
+use shared::entity::{
+    PickupData, PickupEntity, PickupKind,
+    PICKUP_RADIUS, PICKUP_RESPAWN_INTERVAL, PLAYER_RADIUS,
+    PLAYER_BUFF_DAMAGE, PLAYER_BUFF_SPEED, PLAYER_BUFF_SHIELD
+};
+
+
+// Helpers --------------------------------------------------------------------
+fn kinds() -> Vec<PickupKind> {
+    vec![
+        PickupKind::Health,
+        PickupKind::DamageBoost,
+        PickupKind::Speed,
+        PickupKind::Shield
+    ]
+}
+
+fn remote_pickup(pickup: &PickupEntity) -> PickupEntity {
+    <PickupEntity as Entity<ConnectionID>>::from_bytes(&pickup.data.to_bytes()).unwrap()
+}
+
+fn touches(pickup: &PickupEntity, x: f32, y: f32) -> bool {
+    aabb_intersect_circle(&pickup.data.aabb(), x, y, PLAYER_RADIUS)
+}
+
+
+// Pickup Kinds ---------------------------------------------------------------
+#[test]
+fn test_kind_ids() {
+    for kind in kinds() {
+        assert_eq!(PickupKind::from_u8(kind.to_u8()), Some(kind));
+    }
+    assert_eq!(PickupKind::from_u8(4), None);
+}
+
+#[test]
+fn test_kind_names() {
+    assert_eq!(PickupKind::from_name("health"), Some(PickupKind::Health));
+    assert_eq!(PickupKind::from_name("damage"), Some(PickupKind::DamageBoost));
+    assert_eq!(PickupKind::from_name("speed"), Some(PickupKind::Speed));
+    assert_eq!(PickupKind::from_name("shield"), Some(PickupKind::Shield));
+    assert_eq!(PickupKind::from_name("armor"), None);
+}
+
+#[test]
+fn test_kind_buffs() {
+    assert_eq!(PickupKind::Health.buff(), None);
+    assert_eq!(PickupKind::DamageBoost.buff(), Some(PLAYER_BUFF_DAMAGE));
+    assert_eq!(PickupKind::Speed.buff(), Some(PLAYER_BUFF_SPEED));
+    assert_eq!(PickupKind::Shield.buff(), Some(PLAYER_BUFF_SHIELD));
+    assert_eq!(PickupKind::Health.color_name(), ColorName::Green);
+}
+
+
+// Serialization --------------------------------------------------------------
+#[test]
+fn test_data_bytes() {
+    for kind in kinds() {
+        let mut data = PickupData::new(kind, 12.5, -4.0);
+        data.active = false;
+
+        let decoded = PickupData::from_bytes(&data.to_bytes()).unwrap();
+        assert_eq!(decoded.kind, kind);
+        assert_eq!((decoded.x, decoded.y), (12.5, -4.0));
+        assert!(!decoded.active);
+    }
+}
+
+#[test]
+fn test_data_bytes_invalid() {
+    let mut bytes = PickupData::new(PickupKind::Health, 0.0, 0.0).to_bytes();
+    assert!(PickupData::from_bytes(&bytes[..2]).is_none());
+
+    // Unknown kind
+    bytes[0] = 4;
+    assert!(PickupData::from_bytes(&bytes).is_none());
+}
+
+
+// Respawning -----------------------------------------------------------------
+#[test]
+fn test_collect_and_respawn() {
+
+    let mut pickup = PickupEntity::new(PickupKind::Speed, 0.0, 0.0, 1000);
+    assert!(pickup.data.active);
+
+    pickup.collect(5000);
+    assert!(!pickup.data.active);
+
+    pickup.update(5999);
+    assert!(!pickup.data.active);
+
+    pickup.update(6000);
+    assert!(pickup.data.active);
+
+}
+
+#[test]
+fn test_remote_state() {
+
+    let mut pickup = PickupEntity::new(PickupKind::Shield, 10.0, 20.0, 1000);
+    let mut remote = remote_pickup(&pickup);
+    assert_eq!(remote.data.kind, PickupKind::Shield);
+    assert_eq!((remote.data.x, remote.data.y), (10.0, 20.0));
+
+    // Only the server side sends out its state
+    assert!(remote.part_bytes(None).is_none());
+
+    pickup.collect(0);
+    let bytes = pickup.part_bytes(None).unwrap();
+    remote.merge_bytes(None, &bytes);
+    assert!(!remote.data.active);
+
+    pickup.update(2000);
+    let bytes = pickup.part_bytes(None).unwrap();
+    remote.merge_bytes(None, &bytes);
+    assert!(remote.data.active);
+
+}
+
+
+// Collection -----------------------------------------------------------------
+#[test]
+fn test_collision() {
+    let pickup = PickupEntity::new(PickupKind::Health, 50.0, 50.0, 1000);
+    let reach = PICKUP_RADIUS + PLAYER_RADIUS;
+    assert!(touches(&pickup, 50.0, 50.0));
+    assert!(touches(&pickup, 50.0 + reach - 0.5, 50.0));
+    assert!(!touches(&pickup, 50.0 + reach + 0.5, 50.0));
+    assert!(!touches(&pickup, 50.0, 50.0 - reach - 0.5));
+}
+
+
+// Level Spawners -------------------------------------------------------------
+#[test]
+fn test_level_spawners() {
+
+    let level = Level::from_toml_string(r#"
+        pickups = [
+            { kind = "health", x = 10.0, y = 20.0 },
+            { kind = "shield", x = 30.0, y = 40.0, respawn = 5000 },
+            { kind = "unknown", x = 50.0, y = 60.0 }
+        ]
+    "#);
+
+    assert_eq!(level.pickups.len(), 2);
+
+    let health = &level.pickups[0];
+    assert_eq!(health.kind, PickupKind::Health);
+    assert_eq!((health.x, health.y), (10.0, 20.0));
+    assert_eq!(health.respawn_interval, PICKUP_RESPAWN_INTERVAL);
+
+    let shield = &level.pickups[1];
+    assert_eq!(shield.kind, PickupKind::Shield);
+    assert_eq!(shield.respawn_interval, 5000);
+
+}
+
@@ -0,0 +1,168 @@
+extern crate netsync;
+extern crate shared;
+
+use netsync::{ClientState, ServerState, NetworkProperty, NetworkState, SnapshotBuffer};
+use shared::collision::line_segment_distance_to_point;
+use shared::entity::{
+    PlayerData, PlayerInput, rewind_ticks,
+    ENTITY_STATE_DELAY, PLAYER_MAX_HP, PLAYER_RADIUS,
+    PLAYER_ERROR_SMOOTHING_TICKS, PLAYER_ERROR_SNAP_DISTANCE
+};
+
+
+// Helpers --------------------------------------------------------------------
+fn predict(client: &mut ClientState<PlayerData, PlayerInput>, tick: u16) -> f32 {
+    client.input(PlayerInput::new(tick, 0, 0.0, 0.0));
+    client.update_with(|state, _, input| {
+        if input.is_some() {
+            state.x += 1.0;
+        }
+    });
+    client.interpolate(1.0).x
+}
+
+fn local_client() -> ClientState<PlayerData, PlayerInput> {
+    let mut client = ClientState::new(30);
+    client.set_error_smoothing(PLAYER_ERROR_SMOOTHING_TICKS, PLAYER_ERROR_SNAP_DISTANCE);
+    client.set(PlayerData::new(0.0, 0.0, 0.0, PLAYER_MAX_HP));
+    for tick in 1..4 {
+        predict(&mut client, tick);
+    }
+    client
+}
+
+fn moving_target(ticks: usize) -> ServerState<PlayerData, PlayerInput> {
+    let mut target = ServerState::new(30);
+    target.set(PlayerData::new(0.0, 0.0, 0.0, PLAYER_MAX_HP));
+    for _ in 0..ticks {
+        target.update_with(|state, _, _| state.x += PLAYER_RADIUS);
+    }
+    target
+}
+
+fn visible_at(x: f32) -> PlayerData {
+    let mut data = PlayerData::new(x, 0.0, 0.0, PLAYER_MAX_HP);
+    data.visible = true;
+    data
+}
+
+/// Checks a shot from `x`, `y` through the aimed at position against the
+/// rewound target.
+fn hits(x: f32, y: f32, aim: &PlayerData, target: &PlayerData) -> bool {
+    let line = [x, y, aim.x * 2.0 - x, aim.y * 2.0 - y];
+    line_segment_distance_to_point(&line, target.x, target.y) <= PLAYER_RADIUS
+}
+
+
+// Prediction Error Smoothing -------------------------------------------------
+#[test]
+fn test_local_correction_decays() {
+
+    let mut client = local_client();
+
+    // The server state of the local player is never marked as visible
+    let confirmed = PlayerData::new(12.0, 0.0, 0.0, PLAYER_MAX_HP);
+    assert!(!confirmed.visible);
+    client.receive(&confirmed.to_bytes(), Some(2));
+
+    // Confirmed tick 2 at 12 plus the replayed inputs of ticks 3 and 4, the
+    // displayed state continues where the old prediction would have been
+    let mut offset = 14.0 - predict(&mut client, 4);
+    assert_eq!(offset, 10.0);
+
+    for tick in 5..(5 + PLAYER_ERROR_SMOOTHING_TICKS as u16) {
+        let remaining = (tick + 10) as f32 - predict(&mut client, tick);
+        assert!(remaining < offset, "error should decay every tick");
+        offset = remaining;
+    }
+
+    assert!(offset.abs() < 0.001, "error should be gone after the smoothing ticks");
+    assert_eq!(client.prediction_stats().corrections, 1);
+    assert_eq!(client.prediction_stats().snaps, 0);
+
+}
+
+#[test]
+fn test_dead_player_correction_is_not_smoothed() {
+
+    let mut client = local_client();
+    client.receive(&PlayerData::new(12.0, 0.0, 0.0, 0).to_bytes(), Some(2));
+    assert_eq!(predict(&mut client, 4), 14.0);
+    assert_eq!(predict(&mut client, 5), 15.0);
+
+}
+
+
+// Snapshot Interpolation -----------------------------------------------------
+#[test]
+fn test_interpolate_dropped_snapshot() {
+
+    let mut buffer = SnapshotBuffer::new(10, 20, 30);
+    buffer.push(1000, 0, visible_at(0.0));
+    buffer.push(1010, 1, visible_at(3.0));
+
+    // Snapshot 2 was lost in transit
+    buffer.push(1030, 3, visible_at(9.0));
+
+    assert!((buffer.sample(1035).unwrap().x - 4.5).abs() < 0.001);
+    assert!((buffer.sample(1040).unwrap().x - 6.0).abs() < 0.001, "should interpolate across the gap");
+    assert!((buffer.sample(1060).unwrap().x - 12.0).abs() < 0.001, "should extrapolate at the same speed");
+
+}
+
+#[test]
+fn test_interpolate_respawn() {
+
+    let mut buffer = SnapshotBuffer::new(10, 20, 30);
+    buffer.push(1000, 0, visible_at(0.0));
+    buffer.push(1010, 1, visible_at(500.0));
+
+    // Players are not dragged across the level after respawning
+    assert_eq!(buffer.sample(1025).unwrap().x, 500.0);
+
+}
+
+
+// Lag Compensation -----------------------------------------------------------
+#[test]
+fn test_rewind_ticks() {
+    assert_eq!(rewind_ticks(100, 97, true), 3 + ENTITY_STATE_DELAY);
+    assert_eq!(rewind_ticks(100, 97, false), 3);
+    assert_eq!(rewind_ticks(100, 100, false), 0);
+
+    // View ticks from the future are not rewound at all
+    assert_eq!(rewind_ticks(97, 100, false), 0);
+    assert_eq!(rewind_ticks(2, 65534, false), 4);
+}
+
+#[test]
+fn test_bot_hits_moving_target() {
+
+    let target = moving_target(20);
+
+    // Bots aim at the current server state and send the server tick as their
+    // view tick
+    let server_tick = 1000;
+    let aim = target.get_relative(0);
+    let rewound = target.get_relative(rewind_ticks(server_tick, server_tick, false));
+    assert!(hits(0.0, -100.0, &aim, &rewound));
+
+    // Rewinding by the state delay would check against an older position
+    let delayed = target.get_relative(rewind_ticks(server_tick, server_tick, true));
+    assert!(!hits(0.0, -100.0, &aim, &delayed));
+
+}
+
+#[test]
+fn test_client_hits_delayed_target() {
+
+    let target = moving_target(20);
+
+    // Clients aim at the state rendered with the state delay
+    let server_tick = 1000;
+    let aim = target.get_relative(ENTITY_STATE_DELAY);
+    let rewound = target.get_relative(rewind_ticks(server_tick, server_tick, true));
+    assert!(hits(0.0, -100.0, &aim, &rewound));
+    assert!(!hits(0.0, -100.0, &aim, &target.get_relative(0)));
+
+}
@@ -0,0 +1,244 @@
+extern crate cobalt;
+extern crate hexahydrate;
+extern crate netsync;
+extern crate shared;
+
+use cobalt::ConnectionID;
+use hexahydrate::Entity;
+use netsync::{ClientState, NetworkProperty, NetworkState};
+use shared::color::ColorName;
+use shared::entity::{ProjectileData, ProjectileEntity, ProjectileInput};
+use shared::level::Level;
+use shared::weapon::Weapon;
+
+
+// Statics --------------------------------------------------------------------
+const DT: f32 = 1.0 / 30.0;
+
+
+// Helpers --------------------------------------------------------------------
+type ClientProjectile = ProjectileEntity<ClientState<ProjectileData, ProjectileInput>>;
+
+fn open_level() -> Level {
+    Level::from_toml_string("")
+}
+
+fn wall_level() -> Level {
+    Level::from_toml_string(r#"
+        walls = [
+            { line = [22.0, -50.0, 22.0, 50.0] }
+        ]
+    "#)
+}
+
+fn rocket(y: f32) -> ProjectileData {
+    ProjectileData::new(Weapon::Rocket, ColorName::Red, 0.0, y, 0.0)
+}
+
+/// Runs the server side simulation of a projectile for the given ticks.
+fn simulate(level: &Level, mut data: ProjectileData, ticks: usize) -> ProjectileData {
+    for _ in 0..ticks {
+        ProjectileData::update(DT, &mut data, level);
+    }
+    data
+}
+
+/// Runs the client side prediction of a projectile for a single tick.
+fn predict(projectile: &mut ClientProjectile, level: &Level, tick: u16) -> ProjectileData {
+    projectile.state.input(ProjectileInput::new(tick, DT));
+    projectile.state.update_with(|state, _, input| {
+        ProjectileData::update(input.unwrap().dt, state, level);
+    });
+    projectile.state.interpolate(1.0)
+}
+
+fn predicted(level: &Level, fired_tick: u16, ticks: u16) -> ClientProjectile {
+    let mut projectile = ProjectileEntity::new(None, true, fired_tick, rocket(0.0));
+    for tick in fired_tick..fired_tick + ticks {
+        predict(&mut projectile, level, tick);
+    }
+    projectile
+}
+
+/// Rotations are quantized when sent over the network, so positions which
+/// were continued from a received state only match approximately.
+fn assert_position(data: &ProjectileData, x: f32, y: f32) {
+    assert!((data.x - x).abs() < 0.01, "x is {} instead of {}", data.x, x);
+    assert!((data.y - y).abs() < 0.01, "y is {} instead of {}", data.y, y);
+}
+
+
+// Simulation -----------------------------------------------------------------
+#[test]
+fn test_update() {
+    let data = simulate(&open_level(), rocket(0.0), 1);
+    assert_position(&data, 5.0, 0.0);
+    assert!((data.travelled - 5.0).abs() < 0.001);
+    assert_eq!(data.ticks, 1);
+    assert!(!data.impacted);
+}
+
+#[test]
+fn test_update_range() {
+
+    let range = Weapon::Rocket.stats().range;
+    let data = simulate(&open_level(), rocket(0.0), 35);
+    assert!(!data.impacted);
+
+    let data = simulate(&open_level(), data, 1);
+    assert!(data.impacted);
+    assert_position(&data, range, 0.0);
+
+    // Impacted projectiles no longer move
+    let data = simulate(&open_level(), data, 5);
+    assert_position(&data, range, 0.0);
+    assert_eq!(data.ticks, 36);
+
+}
+
+#[test]
+fn test_update_wall_impact() {
+    let data = simulate(&wall_level(), rocket(0.0), 5);
+    assert!(data.impacted);
+    assert!(data.x > 21.0 && data.x < 22.0);
+
+    let data = simulate(&wall_level(), data, 5);
+    assert!(data.x < 22.0);
+}
+
+
+// Serialization --------------------------------------------------------------
+#[test]
+fn test_data_bytes() {
+
+    let mut data = ProjectileData::new(Weapon::Rocket, ColorName::Blue, 12.5, -4.0, 1.0);
+    data.travelled = 30.0;
+    data.ticks = 6;
+
+    let decoded = ProjectileData::decode(&data.to_bytes()).unwrap();
+    assert_eq!(decoded.weapon, Weapon::Rocket);
+    assert_eq!(decoded.color, ColorName::Blue);
+    assert_position(&decoded, 12.5, -4.0);
+    assert!((decoded.r - 1.0).abs() < 0.001);
+    assert_eq!(decoded.travelled, 30.0);
+    assert_eq!(decoded.ticks, 6);
+    assert!(!decoded.impacted);
+
+}
+
+#[test]
+fn test_data_bytes_invalid() {
+
+    let mut bytes = rocket(0.0).to_bytes();
+    assert!(ProjectileData::decode(&bytes[..4]).is_none());
+
+    // Unknown weapon
+    bytes[0] = 9;
+    assert!(ProjectileData::decode(&bytes).is_none());
+
+}
+
+#[test]
+fn test_entity_from_bytes() {
+
+    let mut bytes = vec![1, 0x2C, 0x01];
+    bytes.append(&mut rocket(0.0).to_bytes());
+
+    let projectile = <ClientProjectile as Entity<ConnectionID>>::from_bytes(&bytes).unwrap();
+    assert!(projectile.local);
+    assert_eq!(projectile.fired_tick, 300);
+
+    bytes[3] = 9;
+    assert!(<ClientProjectile as Entity<ConnectionID>>::from_bytes(&bytes).is_none());
+    assert!(<ClientProjectile as Entity<ConnectionID>>::from_bytes(&bytes[..3]).is_none());
+
+}
+
+
+// Client Side Prediction -----------------------------------------------------
+#[test]
+fn test_prediction_matches_server() {
+
+    let level = open_level();
+    let mut projectile = predicted(&level, 10, 5);
+
+    // The server state after 3 steps confirms the prediction of tick 12, the
+    // remaining predicted steps are applied on top of it
+    let confirmed = simulate(&level, rocket(0.0), 3);
+    projectile.merge_bytes(None, &confirmed.to_bytes());
+
+    let data = predict(&mut projectile, &level, 15);
+    let expected = simulate(&level, rocket(0.0), 6);
+    assert_position(&data, expected.x, expected.y);
+    assert_eq!(data.ticks, 6);
+
+}
+
+#[test]
+fn test_prediction_is_corrected() {
+
+    let level = open_level();
+    let mut projectile = predicted(&level, 10, 5);
+
+    // The server fired from a different position
+    let confirmed = simulate(&level, rocket(4.0), 3);
+    projectile.merge_bytes(None, &confirmed.to_bytes());
+
+    let data = predict(&mut projectile, &level, 15);
+    let expected = simulate(&level, rocket(4.0), 6);
+    assert_position(&data, expected.x, expected.y);
+
+}
+
+#[test]
+fn test_prediction_wall_impact() {
+
+    // Predicted and confirmed projectiles stop at the same wall
+    let level = wall_level();
+    let mut projectile = predicted(&level, 10, 7);
+    assert!(projectile.state.interpolate(1.0).impacted);
+
+    let confirmed = simulate(&level, rocket(0.0), 5);
+    projectile.merge_bytes(None, &confirmed.to_bytes());
+
+    let data = predict(&mut projectile, &level, 17);
+    assert!(data.impacted);
+    assert_position(&data, confirmed.x, confirmed.y);
+
+}
+
+#[test]
+fn test_prediction_ignores_invalid_state() {
+
+    let level = open_level();
+    let mut projectile = predicted(&level, 10, 3);
+    projectile.merge_bytes(None, &[1, 2, 3]);
+
+    let data = predict(&mut projectile, &level, 13);
+    let expected = simulate(&level, rocket(0.0), 4);
+    assert_position(&data, expected.x, expected.y);
+
+}
+
+#[test]
+fn test_remote_projectile() {
+
+    let level = open_level();
+    let mut bytes = vec![0, 10, 0];
+    bytes.append(&mut rocket(0.0).to_bytes());
+
+    let mut projectile = <ClientProjectile as Entity<ConnectionID>>::from_bytes(&bytes).unwrap();
+    assert!(!projectile.local);
+
+    // Remote projectiles continue the simulation from the received state
+    let confirmed = simulate(&level, rocket(0.0), 3);
+    projectile.merge_bytes(None, &confirmed.to_bytes());
+    projectile.state.update_with(|state, _, _| {
+        ProjectileData::update(DT, state, &level);
+    });
+
+    let expected = simulate(&level, rocket(0.0), 4);
+    assert_position(&projectile.state.interpolate(1.0), expected.x, expected.y);
+
+}
+
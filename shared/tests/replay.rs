@@ -0,0 +1,186 @@
+extern crate cobalt;
+extern crate shared;
+
+use std::io::Cursor;
+
+use cobalt::ConnectionID;
+use shared::action::{Action, ActionVisibility};
+use shared::color::ColorName;
+use shared::entity::PlayerData;
+use shared::replay::{
+    ReplayRecorder, ReplayReader, ReplayError, ReplayEvent, ReplayVisibility,
+    REPLAY_VERSION
+};
+
+
+// Helpers --------------------------------------------------------------------
+const LEVEL: &'static str = "walls = []";
+
+fn record_match() -> Vec<u8> {
+
+    let mut recorder = ReplayRecorder::new(Vec::new(), LEVEL, 30).unwrap();
+
+    recorder.join(ConnectionID(1), ColorName::Red);
+    recorder.player(ConnectionID(1), ColorName::Red, PlayerData::new(10.0, 20.0, 0.5, 255));
+    recorder.end_frame(1000).unwrap();
+
+    recorder.join(ConnectionID(2), ColorName::Blue);
+    recorder.player(ConnectionID(1), ColorName::Red, PlayerData::new(11.0, 20.0, 0.5, 255));
+    recorder.player(ConnectionID(2), ColorName::Blue, PlayerData::new(50.0, 60.0, 1.0, 255));
+    recorder.action(
+        &ActionVisibility::Entity(PlayerData::new(50.0, 60.0, 1.0, 205), Some(ConnectionID(1))),
+        &Action::PlayerHit(6, 1, 50.0, 60.0)
+    );
+    recorder.action(
+        &ActionVisibility::WithinRange { aabb: [0.0, 1.0, 2.0, 3.0], r: 4.0 },
+        &Action::ProjectileImpact(1, 1.0, 2.0, 3.0)
+    );
+    recorder.end_frame(1033).unwrap();
+
+    recorder.leave(ConnectionID(2));
+    recorder.player(ConnectionID(1), ColorName::Red, PlayerData::new(12.0, 20.0, 0.5, 255));
+    recorder.end_frame(1066).unwrap();
+
+    recorder.finish().unwrap()
+
+}
+
+
+// Round Trip -----------------------------------------------------------------
+#[test]
+fn test_header() {
+    let reader = ReplayReader::new(Cursor::new(record_match())).unwrap();
+    assert_eq!(reader.header().updates_per_second, 30);
+    assert_eq!(reader.header().level, LEVEL);
+}
+
+#[test]
+fn test_frames() {
+
+    let reader = ReplayReader::new(Cursor::new(record_match())).unwrap();
+    let frames: Vec<_> = reader.map(|f| f.unwrap()).collect();
+    assert_eq!(frames.len(), 3);
+
+    assert_eq!(frames[0].tick, 0);
+    assert_eq!(frames[0].time, 1000);
+    assert_eq!(frames[0].players.len(), 1);
+    assert_eq!(frames[0].players[0].id, 1);
+    assert_eq!(frames[0].players[0].color, ColorName::Red.to_u8());
+    assert_eq!(frames[0].players[0].data.x, 10.0);
+    match frames[0].events[0] {
+        ReplayEvent::Join(1, color) => assert_eq!(color, ColorName::Red.to_u8()),
+        ref event => panic!("Unexpected event {:?}", event)
+    }
+
+    assert_eq!(frames[1].tick, 1);
+    assert_eq!(frames[1].players.len(), 2);
+    assert_eq!(frames[1].events.len(), 3);
+    match frames[1].events[1] {
+        ReplayEvent::Action(ReplayVisibility::Entity(ref data, Some(1)), Action::PlayerHit(6, 1, _, _)) => {
+            assert_eq!(data.hp, 205);
+        },
+        ref event => panic!("Unexpected event {:?}", event)
+    }
+    match frames[1].events[2] {
+        ReplayEvent::Action(ReplayVisibility::WithinRange(aabb, r), Action::ProjectileImpact(..)) => {
+            assert_eq!(aabb, [0.0, 1.0, 2.0, 3.0]);
+            assert_eq!(r, 4.0);
+        },
+        ref event => panic!("Unexpected event {:?}", event)
+    }
+
+    assert_eq!(frames[2].tick, 2);
+    match frames[2].events[0] {
+        ReplayEvent::Leave(2) => {},
+        ref event => panic!("Unexpected event {:?}", event)
+    }
+
+}
+
+#[test]
+fn test_seek_to_frame_offsets() {
+
+    let mut reader = ReplayReader::new(Cursor::new(record_match())).unwrap();
+    let mut offsets = Vec::new();
+    loop {
+        let offset = reader.offset();
+        if reader.next().is_none() {
+            break;
+        }
+        offsets.push(offset);
+    }
+    assert_eq!(offsets.len(), 3);
+
+    // Frames can be read again in any order without decoding the previous ones
+    reader.seek(offsets[2]).unwrap();
+    assert_eq!(reader.next().unwrap().unwrap().time, 1066);
+    assert!(reader.next().is_none());
+
+    reader.seek(offsets[1]).unwrap();
+    assert_eq!(reader.offset(), offsets[1]);
+    assert_eq!(reader.next().unwrap().unwrap().time, 1033);
+    assert_eq!(reader.offset(), offsets[2]);
+
+    reader.seek(offsets[0]).unwrap();
+    let times: Vec<u64> = reader.map(|f| f.unwrap().time).collect();
+    assert_eq!(times, vec![1000, 1033, 1066]);
+
+}
+
+#[test]
+fn test_empty_match() {
+    let bytes = ReplayRecorder::new(Vec::new(), LEVEL, 30).unwrap().finish().unwrap();
+    assert_eq!(ReplayReader::new(Cursor::new(bytes)).unwrap().count(), 0);
+}
+
+
+// Errors ---------------------------------------------------------------------
+#[test]
+fn test_invalid_magic() {
+    let mut bytes = record_match();
+    bytes[0] = b'X';
+    match ReplayReader::new(Cursor::new(bytes)) {
+        Err(ReplayError::InvalidMagic) => {},
+        _ => panic!("Expected invalid magic")
+    }
+}
+
+#[test]
+fn test_unsupported_version() {
+    let mut bytes = record_match();
+    bytes[4] = (REPLAY_VERSION + 1) as u8;
+    match ReplayReader::new(Cursor::new(bytes)) {
+        Err(ReplayError::UnsupportedVersion(version)) => assert_eq!(version, REPLAY_VERSION + 1),
+        _ => panic!("Expected unsupported version")
+    }
+}
+
+#[test]
+fn test_truncated_frame() {
+
+    let mut bytes = record_match();
+    let len = bytes.len();
+    bytes.truncate(len - 3);
+
+    let frames: Vec<_> = ReplayReader::new(Cursor::new(bytes)).unwrap().collect();
+    assert_eq!(frames.len(), 3);
+    assert!(frames[0].is_ok());
+    assert!(frames[1].is_ok());
+    assert!(frames[2].is_err());
+
+}
+
+#[test]
+fn test_chunk_too_large() {
+
+    let mut bytes = ReplayRecorder::new(Vec::new(), LEVEL, 30).unwrap().finish().unwrap();
+    bytes.extend_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+
+    let mut reader = ReplayReader::new(Cursor::new(bytes)).unwrap();
+    match reader.next() {
+        Some(Err(ReplayError::ChunkTooLarge(size))) => assert_eq!(size, 0xffff_ffff),
+        _ => panic!("Expected chunk too large")
+    }
+
+}
+
@@ -0,0 +1,183 @@
+extern crate shared;
+
+use std::fs::File;
+use std::io::Read;
+
+use shared::level::{
+    Level,
+    SolidError, SolidWinding,
+    clean_solid, validate_solid, solid_area, solid_winding, triangulate_solid
+};
+
+
+// Helpers --------------------------------------------------------------------
+fn triangles_area(triangles: &[f32]) -> f32 {
+    triangles.chunks(6).fold(0.0, |area, t| {
+        area + ((t[2] - t[0]) * (t[5] - t[1]) - (t[3] - t[1]) * (t[4] - t[0])).abs() * 0.5
+    })
+}
+
+fn l_shape() -> Vec<[f32; 2]> {
+    vec![
+        [0.0, 0.0], [20.0, 0.0], [20.0, 10.0],
+        [10.0, 10.0], [10.0, 20.0], [0.0, 20.0]
+    ]
+}
+
+
+// Validation -----------------------------------------------------------------
+#[test]
+fn test_clean_duplicate_points() {
+    let points = vec![
+        [0.0, 0.0], [0.0, 0.0], [10.0, 0.0], [10.0, 10.0],
+        [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]
+    ];
+    assert_eq!(clean_solid(&points), vec![
+        [0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]
+    ]);
+}
+
+#[test]
+fn test_clean_collinear_points() {
+    let points = vec![
+        [0.0, 0.0], [5.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 5.0]
+    ];
+    assert_eq!(clean_solid(&points), vec![
+        [0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]
+    ]);
+}
+
+#[test]
+fn test_validate_simple() {
+    assert_eq!(validate_solid(&l_shape()), Ok(()));
+}
+
+#[test]
+fn test_validate_too_few_points() {
+    assert_eq!(validate_solid(&[[0.0, 0.0], [10.0, 0.0], [10.0, 0.0]]), Err(SolidError::TooFewPoints));
+}
+
+#[test]
+fn test_validate_collinear() {
+    assert_eq!(validate_solid(&[[0.0, 0.0], [10.0, 0.0], [20.0, 0.0]]), Err(SolidError::TooFewPoints));
+    assert_eq!(validate_solid(&[[0.0, 0.0], [10.0, 0.0], [0.0, 0.0], [10.0, 0.0]]), Err(SolidError::TooFewPoints));
+}
+
+#[test]
+fn test_validate_self_intersection() {
+    let bow_tie = vec![[0.0, 0.0], [10.0, 10.0], [10.0, 0.0], [0.0, 10.0]];
+    assert_eq!(validate_solid(&bow_tie), Err(SolidError::SelfIntersection(0, 2)));
+}
+
+#[test]
+fn test_validate_touching_edges() {
+    let touching = vec![
+        [0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [5.0, 0.0], [0.0, 10.0]
+    ];
+    assert!(validate_solid(&touching).is_err());
+}
+
+#[test]
+fn test_winding() {
+    let mut points = l_shape();
+    assert_eq!(solid_winding(&points), SolidWinding::CounterClockwise);
+    assert_eq!(solid_area(&points), 300.0);
+
+    points.reverse();
+    assert_eq!(solid_winding(&points), SolidWinding::Clockwise);
+    assert_eq!(solid_area(&points), -300.0);
+}
+
+
+// Triangulation --------------------------------------------------------------
+#[test]
+fn test_triangulate_triangle() {
+    assert_eq!(triangulate_solid(&[[0.0, 0.0], [10.0, 0.0], [0.0, 10.0]]), vec![
+        0.0, 0.0, 10.0, 0.0, 0.0, 10.0
+    ]);
+}
+
+#[test]
+fn test_triangulate_degenerate() {
+    assert!(triangulate_solid(&[]).is_empty());
+    assert!(triangulate_solid(&[[0.0, 0.0], [10.0, 0.0]]).is_empty());
+    assert!(triangulate_solid(&[[0.0, 0.0], [10.0, 0.0], [20.0, 0.0]]).is_empty());
+}
+
+#[test]
+fn test_triangulate_concave() {
+    let triangles = triangulate_solid(&l_shape());
+    assert_eq!(triangles.len(), 4 * 6);
+    assert_eq!(triangles_area(&triangles), 300.0);
+}
+
+#[test]
+fn test_triangulate_clockwise() {
+    let mut points = l_shape();
+    points.reverse();
+    let triangles = triangulate_solid(&points);
+    assert_eq!(triangles.len(), 4 * 6);
+    assert_eq!(triangles_area(&triangles), 300.0);
+}
+
+#[test]
+fn test_triangulate_duplicate_points() {
+    let points = vec![
+        [0.0, 0.0], [20.0, 0.0], [20.0, 0.0], [20.0, 10.0], [10.0, 10.0],
+        [10.0, 10.0], [10.0, 15.0], [10.0, 20.0], [0.0, 20.0], [0.0, 0.0]
+    ];
+    let triangles = triangulate_solid(&points);
+    assert_eq!(triangles.len(), 4 * 6);
+    assert_eq!(triangles_area(&triangles), 300.0);
+}
+
+#[test]
+fn test_triangulate_self_intersecting_terminates() {
+    let bow_tie = vec![[0.0, 0.0], [10.0, 10.0], [10.0, 0.0], [0.0, 10.0]];
+    assert_eq!(triangulate_solid(&bow_tie).len(), 2 * 6);
+}
+
+
+// Loading --------------------------------------------------------------------
+#[test]
+fn test_load_skips_invalid_solids() {
+    let level = Level::from_toml_string(r#"
+        walls = [{ line = [0.0, 0.0, 100.0, 0.0] }]
+        solids = [
+            [0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0],
+            [20.0, 0.0, 30.0, 10.0, 30.0, 0.0, 20.0, 10.0],
+            [40.0, 0.0, 50.0, 0.0, 60.0, 0.0],
+            [70.0, 0.0, 80.0, 0.0, 80.0, 0.0, 80.0, 10.0, 70.0, 10.0]
+        ]
+    "#);
+    assert_eq!(level.solids, vec![
+        vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]],
+        vec![[70.0, 0.0], [80.0, 0.0], [80.0, 10.0], [70.0, 10.0]]
+    ]);
+    assert_eq!(level.invalid_solids(), &[
+        (1, SolidError::SelfIntersection(0, 2)),
+        (2, SolidError::TooFewPoints)
+    ]);
+}
+
+#[test]
+fn test_map_solids() {
+
+    let mut toml = String::new();
+    File::open("../editor/map.toml").unwrap().read_to_string(&mut toml).unwrap();
+
+    let level = Level::from_toml_string(&toml);
+    assert!(!level.solids.is_empty());
+
+    for solid in &level.solids {
+        assert_eq!(validate_solid(solid), Ok(()));
+
+        let triangles = triangulate_solid(solid);
+        assert_eq!(triangles.len(), (clean_solid(solid).len() - 2) * 6);
+
+        let (expected, actual) = (solid_area(solid).abs(), triangles_area(&triangles));
+        assert!((expected - actual).abs() < 0.01, "{} != {}", expected, actual);
+    }
+
+}
+
@@ -0,0 +1,99 @@
+extern crate shared;
+
+use shared::stats::{NetworkStats, TrafficStats, TrafficKind, NETWORK_STATS_RTT_HISTORY};
+
+
+// Traffic Categories ---------------------------------------------------------
+#[test]
+fn test_server_entity_packet() {
+
+    let mut stats = TrafficStats::default();
+
+    // Create with two bytes of entity data, an update with three and a forget
+    stats.record_server_packet(&[0, 1, 2, 7, 10, 11, 3, 2, 3, 20, 21, 22, 5, 4]);
+
+    assert_eq!(stats.total.packets, 1);
+    assert_eq!(stats.total.bytes, 14);
+    assert_eq!(stats.kind(TrafficKind::Create).bytes, 8);
+    assert_eq!(stats.kind(TrafficKind::Create).packets, 1);
+    assert_eq!(stats.kind(TrafficKind::Update).bytes, 6);
+    assert_eq!(stats.kind(TrafficKind::Update).packets, 1);
+    assert_eq!(stats.kind(TrafficKind::Action).packets, 0);
+
+}
+
+#[test]
+fn test_client_entity_packet() {
+
+    let mut stats = TrafficStats::default();
+    stats.record_client_packet(&[1, 0, 2, 1, 3, 1, 2, 5, 6]);
+
+    assert_eq!(stats.kind(TrafficKind::Create).bytes, 2);
+    assert_eq!(stats.kind(TrafficKind::Update).bytes, 2);
+    assert_eq!(stats.kind(TrafficKind::Input).bytes, 5);
+    assert_eq!(stats.kind(TrafficKind::Input).packets, 1);
+
+}
+
+#[test]
+fn test_actions() {
+
+    let mut stats = TrafficStats::default();
+    stats.record_server_packet(&[6, 0, 0, 0, 1]);
+    stats.record_client_packet(&[0, 0, 0, 0]);
+
+    assert_eq!(stats.total.packets, 2);
+    assert_eq!(stats.kind(TrafficKind::Action).packets, 2);
+    assert_eq!(stats.kind(TrafficKind::Action).bytes, 9);
+
+}
+
+#[test]
+fn test_truncated_entity_packet() {
+
+    let mut stats = TrafficStats::default();
+    stats.record_server_packet(&[3, 1, 10, 1, 2]);
+    assert_eq!(stats.total.bytes, 5);
+    assert_eq!(stats.kind(TrafficKind::Update).bytes, 5);
+
+}
+
+
+// Connection Statistics ------------------------------------------------------
+#[test]
+fn test_rates() {
+
+    let mut stats = NetworkStats::new(1000);
+    stats.sent.record(TrafficKind::Action, 100);
+    stats.received.record_server_packet(&[3, 1, 2, 0, 0]);
+
+    assert!(!stats.update(1500));
+    assert_eq!(stats.sent.rate(), 0);
+
+    stats.sent.record(TrafficKind::Action, 100);
+    assert!(stats.update(3000), "should complete the window");
+    assert_eq!(stats.sent.rate(), 100);
+    assert_eq!(stats.sent.rates[TrafficKind::Action as usize], 100);
+    assert_eq!(stats.received.rates[TrafficKind::Update as usize], 2);
+
+    assert!(stats.update(4000));
+    assert_eq!(stats.sent.rate(), 0);
+    assert_eq!(stats.sent.total.bytes, 200);
+
+}
+
+#[test]
+fn test_rtt_history() {
+
+    let mut stats = NetworkStats::new(0);
+    assert_eq!(stats.rtt(), None);
+
+    for rtt in 0..NETWORK_STATS_RTT_HISTORY as u32 + 10 {
+        stats.record_rtt(rtt);
+    }
+
+    assert_eq!(stats.rtt(), Some(NETWORK_STATS_RTT_HISTORY as u32 + 9));
+    assert_eq!(stats.rtt_history().len(), NETWORK_STATS_RTT_HISTORY);
+    assert_eq!(stats.rtt_history()[0], 10);
+
+}
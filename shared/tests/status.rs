@@ -0,0 +1,47 @@
+extern crate shared;
+
+use shared::status::{ServerStatus, StatusError};
+
+
+// Helpers --------------------------------------------------------------------
+fn status() -> ServerStatus {
+    ServerStatus {
+        name: "Test \"Server\"".to_string(),
+        map: "map".to_string(),
+        mode: "deathmatch".to_string(),
+        version: "0.1.0".to_string(),
+        players: 2,
+        bots: 3,
+        max_players: 8
+    }
+}
+
+
+// Encoding -------------------------------------------------------------------
+#[test]
+fn test_json_round_trip() {
+    let json = status().to_json();
+    assert!(json.contains("\"max_players\":8"));
+    assert_eq!(ServerStatus::from_json(&json).unwrap(), status());
+}
+
+#[test]
+fn test_invalid_json() {
+    match ServerStatus::from_json("<html></html>") {
+        Err(StatusError::InvalidResponse(_)) => {},
+        _ => panic!("Expected an invalid response error")
+    }
+    match ServerStatus::from_json("{\"name\":\"Server\"}") {
+        Err(StatusError::InvalidResponse(_)) => {},
+        _ => panic!("Expected an invalid response error for missing fields")
+    }
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(
+        status().to_string(),
+        "Test \"Server\" - deathmatch on map (5/8 players, 3 bots) v0.1.0"
+    );
+}
+
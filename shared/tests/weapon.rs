@@ -0,0 +1,111 @@
+extern crate netsync;
+extern crate shared;
+
+use netsync::ServerState;
+use shared::color::ColorName;
+use shared::entity::{PlayerData, PlayerInput, PlayerEntity, PLAYER_MAX_HP};
+use shared::weapon::Weapon;
+
+
+// Helpers --------------------------------------------------------------------
+fn weapons() -> Vec<Weapon> {
+    vec![Weapon::LaserBeam, Weapon::Rocket, Weapon::Shotgun]
+}
+
+fn player() -> PlayerEntity<ServerState<PlayerData, PlayerInput>> {
+    PlayerEntity::new(None, false, ColorName::Red, PlayerData::new(0.0, 0.0, 0.0, PLAYER_MAX_HP))
+}
+
+fn fire(player: &mut PlayerEntity<ServerState<PlayerData, PlayerInput>>, weapon: Weapon, t: u64) -> bool {
+    player.fire_weapon(weapon.stats().fire_interval, t)
+}
+
+
+// Weapon Ids -----------------------------------------------------------------
+#[test]
+fn test_weapon_ids() {
+    for weapon in weapons() {
+        assert_eq!(Weapon::from_u8(weapon.to_u8()), Some(weapon));
+    }
+    assert_eq!(Weapon::from_u8(3), None);
+    assert_eq!(Weapon::from_u8(255), None);
+}
+
+
+// Weapon Statistics ----------------------------------------------------------
+#[test]
+fn test_hitscan_and_projectile_weapons() {
+    assert!(Weapon::LaserBeam.stats().projectile_speed.is_none());
+    assert!(Weapon::Shotgun.stats().projectile_speed.is_none());
+    assert!(Weapon::Rocket.stats().projectile_speed.is_some());
+    assert!(Weapon::Rocket.stats().splash_radius > 0.0);
+}
+
+#[test]
+fn test_shotgun_is_short_ranged() {
+    let shotgun = Weapon::Shotgun.stats();
+    assert!(shotgun.range < Weapon::LaserBeam.stats().range);
+    assert!(shotgun.pellets > 1);
+    assert!(shotgun.spread > 0.0);
+}
+
+
+// Pellet Spread --------------------------------------------------------------
+#[test]
+fn test_single_pellet() {
+    assert_eq!(Weapon::LaserBeam.pellet_angles(1.5), vec![1.5]);
+    assert_eq!(Weapon::Rocket.pellet_angles(1.5), vec![1.5]);
+}
+
+#[test]
+fn test_pellet_spread() {
+
+    let stats = Weapon::Shotgun.stats();
+    let angles = Weapon::Shotgun.pellet_angles(1.0);
+    assert_eq!(angles.len(), stats.pellets as usize);
+
+    // Evenly spread across the weapon's spread and centered on the aim
+    assert!((angles[0] - (1.0 - stats.spread * 0.5)).abs() < 0.0001);
+    assert!((angles[angles.len() - 1] - (1.0 + stats.spread * 0.5)).abs() < 0.0001);
+    assert!((angles[angles.len() / 2] - 1.0).abs() < 0.0001);
+
+    let step = angles[1] - angles[0];
+    for pair in angles.windows(2) {
+        assert!((pair[1] - pair[0] - step).abs() < 0.0001);
+    }
+
+}
+
+
+// Weapon Switching -----------------------------------------------------------
+#[test]
+fn test_fire_interval() {
+    let mut player = player();
+    assert!(fire(&mut player, Weapon::LaserBeam, 1000));
+    assert!(!fire(&mut player, Weapon::LaserBeam, 1299));
+    assert!(fire(&mut player, Weapon::LaserBeam, 1300));
+}
+
+#[test]
+fn test_switch_to_faster_weapon() {
+
+    // The interval of the newly selected weapon applies from the last shot
+    let mut player = player();
+    assert!(fire(&mut player, Weapon::Rocket, 1000));
+    assert!(!fire(&mut player, Weapon::Rocket, 1300));
+    assert!(fire(&mut player, Weapon::LaserBeam, 1300));
+
+}
+
+#[test]
+fn test_switch_to_slower_weapon() {
+
+    // Switching weapons does not allow to fire early
+    let mut player = player();
+    assert!(fire(&mut player, Weapon::LaserBeam, 1000));
+    assert!(!fire(&mut player, Weapon::Shotgun, 1300));
+    assert!(!fire(&mut player, Weapon::Rocket, 1800));
+    assert!(fire(&mut player, Weapon::Shotgun, 1800));
+
+}
+